@@ -24,6 +24,9 @@ enum Commands {
         /// Output directory path (default: ./typerust_output)
         #[arg(short, long)]
         output: Option<PathBuf>,
+        /// Emit console.* calls as `tracing` events and initialise a subscriber
+        #[arg(long)]
+        tracing: bool,
     },
 }
 
@@ -38,13 +41,22 @@ async fn main() -> Result<()> {
         Commands::Check { path } => {
             ox_orchestrator::check(FilePath::from(path))?;
         }
-        Commands::Build { path, output } => {
+        Commands::Build {
+            path,
+            output,
+            tracing,
+        } => {
+            let options = ox_orchestrator::Options {
+                console_tracing: tracing,
+                ..Default::default()
+            };
             if path.is_dir() {
                 let output_dir = output.unwrap_or_else(|| PathBuf::from("./typerust_output"));
-                ox_orchestrator::build_project(path, output_dir)?;
+                ox_orchestrator::build_project_with_options(path, output_dir, &options)?;
                 println!("✅ Project built successfully!");
             } else {
-                let output_code = ox_orchestrator::build(FilePath::from(path))?;
+                let output_code =
+                    ox_orchestrator::build_with_options(FilePath::from(path), &options)?;
                 println!("{}", output_code);
             }
         }
@@ -1,16 +1,42 @@
 use quote::{format_ident, quote};
 use swc_ecma_ast::{
-    AssignTarget, ClassDecl, ClassMember, Constructor, Expr, ExprStmt, Lit, Pat, Stmt,
+    AssignTarget, Class, ClassDecl, ClassMember, Constructor, Decl, Expr, ExprStmt, Lit,
+    ParamOrTsParamProp, Pat, Program, Stmt, TsParamProp, TsParamPropParam, TsType,
 };
 
-use super::func::{convert_expr_pub, convert_stmt_pub, to_snake_case};
+use super::func::{
+    convert_expr_pub, convert_param, convert_stmt_pub, declare_param, is_reassigned, param_kinds,
+    reassigned_params, to_snake_case, ConvertedParam,
+};
 use super::interface::RustGenerator;
+use super::scope;
 use super::type_mapper::{is_optional_type, map_ts_type};
 
 impl RustGenerator {
     pub fn process_class_decl(&mut self, n: &ClassDecl) {
+        if n.declare || self.is_ambient {
+            crate::diagnostics::warn(format!(
+                "ambient class `{}` was skipped; provide it in Rust",
+                n.ident.sym
+            ));
+            self.code.push_str(&format!(
+                "// declare class {}: ambient declaration skipped\n",
+                n.ident.sym
+            ));
+            return;
+        }
+
         let class_name = n.ident.sym.to_string();
+        if super::error::lookup(&class_name).is_some() {
+            self.code.push_str(&format!(
+                "// `{}` is a variant of `crate::AppError`\n",
+                class_name
+            ));
+            return;
+        }
         let struct_name = format_ident!("{}", class_name);
+        let is_emitter = super::events::is_emitter(&class_name);
+        let outer_class = super::scope::set_current_class(Some(class_name.clone()));
 
         // Extract generic params early
         let mut generic_params = std::collections::HashSet::new();
@@ -104,6 +130,14 @@ impl RustGenerator {
             quote! {}
         };
 
+        // `class OrderService extends EventEmitter`
+        if is_emitter {
+            fields.push(super::events::bus_field(&class_name));
+            let events = super::events::event_items(&class_name, &vis);
+            self.code.push_str(&events.to_string());
+            self.code.push('\n');
+        }
+
         let (generics_struct_decl, generics_impl_decl, generics_use) = if let Some(type_params) =
             &n.class.type_params
         {
@@ -335,6 +369,7 @@ impl RustGenerator {
 
         self.code.push_str(&impl_block.to_string());
         self.code.push('\n');
+        super::scope::set_current_class(outer_class);
     }
 
     fn convert_prop(
@@ -399,7 +434,7 @@ impl RustGenerator {
 
     fn convert_constructor(
         &self,
-        _struct_name: &proc_macro2::Ident,
+        struct_name: &proc_macro2::Ident,
         constructor: &Constructor,
         class_fields: &[(String, bool)],
         has_generics: bool,
@@ -407,6 +442,7 @@ impl RustGenerator {
         dependency_fields: &std::collections::HashSet<String>,
     ) -> proc_macro2::TokenStream {
         let mut params = Vec::new();
+        let mut prelude = Vec::new();
         let mut field_inits = Vec::new();
         let mut initialized_fields = std::collections::HashSet::new();
         let mut dependency_params: std::collections::HashSet<String> =
@@ -461,8 +497,8 @@ impl RustGenerator {
                         initialized_fields.insert(param_name_str);
                     }
                 }
-                swc_ecma_ast::ParamOrTsParamProp::Param(pat_param) => {
-                    if let Pat::Ident(ident) = &pat_param.pat {
+                swc_ecma_ast::ParamOrTsParamProp::Param(pat_param) => match &pat_param.pat {
+                    Pat::Ident(ident) if !ident.id.optional => {
                         let param_name = format_ident!("{}", ident.sym.to_string());
                         let mut param_type = map_ts_type(ident.type_ann.as_ref());
 
@@ -502,7 +538,16 @@ impl RustGenerator {
 
                         params.push(quote! { #param_name: #param_type });
                     }
-                }
+                    // Optional, default and rest parameters are lowered as for functions
+                    pat => {
+                        if let Some(converted) = convert_param(pat) {
+                            declare_param(pat);
+                            prelude.extend(converted.prelude());
+                            let ConvertedParam { name, ty, .. } = converted;
+                            params.push(quote! { #name: #ty });
+                        }
+                    }
+                },
             }
         }
 
@@ -583,6 +628,12 @@ impl RustGenerator {
             field_inits.push(quote! { _marker: std::marker::PhantomData });
         }
 
+        // An emitter starts without listeners
+        let is_emitter = super::events::is_emitter(&struct_name.to_string());
+        if is_emitter {
+            field_inits.push(quote! { events: Default::default() });
+        }
+
         if !field_inits.is_empty() {
             // Generate new_di (Dependency Injection constructor)
             // It takes only dependencies and defaults primitives
@@ -698,6 +749,9 @@ impl RustGenerator {
             if has_generics {
                 di_field_inits.push(quote! { _marker: std::marker::PhantomData });
             }
+            if is_emitter {
+                di_field_inits.push(quote! { events: Default::default() });
+            }
 
             for (name, _) in class_fields {
                 if !di_initialized_fields.contains(name) {
@@ -708,6 +762,7 @@ impl RustGenerator {
 
             quote! {
                 pub fn new(#(#params),*) -> Self {
+                    #(#prelude)*
                     Self {
                         #(#field_inits),*
                     }
@@ -783,32 +838,43 @@ impl RustGenerator {
             params.push(quote! { &self });
         }
 
+        super::scope::reset();
+        let mut prelude = Vec::new();
+        let reassigned = reassigned_params(method.function.body.as_ref());
         for param in &method.function.params {
-            if let Pat::Ident(ident) = &param.pat {
-                let param_name = format_ident!("{}", to_snake_case(ident.sym.as_ref()));
-                let param_type = map_ts_type(ident.type_ann.as_ref());
-
-                // Check for @Body decorator on parameters
-                let mut is_body = false;
-
-                // Correctly check decorators on the Param node
-                for decorator in &param.decorators {
-                    if let Expr::Call(call) = &*decorator.expr {
-                        if let swc_ecma_ast::Callee::Expr(expr) = &call.callee {
-                            if let Expr::Ident(ident) = &**expr {
-                                if ident.sym == "Body" {
-                                    is_body = true;
-                                }
+            let Some(converted) = convert_param(&param.pat) else {
+                continue;
+            };
+            declare_param(&param.pat);
+            prelude.extend(converted.prelude());
+            let ConvertedParam {
+                name: param_name,
+                ty: param_type,
+                ..
+            } = converted;
+
+            // Check for @Body decorator on parameters
+            let mut is_body = false;
+
+            // Correctly check decorators on the Param node
+            for decorator in &param.decorators {
+                if let Expr::Call(call) = &*decorator.expr {
+                    if let swc_ecma_ast::Callee::Expr(expr) = &call.callee {
+                        if let Expr::Ident(ident) = &**expr {
+                            if ident.sym == "Body" {
+                                is_body = true;
                             }
                         }
                     }
                 }
+            }
 
-                if is_body {
-                    params.push(quote! { axum::Json(#param_name): axum::Json<#param_type> });
-                } else {
-                    params.push(quote! { #param_name: #param_type });
-                }
+            if is_body {
+                params.push(quote! { axum::Json(#param_name): axum::Json<#param_type> });
+            } else if is_reassigned(&param.pat, &reassigned) {
+                params.push(quote! { mut #param_name: #param_type });
+            } else {
+                params.push(quote! { #param_name: #param_type });
             }
         }
 
@@ -838,9 +904,20 @@ impl RustGenerator {
 
         // Convert body
         let mut body_stmts = Vec::new();
+        let was_fallible = super::scope::set_fallible(is_handler || method.function.is_async);
+        let outer_return_type = super::scope::set_return_type(
+            method
+                .function
+                .return_type
+                .as_ref()
+                .map(|type_ann| (*type_ann.type_ann).clone()),
+        );
         if let Some(body) = &method.function.body {
             // Define return handler
             let return_handler = |ret: &swc_ecma_ast::ReturnStmt| -> proc_macro2::TokenStream {
+                if method.function.is_async && !is_handler {
+                    return super::func::async_return(ret);
+                }
                 if let Some(arg) = &ret.arg {
                     let expr = convert_expr_pub(arg);
 
@@ -854,9 +931,6 @@ impl RustGenerator {
                         } else {
                             quote! { return Ok(#expr.into()); }
                         }
-                    } else if method.function.is_async {
-                        // For async methods, wrap in Ok
-                        quote! { return Ok(#expr); }
                     } else {
                         quote! { return #expr; }
                     }
@@ -874,6 +948,9 @@ impl RustGenerator {
             }
         }
 
+        super::scope::set_fallible(was_fallible);
+        super::scope::set_return_type(outer_return_type);
+
         let fn_keyword = if is_handler || method.function.is_async {
             quote! { async fn }
         } else {
@@ -901,6 +978,7 @@ impl RustGenerator {
         let tokens = quote! {
             #doc_comment
             pub #fn_keyword #method_name(#(#params),*) -> #return_type {
+                #(#prelude)*
                 #(#body_stmts)*
             }
         };
@@ -910,3 +988,85 @@ impl RustGenerator {
         (tokens, route_info)
     }
 }
+
+/// Records the typed members of every top-level class, so method calls on
+/// `this.items` or on a call's result can be routed by the receiver's type.
+pub fn collect_class_members(program: &Program) {
+    for decl in scope::top_level_decls(program) {
+        let Decl::Class(decl) = decl else {
+            continue;
+        };
+        let mut members = scope::ClassMembers {
+            fields: class_fields(&decl.class).into_iter().collect(),
+            ..Default::default()
+        };
+        for member in &decl.class.body {
+            match member {
+                ClassMember::Method(method) => {
+                    let Some(key) = method.key.as_ident() else {
+                        continue;
+                    };
+                    let name = key.sym.to_string();
+                    let return_type = method
+                        .function
+                        .return_type
+                        .as_ref()
+                        .map(|ann| (*ann.type_ann).clone());
+                    let kinds = param_kinds(method.function.params.iter().map(|p| &p.pat));
+                    members.methods.insert(name.clone(), return_type);
+                    members.method_params.insert(name, kinds);
+                }
+                // Parameter properties are always passed
+                ClassMember::Constructor(constructor) => {
+                    members.constructor_params = constructor
+                        .params
+                        .iter()
+                        .filter_map(|param| match param {
+                            ParamOrTsParamProp::Param(param) => Some(param_kinds([&param.pat])[0]),
+                            ParamOrTsParamProp::TsParamProp(TsParamProp {
+                                param: TsParamPropParam::Ident(_),
+                                ..
+                            }) => Some(scope::ParamKind::Required),
+                            ParamOrTsParamProp::TsParamProp(_) => None,
+                        })
+                        .collect();
+                }
+                _ => {}
+            }
+        }
+        scope::declare_class(&decl.ident.sym, members);
+    }
+}
+
+/// Annotated class properties and parameter properties
+pub fn class_fields(class: &Class) -> Vec<(String, TsType)> {
+    let mut fields = Vec::new();
+    for member in &class.body {
+        match member {
+            ClassMember::ClassProp(prop) => {
+                if let (Some(key), Some(type_ann)) = (prop.key.as_ident(), &prop.type_ann) {
+                    let ts_type = match prop.is_optional {
+                        true => scope::nullable(&type_ann.type_ann),
+                        false => (*type_ann.type_ann).clone(),
+                    };
+                    fields.push((key.sym.to_string(), ts_type));
+                }
+            }
+            ClassMember::Constructor(constructor) => {
+                for param in &constructor.params {
+                    if let ParamOrTsParamProp::TsParamProp(TsParamProp {
+                        param: TsParamPropParam::Ident(ident),
+                        ..
+                    }) = param
+                    {
+                        if let Some(type_ann) = &ident.type_ann {
+                            fields.push((ident.sym.to_string(), (*type_ann.type_ann).clone()));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    fields
+}
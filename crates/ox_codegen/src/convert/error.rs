@@ -0,0 +1,1028 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+use super::func::{convert_expr, convert_stmt, convert_stmt_recursive, to_snake_case};
+use super::module::infer_expr_type;
+use super::scope;
+use super::type_mapper::map_ts_type;
+use crate::diagnostics;
+
+// Classes extending `Error`, directly or through each other, are not emitted as
+// structs: each one is a variant of the generated `crate::AppError` enum, with
+// `message` and the class fields, and `new NotFoundError(id)` calls the
+// `AppError::not_found_error` constructor emitted next to it in `error.rs`.
+
+/// A user-defined error class, as a variant of `crate::AppError`
+#[derive(Debug, Clone)]
+pub struct ErrorClass {
+    pub name: String,
+    /// Fields besides `message` as (Rust name, Rust type), inherited ones first
+    pub fields: Vec<(String, String)>,
+    /// Parameters of `new Name(...)` as (Rust name, Rust type)
+    pub params: Vec<(String, String)>,
+    /// Statements of the `AppError` constructor, binding `message` and the fields
+    pub body: String,
+    pub status: ErrorStatus,
+    /// The error class this one extends, if it is not a built-in
+    pub parent: Option<String>,
+}
+
+/// The HTTP status an error is answered with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorStatus {
+    Code(u16),
+    /// Read from a numeric `status` or `statusCode` field
+    Field(String),
+}
+
+thread_local! {
+    static ERROR_CLASSES: RefCell<HashMap<String, ErrorClass>> = RefCell::new(HashMap::new());
+}
+
+/// The JS built-in errors, which all become `AppError::Error(message)`
+const BUILTIN_ERRORS: &[&str] = &[
+    "Error",
+    "TypeError",
+    "RangeError",
+    "SyntaxError",
+    "ReferenceError",
+    "EvalError",
+    "URIError",
+];
+
+fn is_builtin(name: &str) -> bool {
+    BUILTIN_ERRORS.contains(&name)
+}
+
+/// The user-defined error class `name`, if any
+pub fn lookup(name: &str) -> Option<ErrorClass> {
+    ERROR_CLASSES.with(|e| e.borrow().get(name).cloned())
+}
+
+/// Whether `name` is a type represented by `crate::AppError`
+pub fn is_error_type(name: &str) -> bool {
+    (is_builtin(name) && scope::lookup_struct(name).is_none()) || lookup(name).is_some()
+}
+
+/// Use the error classes of the whole project, plus those of `program` itself
+/// so single files can be generated on their own.
+pub fn register(program: &Program, project: &[ErrorClass]) {
+    let own = collect_error_classes(std::slice::from_ref(program));
+    ERROR_CLASSES.with(|e| {
+        let mut classes = e.borrow_mut();
+        classes.clear();
+        for class in project.iter().chain(&own) {
+            classes.insert(class.name.clone(), class.clone());
+        }
+    });
+}
+
+/// The class a class declaration extends, if it is a plain name
+fn parent_name(class: &Class) -> Option<String> {
+    class
+        .super_class
+        .as_deref()
+        .and_then(|parent| parent.as_ident())
+        .map(|parent| parent.sym.to_string())
+}
+
+/// Every class in `programs` extending `Error`, parents before children.
+pub fn collect_error_classes(programs: &[Program]) -> Vec<ErrorClass> {
+    let classes: HashMap<String, &Class> = programs
+        .iter()
+        .flat_map(scope::top_level_decls)
+        .filter_map(|decl| match decl {
+            Decl::Class(class) if !class.declare => {
+                Some((class.ident.sym.to_string(), &*class.class))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut collected = Vec::new();
+    let mut names: Vec<&String> = classes.keys().collect();
+    names.sort();
+    for name in names {
+        collect_class(name, &classes, &mut collected, &mut HashSet::new());
+    }
+    scope::reset();
+    collected
+}
+
+/// Adds `name` to `collected` after its parents, if it is an error class
+fn collect_class(
+    name: &str,
+    classes: &HashMap<String, &Class>,
+    collected: &mut Vec<ErrorClass>,
+    visiting: &mut HashSet<String>,
+) -> bool {
+    if collected.iter().any(|class| class.name == name) {
+        return true;
+    }
+    let Some(class) = classes.get(name) else {
+        return false;
+    };
+    // `class A extends B` with `B extends A` is an error in TS; don't loop on it
+    if !visiting.insert(name.to_string()) {
+        return false;
+    }
+    let parent = match parent_name(class) {
+        Some(parent) if is_builtin(&parent) && !classes.contains_key(&parent) => None,
+        Some(parent) if collect_class(&parent, classes, collected, visiting) => {
+            collected.iter().find(|class| class.name == parent).cloned()
+        }
+        _ => return false,
+    };
+    let error_class = error_class(name, class, parent.as_ref());
+    collected.push(error_class);
+    true
+}
+
+/// Names of the class properties and parameter properties declared by `class`
+fn own_fields(class: &Class) -> Vec<(String, TsType, Option<Expr>)> {
+    let mut fields = Vec::new();
+    for member in &class.body {
+        match member {
+            ClassMember::ClassProp(prop) if !prop.is_static => {
+                let Some(key) = prop.key.as_ident() else {
+                    continue;
+                };
+                let ts_type = prop
+                    .type_ann
+                    .as_ref()
+                    .map(|type_ann| (*type_ann.type_ann).clone())
+                    .unwrap_or_else(|| scope::type_ref("unknown"));
+                fields.push((key.sym.to_string(), ts_type, prop.value.as_deref().cloned()));
+            }
+            ClassMember::Constructor(constructor) => {
+                for param in &constructor.params {
+                    if let ParamOrTsParamProp::TsParamProp(prop) = param {
+                        if let TsParamPropParam::Ident(ident) = &prop.param {
+                            let ts_type = ident
+                                .type_ann
+                                .as_ref()
+                                .map(|type_ann| (*type_ann.type_ann).clone())
+                                .unwrap_or_else(|| scope::type_ref("unknown"));
+                            fields.push((ident.sym.to_string(), ts_type, None));
+                        }
+                    }
+                }
+            }
+            ClassMember::Method(method) => diagnostics::warn(format!(
+                "error classes become `AppError` variants; the method `{}` was dropped",
+                method
+                    .key
+                    .as_ident()
+                    .map(|key| key.sym.as_ref())
+                    .unwrap_or("?")
+            )),
+            _ => {}
+        }
+    }
+    fields.retain(|(name, _, _)| !matches!(name.as_str(), "message" | "name" | "stack"));
+    fields
+}
+
+fn rust_type(ts_type: &TsType, init: Option<&Expr>) -> TokenStream {
+    let is_unknown = matches!(
+        ts_type,
+        TsType::TsTypeRef(type_ref)
+            if type_ref.type_name.as_ident().is_some_and(|name| name.sym == "unknown")
+    );
+    match init.and_then(infer_expr_type) {
+        Some(inferred) if is_unknown => inferred,
+        _ => map_ts_type(Some(&Box::new(TsTypeAnn {
+            span: swc_common::DUMMY_SP,
+            type_ann: Box::new(ts_type.clone()),
+        }))),
+    }
+}
+
+/// `name: value` pairs of a status code guessed from an error class name
+fn status_from_name(name: &str) -> Option<u16> {
+    let status = [
+        ("NotFound", 404),
+        ("Validation", 400),
+        ("BadRequest", 400),
+        ("Invalid", 400),
+        ("Unauthorized", 401),
+        ("Unauthenticated", 401),
+        ("Forbidden", 403),
+        ("Conflict", 409),
+        ("AlreadyExists", 409),
+    ];
+    status
+        .iter()
+        .find(|(part, _)| name.contains(part))
+        .map(|(_, code)| *code)
+}
+
+/// Converts an error class into its `AppError` variant and constructor
+fn error_class(name: &str, class: &Class, parent: Option<&ErrorClass>) -> ErrorClass {
+    scope::reset();
+    let own = own_fields(class);
+    let constructor = class.body.iter().find_map(|member| match member {
+        ClassMember::Constructor(constructor) => Some(constructor),
+        _ => None,
+    });
+
+    // Parameters of `new Name(...)`
+    let mut params = Vec::new();
+    match constructor {
+        Some(constructor) => {
+            for param in &constructor.params {
+                let ident = match param {
+                    ParamOrTsParamProp::Param(Param {
+                        pat: Pat::Ident(ident),
+                        ..
+                    }) => ident,
+                    ParamOrTsParamProp::TsParamProp(TsParamProp {
+                        param: TsParamPropParam::Ident(ident),
+                        ..
+                    }) => ident,
+                    _ => {
+                        diagnostics::warn(format!(
+                            "only plain parameters are supported in the `{}` constructor",
+                            name
+                        ));
+                        continue;
+                    }
+                };
+                if let Some(type_ann) = &ident.type_ann {
+                    scope::declare(&ident.sym, &type_ann.type_ann);
+                }
+                let mut ty = map_ts_type(ident.type_ann.as_ref());
+                if ident.optional {
+                    ty = quote! { Option<#ty> };
+                }
+                params.push((to_snake_case(&ident.sym), ty.to_string()));
+            }
+        }
+        None => match parent {
+            Some(parent) => params = parent.params.clone(),
+            None => params.push(("message".to_string(), "String".to_string())),
+        },
+    }
+
+    // Fields bound by the statements so far, besides `message`
+    let mut bound: HashSet<String> = own
+        .iter()
+        .filter(|(field, _, _)| {
+            params
+                .iter()
+                .any(|(param, _)| *param == to_snake_case(field))
+        })
+        .map(|(field, _, _)| to_snake_case(field))
+        .collect();
+    let mut body = Vec::new();
+    let mut has_message = false;
+
+    let mut super_call = |args: &[ExprOrSpread], body: &mut Vec<TokenStream>| {
+        has_message = true;
+        match parent {
+            Some(parent) => {
+                let variant = format_ident!("{}", parent.name);
+                let constructor = format_ident!("{}", to_snake_case(&parent.name));
+                let fields: Vec<_> = parent
+                    .fields
+                    .iter()
+                    .map(|(field, _)| format_ident!("{}", field))
+                    .collect();
+                let args = constructor_args(args, parent.params.len());
+                body.push(quote! {
+                    let Self::#variant { message, #(#fields,)* } = Self::#constructor(#(#args),*) else {
+                        unreachable!()
+                    };
+                });
+            }
+            None => match args.first() {
+                Some(message) => {
+                    let message = self::message(&message.expr);
+                    body.push(quote! { let message = #message; });
+                }
+                None => body.push(quote! { let message = String::new(); }),
+            },
+        }
+    };
+
+    match constructor.and_then(|constructor| constructor.body.as_ref()) {
+        Some(ctor_body) => {
+            for stmt in &ctor_body.stmts {
+                let Stmt::Expr(ExprStmt { expr, .. }) = stmt else {
+                    diagnostics::warn(format!(
+                        "only `super(...)` and `this.x = ...` are supported in the `{}` \
+                         constructor; other statements were dropped",
+                        name
+                    ));
+                    continue;
+                };
+                match &**expr {
+                    Expr::Call(call) if matches!(call.callee, Callee::Super(_)) => {
+                        super_call(&call.args, &mut body);
+                        // Field initializers run right after `super()`
+                        for (field, _, init) in &own {
+                            if let Some(init) = init {
+                                let ident = format_ident!("{}", to_snake_case(field));
+                                let init = convert_expr(init);
+                                body.push(quote! { let #ident = #init; });
+                                bound.insert(to_snake_case(field));
+                            }
+                        }
+                    }
+                    Expr::Assign(assign) if assign.op == AssignOp::Assign => {
+                        let Some(field) = assign
+                            .left
+                            .as_simple()
+                            .and_then(|target| target.as_member())
+                            .filter(|member| member.obj.is_this())
+                            .and_then(|member| member.prop.as_ident())
+                        else {
+                            continue;
+                        };
+                        match field.sym.as_ref() {
+                            "message" => {
+                                let message = message(&assign.right);
+                                body.push(quote! { let message = #message; });
+                            }
+                            "name" | "stack" => {}
+                            field => {
+                                let field = to_snake_case(field);
+                                let is_param = assign
+                                    .right
+                                    .as_ident()
+                                    .is_some_and(|value| to_snake_case(&value.sym) == field);
+                                if !is_param {
+                                    let ident = format_ident!("{}", field);
+                                    let value = convert_expr(&assign.right);
+                                    body.push(quote! { let #ident = #value; });
+                                }
+                                bound.insert(field);
+                            }
+                        }
+                    }
+                    // `Object.setPrototypeOf(this, X.prototype)` and
+                    // `Error.captureStackTrace(this)` have no Rust counterpart
+                    Expr::Call(_) => {}
+                    _ => diagnostics::warn(format!(
+                        "a statement in the `{}` constructor was dropped",
+                        name
+                    )),
+                }
+            }
+        }
+        // The inherited constructor
+        None => {
+            let args: Vec<ExprOrSpread> = params
+                .iter()
+                .map(|(param, _)| ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(Expr::Ident(Ident::new_no_ctxt(
+                        param.as_str().into(),
+                        swc_common::DUMMY_SP,
+                    ))),
+                })
+                .collect();
+            super_call(&args, &mut body);
+        }
+    }
+    if !has_message {
+        body.push(quote! { let message = String::new(); });
+    }
+
+    let mut fields: Vec<(String, String)> = parent
+        .map(|parent| parent.fields.clone())
+        .unwrap_or_default();
+    if let Some(parent) = parent {
+        bound.extend(parent.fields.iter().map(|(field, _)| field.clone()));
+    }
+    for (field, ts_type, init) in &own {
+        let field = to_snake_case(field);
+        if fields.iter().any(|(existing, _)| *existing == field) {
+            continue;
+        }
+        fields.push((field, rust_type(ts_type, init.as_ref()).to_string()));
+    }
+    let inits: Vec<_> = fields
+        .iter()
+        .map(|(field, _)| {
+            let ident = format_ident!("{}", field);
+            if bound.contains(field) {
+                quote! { #ident }
+            } else {
+                quote! { #ident: Default::default() }
+            }
+        })
+        .collect();
+    let variant = format_ident!("{}", name);
+    body.push(quote! { Self::#variant { message, #(#inits),* } });
+
+    let status_field = fields
+        .iter()
+        .find(|(field, ty)| matches!(field.as_str(), "status" | "status_code") && ty == "f64");
+    let status = match (status_field, status_from_name(name)) {
+        (Some((field, _)), _) => ErrorStatus::Field(field.clone()),
+        (None, Some(code)) => ErrorStatus::Code(code),
+        (None, None) => parent
+            .map(|parent| parent.status.clone())
+            .unwrap_or(ErrorStatus::Code(500)),
+    };
+
+    scope::reset();
+    ErrorClass {
+        name: name.to_string(),
+        fields,
+        params,
+        body: quote! { #(#body)* }.to_string(),
+        status,
+        parent: parent.map(|parent| parent.name.clone()),
+    }
+}
+
+/// An error message as a `String`
+fn message(expr: &Expr) -> TokenStream {
+    match expr {
+        Expr::Lit(Lit::Str(_)) | Expr::Tpl(_) => convert_expr(expr),
+        Expr::Bin(_) | Expr::Cond(_) => {
+            let expr = convert_expr(expr);
+            quote! { (#expr).to_string() }
+        }
+        _ => {
+            let expr = convert_expr(expr);
+            quote! { #expr.to_string() }
+        }
+    }
+}
+
+/// Arguments of an error constructor; missing trailing ones are defaulted
+fn constructor_args(args: &[ExprOrSpread], arity: usize) -> Vec<TokenStream> {
+    let mut converted: Vec<_> = args.iter().map(|arg| convert_expr(&arg.expr)).collect();
+    while converted.len() < arity {
+        converted.push(quote! { Default::default() });
+    }
+    converted
+}
+
+/// Errors of the libraries generated code uses `?` on
+const LIBRARY_ERRORS: &[&str] = &[
+    "std::io::Error",
+    "std::fmt::Error",
+    "std::num::ParseFloatError",
+    "std::num::ParseIntError",
+    "serde_json::Error",
+    "reqwest::Error",
+    "tokio::task::JoinError",
+];
+
+fn parse(tokens: &str) -> TokenStream {
+    tokens.parse().unwrap_or_default()
+}
+
+/// The `AppError` status code for `status`, as an `axum::http::StatusCode`
+fn status_code(status: u16) -> TokenStream {
+    quote! {
+        StatusCode::from_u16(#status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+/// The source of the generated `error.rs`: `AppError` with a variant per error
+/// class, its constructors, and the HTTP response for each variant
+pub fn error_module(classes: &[ErrorClass]) -> String {
+    let variants = classes.iter().map(|class| {
+        let variant = format_ident!("{}", class.name);
+        let fields = class.fields.iter().map(|(field, ty)| {
+            let field = format_ident!("{}", field);
+            let ty = parse(ty);
+            quote! { #field: #ty }
+        });
+        quote! {
+            #[error("{message}")]
+            #variant { message: String, #(#fields),* },
+        }
+    });
+    let constructors = classes.iter().map(|class| {
+        let constructor = format_ident!("{}", to_snake_case(&class.name));
+        let params = class.params.iter().map(|(param, ty)| {
+            let param = format_ident!("{}", param);
+            let ty = parse(ty);
+            quote! { #param: #ty }
+        });
+        let body = parse(&class.body);
+        quote! {
+            #[allow(unused_variables)]
+            pub fn #constructor(#(#params),*) -> Self {
+                #body
+            }
+        }
+    });
+    let names = classes.iter().map(|class| {
+        let variant = format_ident!("{}", class.name);
+        let name = &class.name;
+        quote! { Self::#variant { .. } => #name, }
+    });
+    let statuses = classes.iter().filter_map(|class| {
+        let variant = format_ident!("{}", class.name);
+        match &class.status {
+            ErrorStatus::Code(500) => None,
+            ErrorStatus::Code(code) => {
+                let status = status_code(*code);
+                Some(quote! { Self::#variant { .. } => #status, })
+            }
+            ErrorStatus::Field(field) => {
+                let field = format_ident!("{}", field);
+                Some(quote! {
+                    Self::#variant { #field, .. } => StatusCode::from_u16(*#field as u16)
+                        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                })
+            }
+        }
+    });
+
+    let library_errors = LIBRARY_ERRORS.iter().map(|error| {
+        let error = parse(error);
+        quote! {
+            impl From<#error> for AppError {
+                fn from(err: #error) -> Self {
+                    Self::Other(Box::new(err))
+                }
+            }
+        }
+    });
+
+    let module = quote! {
+        use axum::{http::StatusCode, response::{IntoResponse, Response}};
+
+        #[derive(Debug, thiserror::Error)]
+        pub enum AppError {
+            #(#variants)*
+            #[error("{0}")]
+            Error(String),
+            #[error(transparent)]
+            Other(Box<dyn std::error::Error + Send + Sync>),
+        }
+
+        impl AppError {
+            #(#constructors)*
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    #(#names)*
+                    Self::Error(_) | Self::Other(_) => "Error",
+                }
+            }
+        }
+
+        #(#library_errors)*
+
+        impl IntoResponse for AppError {
+            fn into_response(self) -> Response {
+                let status = match &self {
+                    #(#statuses)*
+                    _ => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, self.to_string()).into_response()
+            }
+        }
+    };
+    format!(
+        "// Errors thrown by the application: a variant per `Error` subclass, \
+         `Error` for the built-in errors and `Other` for errors of Rust libraries\n{}",
+        module
+    )
+}
+
+/// `new NotFoundError(id)` -> `crate::AppError::not_found_error(id)`, and the
+/// built-in `new Error(message)` -> `crate::AppError::Error(message)`
+pub fn new_error(new_expr: &NewExpr) -> Option<TokenStream> {
+    let name = new_expr.callee.as_ident()?.sym.as_ref();
+    let args = new_expr.args.as_deref().unwrap_or_default();
+    if let Some(class) = lookup(name) {
+        let constructor = format_ident!("{}", to_snake_case(name));
+        let args = constructor_args(args, class.params.len());
+        return Some(quote! { crate::AppError::#constructor(#(#args),*) });
+    }
+    if !is_error_type(name) {
+        return None;
+    }
+    match args.first() {
+        Some(message) => {
+            let message = self::message(&message.expr);
+            Some(quote! { crate::AppError::Error(#message) })
+        }
+        None => Some(quote! { crate::AppError::Error(String::new()) }),
+    }
+}
+
+/// Whether `expr` is a local known to hold a `crate::AppError`
+fn is_error_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(paren) => is_error_expr(&paren.expr),
+        Expr::Ident(ident) => scope::lookup(&ident.sym).is_some_and(|ts_type| {
+            matches!(
+                &ts_type,
+                TsType::TsTypeRef(type_ref)
+                    if type_ref.type_name.as_ident().is_some_and(|name| is_error_type(&name.sym))
+            )
+        }),
+        _ => false,
+    }
+}
+
+/// The `crate::AppError` a `throw` or a rejected promise produces
+pub fn app_error(reason: Option<&Expr>) -> TokenStream {
+    match reason {
+        Some(Expr::New(new_expr)) => new_error(new_expr).unwrap_or_else(|| {
+            let reason = convert_expr(&Expr::New(new_expr.clone()));
+            quote! { crate::AppError::Error(#reason.to_string()) }
+        }),
+        // Rethrowing a caught error
+        Some(reason) if is_error_expr(reason) => convert_expr(reason),
+        Some(reason) => {
+            let reason = message(reason);
+            quote! { crate::AppError::Error(#reason) }
+        }
+        None => quote! { crate::AppError::Error(String::from("undefined")) },
+    }
+}
+
+/// `throw err`: an `Err` return where the function returns `Result`
+pub fn throw(arg: &Expr) -> TokenStream {
+    let err = app_error(Some(arg));
+    if scope::is_fallible() {
+        quote! { return Err(#err); }
+    } else {
+        diagnostics::warn(
+            "`throw` in a function that does not return `Result` panics; \
+             make the function `async` or catch the error",
+        );
+        quote! { panic!("{}", #err); }
+    }
+}
+
+/// `name` and the error classes extending it, directly or not, in any file
+fn with_descendants(name: &str) -> Vec<String> {
+    let mut names = vec![name.to_string()];
+    ERROR_CLASSES.with(|e| {
+        let classes = e.borrow();
+        let mut children: Vec<&ErrorClass> = classes.values().collect();
+        children.sort_by(|a, b| a.name.cmp(&b.name));
+        // `names` grows as the children of each name are found
+        let mut index = 0;
+        while index < names.len() {
+            for class in &children {
+                if class.parent.as_deref() == Some(names[index].as_str())
+                    && !names.contains(&class.name)
+                {
+                    names.push(class.name.clone());
+                }
+            }
+            index += 1;
+        }
+    });
+    names
+}
+
+/// The variant pattern an `instanceof` right-hand side matches: the class's
+/// variant or a subclass's
+fn variant_pattern(class: &Expr) -> Option<TokenStream> {
+    let name = class.as_ident()?.sym.as_ref();
+    if lookup(name).is_some() {
+        let variants = with_descendants(name)
+            .into_iter()
+            .map(|name| format_ident!("{}", name));
+        return Some(quote! { #(crate::AppError::#variants { .. })|* });
+    }
+    match name {
+        "Error" if is_error_type(name) => Some(quote! { _ }),
+        name if is_error_type(name) => Some(quote! { crate::AppError::Error(_) }),
+        _ => None,
+    }
+}
+
+/// `err instanceof NotFoundError` -> `matches!(&err, crate::AppError::NotFoundError { .. })`
+pub fn instance_of(bin: &BinExpr) -> Option<TokenStream> {
+    if bin.op != BinaryOp::InstanceOf || !is_error_expr(&bin.left) {
+        return None;
+    }
+    let pattern = variant_pattern(&bin.right)?;
+    let err = convert_expr(&bin.left);
+    Some(quote! { matches!(&#err, #pattern) })
+}
+
+/// Whether `expr` reads a property of a caught or constructed error
+pub fn is_error_member(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(paren) => is_error_member(&paren.expr),
+        Expr::Member(member) => is_error_expr(&member.obj),
+        _ => false,
+    }
+}
+
+/// `err.message`, `err.name`, the fields of a narrowed error class, and the
+/// `response` of an axios error
+pub fn try_handle_member(member: &MemberExpr) -> Option<TokenStream> {
+    if !is_error_expr(&member.obj) {
+        return None;
+    }
+    let prop = member.prop.as_ident()?.sym.as_ref();
+    let err = convert_expr(&member.obj);
+    match prop {
+        "message" => Some(quote! { #err.to_string() }),
+        "name" => Some(quote! { #err.name().to_string() }),
+        field => class_field(member, &err, field)
+            .or_else(|| (field == "response").then(|| crate::stdlib::axios::error_response(err))),
+    }
+}
+
+/// `err.field` where `err` is narrowed to an error class declaring `field`
+fn class_field(member: &MemberExpr, err: &TokenStream, field: &str) -> Option<TokenStream> {
+    let ident = member.obj.as_ident()?;
+    let Some(TsType::TsTypeRef(type_ref)) = scope::lookup(&ident.sym) else {
+        return None;
+    };
+    let class = lookup(&type_ref.type_name.as_ident()?.sym)?;
+    let field = format_ident!("{}", to_snake_case(field));
+    if !class.fields.iter().any(|(name, _)| field == name) {
+        return None;
+    }
+    // Subclasses inherit the field
+    let variants = with_descendants(&class.name)
+        .into_iter()
+        .map(|name| format_ident!("{}", name));
+    Some(quote! {
+        match &#err {
+            #(crate::AppError::#variants { #field, .. })|* => #field.clone(),
+            _ => unreachable!(),
+        }
+    })
+}
+
+/// Whether statements contain an `await` outside of nested functions
+#[derive(Default)]
+struct AwaitFinder {
+    found: bool,
+}
+
+impl Visit for AwaitFinder {
+    fn visit_await_expr(&mut self, _: &AwaitExpr) {
+        self.found = true;
+    }
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+}
+
+fn has_await(block: &BlockStmt) -> bool {
+    let mut finder = AwaitFinder::default();
+    block.visit_with(&mut finder);
+    finder.found
+}
+
+/// Whether statements return outside of nested functions
+#[derive(Default)]
+struct ReturnFinder {
+    found: bool,
+}
+
+impl Visit for ReturnFinder {
+    fn visit_return_stmt(&mut self, _: &ReturnStmt) {
+        self.found = true;
+    }
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+}
+
+fn has_return(block: &BlockStmt) -> bool {
+    let mut finder = ReturnFinder::default();
+    block.visit_with(&mut finder);
+    finder.found
+}
+
+/// `return x` inside a `try` or `catch` block, which is a closure or an
+/// `async` block resolving to `Ok(Some(x))`
+fn block_return(ret: &ReturnStmt) -> TokenStream {
+    match ret.arg.as_deref() {
+        Some(arg) if crate::stdlib::promise::is_promise_expr(arg) => {
+            let value = crate::stdlib::promise::await_expr(arg);
+            quote! { return Ok(Some(#value)); }
+        }
+        Some(arg) => {
+            let value = convert_expr(arg);
+            quote! { return Ok(Some(#value)); }
+        }
+        None => quote! { return Ok(Some(Default::default())); },
+    }
+}
+
+/// Whether the last statement returns or throws on every path
+fn ends_in_exit(stmts: &[Stmt]) -> bool {
+    match stmts.last() {
+        Some(Stmt::Return(_) | Stmt::Throw(_)) => true,
+        Some(Stmt::Block(block)) => ends_in_exit(&block.stmts),
+        Some(Stmt::If(if_stmt)) => if_stmt.alt.as_deref().is_some_and(|alt| {
+            ends_in_exit(std::slice::from_ref(&*if_stmt.cons))
+                && ends_in_exit(std::slice::from_ref(alt))
+        }),
+        _ => false,
+    }
+}
+
+/// Statements of a `try` or `catch` block as a `Result<Option<T>, AppError>`:
+/// `Err` when it throws, `Some` when it returns
+fn protected(stmts: Vec<TokenStream>, is_async: bool, returns: bool) -> TokenStream {
+    let value = if returns {
+        quote! { _ }
+    } else {
+        quote! { () }
+    };
+    if is_async {
+        quote! {
+            async {
+                #(#stmts)*
+                Ok::<Option<#value>, crate::AppError>(None)
+            }
+            .await
+        }
+    } else {
+        quote! {
+            (|| -> Result<Option<#value>, crate::AppError> {
+                #(#stmts)*
+                Ok(None)
+            })()
+        }
+    }
+}
+
+/// A leading `if (err instanceof A) {...} else if (err instanceof B) {...}`
+/// chain in a catch block, as match arms on the caught error
+fn instanceof_arms(param: &str, stmt: &Stmt) -> Option<Vec<(TokenStream, Option<String>, Stmt)>> {
+    if !matches!(stmt, Stmt::If(_)) {
+        return None;
+    }
+    let mut arms = Vec::new();
+    let mut current = stmt;
+    loop {
+        let Stmt::If(if_stmt) = current else {
+            arms.push((quote! { _ }, None, current.clone()));
+            return Some(arms);
+        };
+        let Expr::Bin(bin) = &*if_stmt.test else {
+            return None;
+        };
+        let is_param = bin.left.as_ident().is_some_and(|left| left.sym == param);
+        if bin.op != BinaryOp::InstanceOf || !is_param {
+            return None;
+        }
+        let pattern = variant_pattern(&bin.right)?;
+        let class = bin
+            .right
+            .as_ident()
+            .map(|class| class.sym.to_string())
+            .filter(|class| lookup(class).is_some());
+        arms.push((pattern, class, (*if_stmt.cons).clone()));
+        match &if_stmt.alt {
+            Some(alt) => current = alt,
+            None => {
+                arms.push((quote! { _ }, None, Stmt::Block(BlockStmt::default())));
+                return Some(arms);
+            }
+        }
+    }
+}
+
+/// The statements of a catch block, with `instanceof` checks as `match` arms
+fn catch_stmts(param: Option<&str>, block: &BlockStmt) -> Vec<TokenStream> {
+    let convert = |stmt: &Stmt| convert_stmt_recursive(stmt, &block_return);
+    let Some(param) = param else {
+        return block.stmts.iter().map(convert).collect();
+    };
+    let err = format_ident!("{}", to_snake_case(param));
+    let error_type = scope::type_ref("Error");
+    scope::declare(param, &error_type);
+
+    let Some((first, rest)) = block.stmts.split_first() else {
+        return Vec::new();
+    };
+    let Some(arms) = instanceof_arms(param, first) else {
+        return block.stmts.iter().map(convert).collect();
+    };
+    let arms: Vec<_> = arms
+        .iter()
+        .map(|(pattern, class, body)| {
+            // Inside the arm the error is known to be that class
+            if let Some(class) = class {
+                scope::declare(param, &scope::type_ref(class));
+            }
+            let body = convert(body);
+            scope::declare(param, &error_type);
+            quote! { #pattern => #body }
+        })
+        .collect();
+    let mut stmts = vec![quote! { match &#err { #(#arms)* } }];
+    stmts.extend(rest.iter().map(convert));
+    stmts
+}
+
+/// `try { ... } catch (err) { ... } finally { ... }`. The blocks run as
+/// closures (or `async` blocks when they await) returning
+/// `Result<Option<T>, AppError>`, so `throw` and `?` are caught and `return`
+/// leaves the enclosing function through `ret`.
+pub fn convert_try<F>(try_stmt: &TryStmt, ret: &F) -> TokenStream
+where
+    F: Fn(&ReturnStmt) -> TokenStream,
+{
+    let catch_body = try_stmt.handler.as_ref().map(|handler| &handler.body);
+    let is_async = has_await(&try_stmt.block) || catch_body.is_some_and(has_await);
+    let returns = has_return(&try_stmt.block) || catch_body.is_some_and(has_return);
+
+    // Errors thrown inside are caught, so they are always returned as `Err`
+    let was_fallible = scope::set_fallible(true);
+    let body: Vec<_> = try_stmt
+        .block
+        .stmts
+        .iter()
+        .map(|stmt| convert_stmt_recursive(stmt, &block_return))
+        .collect();
+    let body = protected(body, is_async, returns);
+
+    let caught = try_stmt.handler.as_ref().map(|handler| {
+        let param = match &handler.param {
+            Some(Pat::Ident(ident)) => Some(ident.sym.to_string()),
+            Some(_) => {
+                diagnostics::warn("destructuring a caught error is not supported");
+                None
+            }
+            None => None,
+        };
+        let stmts = catch_stmts(param.as_deref(), &handler.body);
+        let err = match &param {
+            Some(param) => {
+                let param = format_ident!("{}", to_snake_case(param));
+                quote! { #param }
+            }
+            None => quote! { _ },
+        };
+        let handled = protected(stmts, is_async, returns);
+        quote! {
+            let outcome = match outcome {
+                Err(#err) => #handled,
+                outcome => outcome,
+            };
+        }
+    });
+    scope::set_fallible(was_fallible);
+
+    let finally: Vec<_> = try_stmt
+        .finalizer
+        .iter()
+        .flat_map(|finalizer| &finalizer.stmts)
+        .map(convert_stmt)
+        .collect();
+
+    // Rethrow what was not caught, and return what was returned
+    let outcome = if scope::is_fallible() {
+        quote! { outcome? }
+    } else {
+        quote! { outcome.unwrap_or_else(|err| panic!("{}", err)) }
+    };
+    let always_returns = ends_in_exit(&try_stmt.block.stmts)
+        && catch_body.is_none_or(|catch_body| ends_in_exit(&catch_body.stmts));
+    let returned = ret(&ReturnStmt {
+        span: swc_common::DUMMY_SP,
+        arg: Some(Box::new(Expr::Ident(Ident::new_no_ctxt(
+            "returned".into(),
+            swc_common::DUMMY_SP,
+        )))),
+    });
+    let dispatch = match (returns, always_returns) {
+        // Both blocks return or throw, so the statement itself never completes
+        (true, true) => quote! {
+            match #outcome {
+                Some(returned) => { #returned }
+                None => unreachable!(),
+            }
+        },
+        (true, false) => quote! {
+            if let Some(returned) = #outcome {
+                #returned
+            }
+        },
+        (false, true) => quote! {
+            #outcome;
+            unreachable!()
+        },
+        (false, false) => quote! { #outcome; },
+    };
+
+    quote! {
+        {
+            let outcome = #body;
+            #caught
+            #(#finally)*
+            #dispatch
+        }
+    }
+}
@@ -0,0 +1,722 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+use super::class::class_fields;
+use super::func::{convert_expr, convert_place_expr, convert_stmt, to_snake_case};
+use super::module::infer_expr_type;
+use super::scope;
+use super::type_mapper::map_inner_type;
+use crate::{diagnostics, support};
+
+// Classes extending Node's `EventEmitter`, directly or through each other, get
+// an `events: crate::events::EventBus<NameEvent>` field. `NameEvent` is an enum
+// emitted next to the struct, with a variant per event name carrying its
+// payload: declared by `EventEmitter<{ name: [T] }>`, or else inferred from the
+// `this.emit("name", ...)` calls of the class. Listeners are boxed closures
+// that destructure their variant.
+
+/// An event of an emitter class
+#[derive(Debug, Clone)]
+pub struct EmitterEvent {
+    /// The JS event name
+    pub name: String,
+    /// The Rust type of each listener argument
+    pub payload: Vec<TokenStream>,
+    /// The TS type of each listener argument, when known
+    pub ts_types: Vec<Option<TsType>>,
+}
+
+thread_local! {
+    static EMITTERS: RefCell<HashMap<String, Vec<EmitterEvent>>> = RefCell::new(HashMap::new());
+    /// Fields of each class holding an emitter, as (class, TS field name) -> emitter class
+    static EMITTER_FIELDS: RefCell<HashMap<(String, String), String>> = RefCell::new(HashMap::new());
+}
+
+/// Whether an import source is Node's `events` module
+pub fn is_events_module(src: &str) -> bool {
+    src.strip_prefix("node:").unwrap_or(src) == "events"
+}
+
+/// The events of the emitter class `name`, if it is one
+pub fn lookup(name: &str) -> Option<Vec<EmitterEvent>> {
+    EMITTERS.with(|e| e.borrow().get(name).cloned())
+}
+
+pub fn is_emitter(name: &str) -> bool {
+    EMITTERS.with(|e| e.borrow().contains_key(name))
+}
+
+/// Names `EventEmitter` is imported as: the class itself, or the module
+/// object of `import * as events from "events"`
+#[derive(Default)]
+struct EmitterImports {
+    classes: HashSet<String>,
+    modules: HashSet<String>,
+}
+
+fn emitter_imports(program: &Program) -> EmitterImports {
+    let mut imports = EmitterImports::default();
+    let Program::Module(module) = program else {
+        return imports;
+    };
+    for item in &module.body {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+            continue;
+        };
+        if !import.src.value.as_str().is_some_and(is_events_module) {
+            continue;
+        }
+        for specifier in &import.specifiers {
+            match specifier {
+                ImportSpecifier::Named(named) => {
+                    let imported = match &named.imported {
+                        Some(ModuleExportName::Ident(ident)) => ident.sym.to_string(),
+                        Some(ModuleExportName::Str(s)) => s.value.to_string_lossy().into_owned(),
+                        None => named.local.sym.to_string(),
+                    };
+                    if imported == "EventEmitter" {
+                        imports.classes.insert(named.local.sym.to_string());
+                    }
+                }
+                // The default export of `events` is `EventEmitter`
+                ImportSpecifier::Default(default) => {
+                    imports.classes.insert(default.local.sym.to_string());
+                }
+                ImportSpecifier::Namespace(ns) => {
+                    imports.modules.insert(ns.local.sym.to_string());
+                }
+            }
+        }
+    }
+    imports
+}
+
+impl EmitterImports {
+    fn is_emitter_class(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Ident(ident) => self.classes.contains(ident.sym.as_ref()),
+            Expr::Member(member) => {
+                member
+                    .obj
+                    .as_ident()
+                    .is_some_and(|obj| self.modules.contains(obj.sym.as_ref()))
+                    && member
+                        .prop
+                        .as_ident()
+                        .is_some_and(|prop| prop.sym == "EventEmitter")
+            }
+            _ => false,
+        }
+    }
+}
+
+/// An emitter class found anywhere in the project
+#[derive(Debug, Clone)]
+pub struct EmitterClass {
+    pub name: String,
+    pub events: Vec<EmitterEvent>,
+}
+
+/// Classes declared at the top level of a file, with the names `EventEmitter`
+/// is imported as there
+type Classes<'a> = HashMap<String, (&'a Class, &'a EmitterImports)>;
+
+fn top_level_classes<'a>(program: &'a Program, imports: &'a EmitterImports) -> Classes<'a> {
+    scope::top_level_decls(program)
+        .into_iter()
+        .filter_map(|decl| match decl {
+            Decl::Class(class) if !class.declare => {
+                Some((class.ident.sym.to_string(), (&*class.class, imports)))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Every emitter class in `programs` and its events, so a class can extend or
+/// hold an emitter declared in another file.
+pub fn collect_emitter_classes(programs: &[Program]) -> Vec<EmitterClass> {
+    EMITTERS.with(|e| e.borrow_mut().clear());
+    // Event maps may name interfaces of any file
+    scope::reset_module();
+    for program in programs {
+        super::interface::collect_struct_fields(program);
+    }
+
+    let imports: Vec<EmitterImports> = programs.iter().map(emitter_imports).collect();
+    let classes: Classes = programs
+        .iter()
+        .zip(&imports)
+        .flat_map(|(program, imports)| top_level_classes(program, imports))
+        .collect();
+    let mut names: Vec<&String> = classes.keys().collect();
+    names.sort();
+    for name in names {
+        collect_emitter(name, &classes, &mut HashSet::new());
+    }
+
+    let mut collected: Vec<EmitterClass> = EMITTERS.with(|e| {
+        e.borrow_mut()
+            .drain()
+            .map(|(name, events)| EmitterClass { name, events })
+            .collect()
+    });
+    collected.sort_by(|a, b| a.name.cmp(&b.name));
+    scope::reset();
+    scope::reset_module();
+    collected
+}
+
+/// Use the emitter classes of the whole project, plus those of `program`
+/// itself, which are found again with the file's own types in scope.
+pub fn register(program: &Program, project: &[EmitterClass]) {
+    EMITTERS.with(|e| e.borrow_mut().clear());
+    EMITTER_FIELDS.with(|f| f.borrow_mut().clear());
+
+    let imports = emitter_imports(program);
+    let classes = top_level_classes(program, &imports);
+    EMITTERS.with(|e| {
+        let mut emitters = e.borrow_mut();
+        for class in project.iter().filter(|c| !classes.contains_key(&c.name)) {
+            emitters.insert(class.name.clone(), class.events.clone());
+        }
+    });
+
+    let mut names: Vec<&String> = classes.keys().collect();
+    names.sort();
+    for name in &names {
+        collect_emitter(name, &classes, &mut HashSet::new());
+    }
+    for name in names {
+        for (field, ts_type) in class_fields(classes[name].0) {
+            if let Some(emitter) = type_name(&ts_type).filter(|emitter| is_emitter(emitter)) {
+                EMITTER_FIELDS.with(|f| {
+                    f.borrow_mut().insert((name.clone(), field), emitter);
+                });
+            }
+        }
+    }
+    scope::reset();
+}
+
+/// Registers `name` after its parents, if it is an emitter class
+fn collect_emitter(name: &str, classes: &Classes, visiting: &mut HashSet<String>) -> bool {
+    if is_emitter(name) {
+        return true;
+    }
+    let Some(&(class, imports)) = classes.get(name) else {
+        return false;
+    };
+    if !visiting.insert(name.to_string()) {
+        return false;
+    }
+    let Some(parent) = class.super_class.as_deref() else {
+        return false;
+    };
+    let mut events = if imports.is_emitter_class(parent) {
+        declared_events(class)
+    } else {
+        match parent.as_ident() {
+            Some(parent) if collect_emitter(&parent.sym, classes, visiting) => {
+                lookup(&parent.sym).unwrap_or_default()
+            }
+            _ => return false,
+        }
+    };
+    for event in emitted_events(name, class) {
+        match events.iter().find(|known| known.name == event.name) {
+            Some(known) if known.payload.len() != event.payload.len() => {
+                diagnostics::warn(format!(
+                    "`{}` emits \"{}\" with {} arguments, but listeners get {}",
+                    name,
+                    event.name,
+                    event.payload.len(),
+                    known.payload.len()
+                ));
+            }
+            Some(_) => {}
+            None => events.push(event),
+        }
+    }
+    EMITTERS.with(|e| {
+        e.borrow_mut().insert(name.to_string(), events);
+    });
+    true
+}
+
+/// Events declared by `extends EventEmitter<{ name: [T, U] }>`, or by an
+/// interface of such tuples
+fn declared_events(class: &Class) -> Vec<EmitterEvent> {
+    let Some(event_map) = class
+        .super_type_params
+        .as_ref()
+        .and_then(|type_args| type_args.params.first())
+    else {
+        return Vec::new();
+    };
+    let entries: Vec<(String, TsType)> = match &**event_map {
+        TsType::TsTypeLit(lit) => lit
+            .members
+            .iter()
+            .filter_map(|member| {
+                let TsTypeElement::TsPropertySignature(prop) = member else {
+                    return None;
+                };
+                let name = match &*prop.key {
+                    Expr::Ident(ident) => ident.sym.to_string(),
+                    Expr::Lit(Lit::Str(s)) => s.value.to_string_lossy().into_owned(),
+                    _ => return None,
+                };
+                Some((name, (*prop.type_ann.as_ref()?.type_ann).clone()))
+            })
+            .collect(),
+        TsType::TsTypeRef(_) => type_name(event_map)
+            .and_then(|name| scope::lookup_struct(&name))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|field| (field.name, field.ts_type))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    entries
+        .into_iter()
+        .map(|(name, ts_type)| {
+            let args: Vec<TsType> = match ts_type {
+                TsType::TsTupleType(tuple) => tuple
+                    .elem_types
+                    .iter()
+                    .map(|elem| (*elem.ty).clone())
+                    .collect(),
+                _ => {
+                    diagnostics::warn(format!(
+                        "the listener arguments of \"{}\" should be a tuple type",
+                        name
+                    ));
+                    Vec::new()
+                }
+            };
+            EmitterEvent {
+                name,
+                payload: args.iter().map(map_inner_type).collect(),
+                ts_types: args.into_iter().map(Some).collect(),
+            }
+        })
+        .collect()
+}
+
+/// The name of a plain type reference such as `OrderService`
+fn type_name(ts_type: &TsType) -> Option<String> {
+    match ts_type {
+        TsType::TsTypeRef(type_ref) => Some(type_ref.type_name.as_ident()?.sym.to_string()),
+        _ => None,
+    }
+}
+
+/// Finds `this.emit("name", ...)` calls, typing their arguments from the
+/// parameters, annotated locals and fields in scope
+struct EmitCollector<'a> {
+    class: &'a str,
+    fields: &'a [(String, TsType)],
+    events: Vec<EmitterEvent>,
+}
+
+impl EmitCollector<'_> {
+    fn arg_type(&self, arg: &Expr) -> (TokenStream, Option<TsType>) {
+        let ts_type = match arg {
+            Expr::Ident(ident) => scope::lookup(&ident.sym),
+            Expr::Member(member) if member.obj.is_this() => {
+                member.prop.as_ident().and_then(|prop| {
+                    self.fields
+                        .iter()
+                        .find(|(field, _)| prop.sym == *field)
+                        .map(|(_, ts_type)| ts_type.clone())
+                })
+            }
+            _ => None,
+        };
+        if let Some(ts_type) = ts_type {
+            return (map_inner_type(&ts_type), Some(ts_type));
+        }
+        match infer_expr_type(arg) {
+            Some(inferred) => (inferred, None),
+            None => {
+                diagnostics::warn(format!(
+                    "could not infer the type of an argument emitted by `{}`; it is a \
+                     `serde_json::Value`",
+                    self.class
+                ));
+                (quote! { serde_json::Value }, None)
+            }
+        }
+    }
+}
+
+impl Visit for EmitCollector<'_> {
+    fn visit_param(&mut self, param: &Param) {
+        if let Pat::Ident(ident) = &param.pat {
+            if let Some(type_ann) = &ident.type_ann {
+                scope::declare(&ident.sym, &type_ann.type_ann);
+            }
+        }
+    }
+
+    fn visit_var_declarator(&mut self, decl: &VarDeclarator) {
+        if let Pat::Ident(ident) = &decl.name {
+            if let Some(type_ann) = &ident.type_ann {
+                scope::declare(&ident.sym, &type_ann.type_ann);
+            }
+        }
+        decl.visit_children_with(self);
+    }
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        call.visit_children_with(self);
+        let is_this_emit = call
+            .callee
+            .as_expr()
+            .and_then(|callee| callee.as_member())
+            .is_some_and(|member| {
+                member.obj.is_this() && member.prop.as_ident().is_some_and(|p| p.sym == "emit")
+            });
+        if !is_this_emit {
+            return;
+        }
+        let Some(Expr::Lit(Lit::Str(name))) = call.args.first().map(|arg| &*arg.expr) else {
+            diagnostics::warn(format!(
+                "`{}` emits an event whose name is not a string literal; it was skipped",
+                self.class
+            ));
+            return;
+        };
+        let name = name.value.to_string_lossy().into_owned();
+        if self.events.iter().any(|event| event.name == name) {
+            return;
+        }
+        let (payload, ts_types) = call.args[1..]
+            .iter()
+            .map(|arg| self.arg_type(&arg.expr))
+            .unzip();
+        self.events.push(EmitterEvent {
+            name,
+            payload,
+            ts_types,
+        });
+    }
+}
+
+fn emitted_events(name: &str, class: &Class) -> Vec<EmitterEvent> {
+    let fields = class_fields(class);
+    let mut collector = EmitCollector {
+        class: name,
+        fields: &fields,
+        events: Vec::new(),
+    };
+    for member in &class.body {
+        scope::reset();
+        match member {
+            ClassMember::Method(method) => method.function.visit_with(&mut collector),
+            ClassMember::Constructor(constructor) => {
+                for param in &constructor.params {
+                    match param {
+                        ParamOrTsParamProp::Param(param) => collector.visit_param(param),
+                        ParamOrTsParamProp::TsParamProp(TsParamProp {
+                            param: TsParamPropParam::Ident(ident),
+                            ..
+                        }) => {
+                            if let Some(type_ann) = &ident.type_ann {
+                                scope::declare(&ident.sym, &type_ann.type_ann);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                constructor.body.visit_with(&mut collector);
+            }
+            _ => {}
+        }
+    }
+    collector.events
+}
+
+/// `orderPlaced` -> `OrderPlaced`, `user:created` -> `UserCreated`
+fn variant_name(event: &str) -> proc_macro2::Ident {
+    let mut name = String::new();
+    for part in event.split(|c: char| !c.is_alphanumeric()) {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            name.extend(first.to_uppercase());
+            name.push_str(chars.as_str());
+        }
+    }
+    if !name.starts_with(|c: char| c.is_alphabetic()) {
+        name.insert_str(0, "Event");
+    }
+    format_ident!("{}", name)
+}
+
+fn event_enum(class: &str) -> proc_macro2::Ident {
+    format_ident!("{}Event", class)
+}
+
+/// The event enum of an emitter class, emitted before its struct
+pub fn event_items(class: &str, vis: &TokenStream) -> TokenStream {
+    let events = lookup(class).unwrap_or_default();
+    let name = event_enum(class);
+    let variants = events.iter().map(|event| {
+        let variant = variant_name(&event.name);
+        let payload = &event.payload;
+        if payload.is_empty() {
+            quote! { #variant }
+        } else {
+            quote! { #variant(#(#payload),*) }
+        }
+    });
+    let names = events.iter().map(|event| {
+        let variant = variant_name(&event.name);
+        let event_name = &event.name;
+        if event.payload.is_empty() {
+            quote! { Self::#variant => #event_name }
+        } else {
+            quote! { Self::#variant(..) => #event_name }
+        }
+    });
+    let doc = format!("Events emitted by `{}`", class);
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone)]
+        #vis enum #name {
+            #(#variants),*
+        }
+
+        impl crate::events::Event for #name {
+            fn name(&self) -> &'static str {
+                match *self {
+                    #(#names),*
+                }
+            }
+        }
+    }
+}
+
+/// The `events` field of an emitter struct
+pub fn bus_field(class: &str) -> TokenStream {
+    support::require("events");
+    let name = event_enum(class);
+    quote! {
+        #[serde(skip)]
+        pub events: crate::events::EventBus<#name>
+    }
+}
+
+/// The emitter `expr` refers to: `this` in an emitter class, a local typed as
+/// one, or a field holding one
+fn receiver(expr: &Expr) -> Option<(TokenStream, String)> {
+    match expr {
+        Expr::Paren(paren) => receiver(&paren.expr),
+        Expr::This(_) => {
+            let class = scope::current_class().filter(|class| is_emitter(class))?;
+            Some((quote! { self }, class))
+        }
+        Expr::Ident(ident) => {
+            let class = scope::lookup_type(&ident.sym)
+                .as_ref()
+                .and_then(type_name)
+                .filter(|class| is_emitter(class))?;
+            Some((convert_place_expr(expr), class))
+        }
+        Expr::Member(member) if member.obj.is_this() => {
+            let field = member.prop.as_ident()?.sym.to_string();
+            let class = EMITTER_FIELDS.with(|f| {
+                f.borrow()
+                    .get(&(scope::current_class()?, field.clone()))
+                    .cloned()
+            })?;
+            let field = format_ident!("{}", to_snake_case(&field));
+            Some((quote! { self.#field }, class))
+        }
+        _ => None,
+    }
+}
+
+fn event_name_arg(args: &[ExprOrSpread]) -> Option<String> {
+    match args.first().map(|arg| &*arg.expr) {
+        Some(Expr::Lit(Lit::Str(s))) => Some(s.value.to_string_lossy().into_owned()),
+        _ => {
+            diagnostics::warn("only string literal event names are supported");
+            None
+        }
+    }
+}
+
+/// `emitter.on(...)`, `emitter.emit(...)` and the other `EventEmitter` methods
+pub fn try_handle_call(call: &CallExpr) -> Option<TokenStream> {
+    let member = call.callee.as_expr()?.as_member()?;
+    let method = member.prop.as_ident()?.sym.to_string();
+    if !matches!(
+        method.as_str(),
+        "on" | "addListener"
+            | "once"
+            | "off"
+            | "removeListener"
+            | "emit"
+            | "removeAllListeners"
+            | "listenerCount"
+    ) {
+        return None;
+    }
+    let (emitter, class) = receiver(&member.obj)?;
+    let events = quote! { #emitter.events };
+    let args = &call.args;
+
+    if method == "removeAllListeners" {
+        return Some(match args.first() {
+            Some(_) => {
+                let name = event_name_arg(args)?;
+                quote! { #events.remove_all_listeners(Some(#name)) }
+            }
+            None => quote! { #events.remove_all_listeners(None) },
+        });
+    }
+
+    let name = event_name_arg(args)?;
+    let Some(event) = lookup(&class)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|event| event.name == name)
+    else {
+        diagnostics::warn(format!("`{}` never emits \"{}\"", class, name));
+        return Some(match method.as_str() {
+            "emit" => quote! { false },
+            "listenerCount" => quote! { 0.0 },
+            _ => quote! { &#events },
+        });
+    };
+
+    let code = match method.as_str() {
+        "emit" => {
+            let enum_name = event_enum(&class);
+            let variant = variant_name(&name);
+            let payload: Vec<_> = args[1..].iter().map(|arg| payload_arg(&arg.expr)).collect();
+            if payload.is_empty() {
+                quote! { #events.emit(#enum_name::#variant) }
+            } else {
+                quote! { #events.emit(#enum_name::#variant(#(#payload),*)) }
+            }
+        }
+        "listenerCount" => quote! { (#events.listener_count(#name) as f64) },
+        "off" | "removeListener" => match args.get(1).map(|arg| &*arg.expr) {
+            Some(Expr::Ident(listener)) => {
+                let listener = listener.sym.as_ref();
+                quote! { #events.off(#name, #listener) }
+            }
+            // A fresh closure is never a registered listener
+            _ => quote! { &#events },
+        },
+        _ => {
+            let register = if method == "once" {
+                quote! { once }
+            } else {
+                quote! { on }
+            };
+            let listener = &*args.get(1)?.expr;
+            let key = match listener {
+                Expr::Ident(ident) => {
+                    let key = ident.sym.as_ref();
+                    quote! { Some(#key) }
+                }
+                _ => quote! { None },
+            };
+            let listener = listener_closure(&class, &event, listener);
+            quote! { #events.#register(#name, #key, #listener) }
+        }
+    };
+    Some(code)
+}
+
+/// An emitted value, owned by the event
+fn payload_arg(expr: &Expr) -> TokenStream {
+    match expr {
+        Expr::Lit(Lit::Str(_)) => {
+            let expr = convert_expr(expr);
+            quote! { String::from(#expr) }
+        }
+        Expr::Ident(_) => {
+            let expr = convert_expr(expr);
+            quote! { #expr.clone() }
+        }
+        _ => convert_expr(expr),
+    }
+}
+
+/// A listener as a boxed closure taking the class's event enum
+fn listener_closure(class: &str, event: &EmitterEvent, listener: &Expr) -> TokenStream {
+    let enum_name = event_enum(class);
+    let variant = variant_name(&event.name);
+    let arity = event.payload.len();
+
+    let (bindings, body) = match listener {
+        Expr::Arrow(arrow) => {
+            if arrow.params.len() > arity {
+                diagnostics::warn(format!(
+                    "a listener of \"{}\" takes more than the {} emitted arguments",
+                    event.name, arity
+                ));
+            }
+            let bindings: Vec<TokenStream> = (0..arity)
+                .map(|index| match arrow.params.get(index) {
+                    Some(Pat::Ident(ident)) => {
+                        if let Some(ts_type) = &event.ts_types[index] {
+                            scope::declare(&ident.sym, ts_type);
+                        }
+                        let ident = format_ident!("{}", to_snake_case(&ident.sym));
+                        quote! { #ident }
+                    }
+                    _ => quote! { _ },
+                })
+                .collect();
+            // `?` inside the listener would return from the listener
+            let was_fallible = scope::set_fallible(false);
+            let body = match &*arrow.body {
+                BlockStmtOrExpr::BlockStmt(block) => {
+                    let stmts: Vec<_> = block.stmts.iter().map(convert_stmt).collect();
+                    quote! { #(#stmts)* }
+                }
+                BlockStmtOrExpr::Expr(expr) => {
+                    let expr = convert_expr(expr);
+                    quote! { #expr; }
+                }
+            };
+            scope::set_fallible(was_fallible);
+            (bindings, body)
+        }
+        _ => {
+            let bindings: Vec<TokenStream> = (0..arity)
+                .map(|index| {
+                    let ident = format_ident!("arg{}", index);
+                    quote! { #ident }
+                })
+                .collect();
+            let callee = convert_expr(listener);
+            (bindings.clone(), quote! { #callee(#(#bindings),*); })
+        }
+    };
+
+    let pattern = if arity == 0 {
+        quote! { #enum_name::#variant }
+    } else {
+        quote! { #enum_name::#variant(#(#bindings),*) }
+    };
+    quote! {
+        Box::new(move |event: &#enum_name| {
+            if let #pattern = event.clone() {
+                #body
+            }
+        })
+    }
+}
@@ -1,31 +1,65 @@
 use quote::{format_ident, quote};
 use swc_ecma_ast::{
     AwaitExpr, BinExpr, BinaryOp, CallExpr, Callee, Decl, Expr, ExprOrSpread, FnDecl, Lit,
-    MemberExpr, Pat, Stmt, UpdateExpr, UpdateOp,
+    MemberExpr, ModuleDecl, ModuleItem, Pat, Stmt, TsNonNullExpr, TsType, UpdateExpr, UpdateOp,
 };
 
-use super::type_mapper::{map_ts_type, unwrap_promise_type};
+use std::collections::HashSet;
+
+use swc_ecma_visit::{Visit, VisitWith};
+
+use super::scope::{self, StaticKind};
+use super::type_mapper::{is_promise_type, map_inner_type, map_ts_type, unwrap_promise_type};
 
 impl super::interface::RustGenerator {
     pub fn process_fn_decl(&mut self, n: &FnDecl) {
         let fn_name = to_snake_case(&n.ident.sym);
         let fn_ident = format_ident!("{}", fn_name);
 
-        // Check if async
-        let is_async = n.function.is_async;
+        // Check if async; functions returning a `Promise` are async in Rust
+        let is_async = n.function.is_async || is_promise_type(n.function.return_type.as_deref());
+
+        // Overload signatures have no body; remember them until the implementation
+        if n.function.body.is_none() && !n.declare && !self.is_ambient {
+            self.pending_overloads.push(n.clone());
+            return;
+        }
+        let overloads: Vec<FnDecl> = self
+            .pending_overloads
+            .iter()
+            .filter(|o| o.ident.sym == n.ident.sym)
+            .cloned()
+            .collect();
+        self.pending_overloads
+            .retain(|o| o.ident.sym != n.ident.sym);
 
         // Extract parameters
+        scope::reset();
         let mut params = Vec::new();
-        for param in &n.function.params {
-            if let Pat::Ident(ident_pat) = &param.pat {
-                let param_name = format_ident!("{}", ident_pat.sym.to_string());
-                let param_type = map_ts_type(ident_pat.type_ann.as_ref());
-                params.push(quote! { #param_name: #param_type });
+        let mut prelude = Vec::new();
+        let reassigned = reassigned_params(n.function.body.as_ref());
+        for (index, param) in n.function.params.iter().enumerate() {
+            let Some(converted) = convert_param(&param.pat) else {
+                continue;
+            };
+            declare_param(&param.pat);
+            prelude.extend(converted.prelude());
+            let ConvertedParam { name, mut ty, .. } = converted;
+
+            if !overloads.is_empty() {
+                ty = unify_overload_param(&n.ident.sym, index, ty, &overloads);
+            }
+            if is_reassigned(&param.pat, &reassigned) {
+                params.push(quote! { mut #name: #ty });
+            } else {
+                params.push(quote! { #name: #ty });
             }
         }
 
         // Extract return type - unwrap Promise<T> for async functions
-        let return_type = if is_async {
+        let return_type = if !overloads.is_empty() && !is_async {
+            unify_overload_return(&n.ident.sym, n.function.return_type.as_ref(), &overloads)
+        } else if is_async {
             if n.function.return_type.is_none() {
                 // If no return type, assume void for async functions
                 quote! { Result<(), crate::AppError> }
@@ -46,20 +80,30 @@ impl super::interface::RustGenerator {
             super::type_mapper::is_void_or_promise_void(n.function.return_type.as_deref())
         };
 
+        // Ambient declarations (`declare function`) become stubs to fill in
+        let is_stub = n.function.body.is_none() && (n.declare || self.is_ambient);
+
         // Convert body
         let mut body_stmts = Vec::new();
-        if let Some(block_stmt) = &n.function.body {
+        let was_fallible = scope::set_fallible(is_async);
+        let outer_return_type = scope::set_return_type(
+            n.function
+                .return_type
+                .as_ref()
+                .map(|type_ann| (*type_ann.type_ann).clone()),
+        );
+        if is_stub {
+            let msg = format!(
+                "`{}` is an ambient declaration; provide a Rust implementation",
+                n.ident.sym
+            );
+            crate::diagnostics::warn(msg.clone());
+            body_stmts.push(quote! { todo!(#msg) });
+        } else if let Some(block_stmt) = &n.function.body {
             if is_async {
                 // Use recursive converter to handle return Ok(...)
                 for stmt in &block_stmt.stmts {
-                    body_stmts.push(convert_stmt_recursive(stmt, &|ret_stmt| {
-                        if let Some(arg) = &ret_stmt.arg {
-                            let expr = convert_expr(arg);
-                            quote! { return Ok(#expr); }
-                        } else {
-                            quote! { return Ok(()); }
-                        }
-                    }));
+                    body_stmts.push(convert_stmt_recursive(stmt, &async_return));
                 }
 
                 if is_void {
@@ -84,7 +128,11 @@ impl super::interface::RustGenerator {
             }
         }
 
-        let vis = if self.is_exporting {
+        scope::set_fallible(was_fallible);
+        scope::set_return_type(outer_return_type);
+
+        // Members of a `declare namespace` are implicitly exported
+        let vis = if self.is_exporting || self.is_ambient {
             quote! { pub }
         } else {
             quote! {}
@@ -105,8 +153,10 @@ impl super::interface::RustGenerator {
             quote! {}
         };
 
+        let body_stmts: Vec<_> = prelude.into_iter().chain(body_stmts).collect();
+
         let fn_def = if is_async {
-            let fallback = if is_void {
+            let fallback = if is_void && !is_stub {
                 quote! { Ok(()) }
             } else {
                 quote! {}
@@ -131,6 +181,435 @@ impl super::interface::RustGenerator {
     }
 }
 
+/// `return x` in an async function: `Ok(x)`, or the outcome of a returned promise
+pub fn async_return(ret_stmt: &swc_ecma_ast::ReturnStmt) -> proc_macro2::TokenStream {
+    match &ret_stmt.arg {
+        Some(arg) => crate::stdlib::axios::return_value(arg)
+            .or_else(|| crate::stdlib::promise::return_value(arg))
+            .unwrap_or_else(|| {
+                let expr = convert_expr(arg);
+                quote! { return Ok(#expr); }
+            }),
+        None => quote! { return Ok(()); },
+    }
+}
+
+/// A function parameter after mapping optional/default/rest syntax to Rust
+pub struct ConvertedParam {
+    pub name: proc_macro2::Ident,
+    pub ty: proc_macro2::TokenStream,
+    pub kind: scope::ParamKind,
+    /// Value applied in the body when an optional parameter is `None`
+    pub default: Option<proc_macro2::TokenStream>,
+}
+
+impl ConvertedParam {
+    /// The statement starting the body that applies the default value
+    pub fn prelude(&self) -> Option<proc_macro2::TokenStream> {
+        let name = &self.name;
+        let default = self.default.as_ref()?;
+        Some(quote! { let mut #name = #name.unwrap_or_else(|| #default); })
+    }
+}
+
+/// How each of `params` is passed at call sites
+pub fn param_kinds<'a>(params: impl IntoIterator<Item = &'a Pat>) -> Vec<scope::ParamKind> {
+    params
+        .into_iter()
+        .map(|pat| {
+            convert_param(pat)
+                .map(|p| p.kind)
+                .unwrap_or(scope::ParamKind::Required)
+        })
+        .collect()
+}
+
+pub fn convert_param(pat: &Pat) -> Option<ConvertedParam> {
+    match pat {
+        Pat::Ident(ident_pat) => {
+            let name = format_ident!("{}", to_snake_case(&ident_pat.sym));
+            let mut ty = map_ts_type(ident_pat.type_ann.as_ref());
+            let kind = if ident_pat.id.optional {
+                if !super::type_mapper::is_optional_type(ident_pat.type_ann.as_deref()) {
+                    ty = quote! { Option<#ty> };
+                }
+                scope::ParamKind::Optional
+            } else {
+                scope::ParamKind::Required
+            };
+            Some(ConvertedParam {
+                name,
+                ty,
+                kind,
+                default: None,
+            })
+        }
+        Pat::Assign(assign) => {
+            let Pat::Ident(ident_pat) = &*assign.left else {
+                crate::diagnostics::warn("destructured parameters with defaults are not supported");
+                return None;
+            };
+            let name = format_ident!("{}", to_snake_case(&ident_pat.sym));
+            let ty = match &ident_pat.type_ann {
+                Some(type_ann) => map_ts_type(Some(type_ann)),
+                None => super::module::infer_expr_type(&assign.right)
+                    .unwrap_or_else(|| quote! { serde_json::Value }),
+            };
+            let default = convert_expr(&assign.right);
+            Some(ConvertedParam {
+                name,
+                ty: quote! { Option<#ty> },
+                kind: scope::ParamKind::Optional,
+                default: Some(default),
+            })
+        }
+        Pat::Rest(rest) => {
+            let Pat::Ident(ident_pat) = &*rest.arg else {
+                crate::diagnostics::warn("destructured rest parameters are not supported");
+                return None;
+            };
+            let name = format_ident!("{}", to_snake_case(&ident_pat.sym));
+            let type_ann = rest.type_ann.as_ref().or(ident_pat.type_ann.as_ref());
+            let mut ty = map_ts_type(type_ann);
+            if ty.to_string() == "serde_json :: Value" {
+                ty = quote! { Vec<serde_json::Value> };
+            }
+            Some(ConvertedParam {
+                name,
+                ty,
+                kind: scope::ParamKind::Rest,
+                default: None,
+            })
+        }
+        _ => {
+            crate::diagnostics::warn("destructured parameters are not supported");
+            None
+        }
+    }
+}
+
+/// An exported function, as the files importing it call it
+#[derive(Debug, Clone)]
+pub struct FunctionSignature {
+    pub name: String,
+    pub params: Vec<scope::ParamKind>,
+    pub return_type: Option<TsType>,
+    pub is_async: bool,
+}
+
+/// The signature of a function implementation; overload signatures do not
+/// describe the Rust function
+fn signature(decl: &FnDecl) -> Option<FunctionSignature> {
+    if decl.function.body.is_none() && !decl.declare {
+        return None;
+    }
+    Some(FunctionSignature {
+        name: decl.ident.sym.to_string(),
+        params: param_kinds(decl.function.params.iter().map(|p| &p.pat)),
+        return_type: decl
+            .function
+            .return_type
+            .as_ref()
+            .map(|return_type| (*return_type.type_ann).clone()),
+        is_async: decl.function.is_async || is_promise_type(decl.function.return_type.as_deref()),
+    })
+}
+
+fn declare_signature(name: &str, signature: &FunctionSignature) {
+    scope::declare_function(name, signature.params.clone());
+    if let Some(return_type) = &signature.return_type {
+        scope::declare_function_return(name, return_type);
+    }
+    if signature.is_async {
+        scope::declare_async_function(name);
+    }
+}
+
+/// The functions every file of the project exports. A name exported with
+/// different parameters by two files is left out, since imports are matched
+/// by name alone.
+pub fn collect_exported_functions(programs: &[swc_ecma_ast::Program]) -> Vec<FunctionSignature> {
+    let mut signatures: Vec<FunctionSignature> = Vec::new();
+    for program in programs {
+        let swc_ecma_ast::Program::Module(module) = program else {
+            continue;
+        };
+        for item in &module.body {
+            let ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) = item else {
+                continue;
+            };
+            if let Some(signature) = export.decl.as_fn_decl().and_then(signature) {
+                signatures.push(signature);
+            }
+        }
+    }
+    let ambiguous: HashSet<String> = signatures
+        .iter()
+        .filter(|a| {
+            signatures
+                .iter()
+                .any(|b| a.name == b.name && a.params != b.params)
+        })
+        .map(|signature| signature.name.clone())
+        .collect();
+    signatures.retain(|signature| !ambiguous.contains(&signature.name));
+    scope::reset();
+    signatures
+}
+
+/// Registers the parameter shape of every module-level function up front, so
+/// call sites can pass `None` for omitted optional arguments and pack rest
+/// arguments. Functions imported from other files of the project take their
+/// shape from `project`.
+pub fn collect_function_signatures(program: &swc_ecma_ast::Program, project: &[FunctionSignature]) {
+    if let swc_ecma_ast::Program::Module(module) = program {
+        for item in &module.body {
+            let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+                continue;
+            };
+            if !import
+                .src
+                .value
+                .as_str()
+                .is_some_and(|src| src.starts_with('.'))
+            {
+                continue;
+            }
+            for (local, imported) in super::module::import_bindings(import) {
+                let Some(imported) = imported else {
+                    continue;
+                };
+                if let Some(signature) = project.iter().find(|s| s.name == imported) {
+                    declare_signature(&local.sym, signature);
+                }
+            }
+        }
+    }
+    for decl in scope::top_level_decls(program) {
+        if let Some(signature) = decl.as_fn_decl().and_then(signature) {
+            declare_signature(&signature.name, &signature);
+        }
+    }
+}
+
+/// Parameters that `body` reassigns, directly or through a `Date` setter,
+/// which must be bound `mut`
+pub fn reassigned_params(body: Option<&swc_ecma_ast::BlockStmt>) -> HashSet<String> {
+    let mut finder = Reassigned::default();
+    if let Some(body) = body {
+        body.visit_with(&mut finder);
+    }
+    finder.names
+}
+
+#[derive(Default)]
+struct Reassigned {
+    names: HashSet<String>,
+}
+
+impl Visit for Reassigned {
+    fn visit_assign_expr(&mut self, assign: &swc_ecma_ast::AssignExpr) {
+        if let Some(ident) = assign.left.as_ident() {
+            self.names.insert(ident.sym.to_string());
+        }
+        assign.visit_children_with(self);
+    }
+
+    fn visit_update_expr(&mut self, update: &UpdateExpr) {
+        if let Expr::Ident(ident) = &*update.arg {
+            self.names.insert(ident.sym.to_string());
+        }
+        update.visit_children_with(self);
+    }
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Some(Expr::Member(member)) = call.callee.as_expr().map(|callee| &**callee) {
+            if let (Expr::Ident(obj), Some(prop)) = (&*member.obj, member.prop.as_ident()) {
+                if crate::stdlib::date::is_setter(&prop.sym) {
+                    self.names.insert(obj.sym.to_string());
+                }
+            }
+        }
+        call.visit_children_with(self);
+    }
+}
+
+/// Whether the plain parameter `pat` is among `reassigned`. Parameters with
+/// defaults are rebound `let mut` already.
+pub fn is_reassigned(pat: &Pat, reassigned: &HashSet<String>) -> bool {
+    match pat {
+        Pat::Ident(ident) => reassigned.contains(ident.sym.as_ref()),
+        _ => false,
+    }
+}
+
+/// Records the TS type of a parameter as it is visible inside the body
+pub fn declare_param(pat: &Pat) {
+    let ident_pat = match pat {
+        // `name?: T` is an `Option<T>`
+        Pat::Ident(ident_pat) if ident_pat.id.optional => {
+            if let Some(type_ann) = &ident_pat.type_ann {
+                scope::declare(&ident_pat.sym, &scope::nullable(&type_ann.type_ann));
+            }
+            return;
+        }
+        Pat::Ident(ident_pat) => ident_pat,
+        Pat::Assign(assign) => match &*assign.left {
+            Pat::Ident(ident_pat) => ident_pat,
+            _ => return,
+        },
+        // `...nums: number[]`, annotated on the rest pattern or its binding
+        Pat::Rest(rest) => match (&*rest.arg, &rest.type_ann) {
+            (Pat::Ident(ident_pat), Some(type_ann)) => {
+                scope::declare(&ident_pat.sym, &type_ann.type_ann);
+                return;
+            }
+            (Pat::Ident(ident_pat), None) => ident_pat,
+            _ => return,
+        },
+        _ => return,
+    };
+    if let Some(type_ann) = &ident_pat.type_ann {
+        scope::declare(&ident_pat.sym, &type_ann.type_ann);
+    }
+}
+
+/// Picks the Rust type for parameter `index` of an overloaded function. The
+/// implementation signature is usually a union (mapped to `serde_json::Value`),
+/// so the overloads' type is used when they all agree.
+fn unify_overload_param(
+    fn_name: &str,
+    index: usize,
+    impl_type: proc_macro2::TokenStream,
+    overloads: &[FnDecl],
+) -> proc_macro2::TokenStream {
+    let overload_types: Vec<String> = overloads
+        .iter()
+        .filter_map(|o| o.function.params.get(index))
+        .filter_map(|p| convert_param(&p.pat))
+        .map(|p| p.ty.to_string())
+        .collect();
+
+    let impl_str = impl_type.to_string();
+    let is_untyped = impl_str.contains("serde_json :: Value");
+    let all_agree = overload_types.windows(2).all(|w| w[0] == w[1]);
+
+    match overload_types.first() {
+        Some(first) if all_agree && is_untyped => {
+            // Optional in the implementation but required in every overload
+            let unified = if impl_str.starts_with("Option <") && !first.starts_with("Option <") {
+                format!("Option<{}>", first)
+            } else {
+                first.clone()
+            };
+            unified.parse().unwrap_or(impl_type)
+        }
+        Some(_) if !all_agree => {
+            crate::diagnostics::warn(format!(
+                "overloads of `{}` disagree on the type of parameter {} ({}); using the implementation signature",
+                fn_name,
+                index + 1,
+                overload_types.join(" vs ")
+            ));
+            impl_type
+        }
+        _ => impl_type,
+    }
+}
+
+#[allow(clippy::borrowed_box)]
+fn unify_overload_return(
+    fn_name: &str,
+    impl_return: Option<&Box<swc_ecma_ast::TsTypeAnn>>,
+    overloads: &[FnDecl],
+) -> proc_macro2::TokenStream {
+    let impl_type = if impl_return.is_none() {
+        quote! { () }
+    } else {
+        map_ts_type(impl_return)
+    };
+    let overload_types: Vec<String> = overloads
+        .iter()
+        .map(|o| map_ts_type(o.function.return_type.as_ref()).to_string())
+        .collect();
+    let all_agree = overload_types.windows(2).all(|w| w[0] == w[1]);
+
+    if !all_agree {
+        crate::diagnostics::warn(format!(
+            "overloads of `{}` return different types ({}); using the implementation signature",
+            fn_name,
+            overload_types.join(" vs ")
+        ));
+        impl_type
+    } else if impl_type.to_string().contains("serde_json :: Value") {
+        overload_types
+            .first()
+            .and_then(|t| t.parse().ok())
+            .unwrap_or(impl_type)
+    } else {
+        impl_type
+    }
+}
+
+/// Adapts call arguments to a known signature: `Some(..)`/`None` for optional
+/// parameters and a `Vec` for rest parameters.
+pub fn convert_call_args(
+    params: &[scope::ParamKind],
+    args: &[ExprOrSpread],
+) -> Vec<proc_macro2::TokenStream> {
+    let mut converted = Vec::new();
+    for (index, kind) in params.iter().enumerate() {
+        match kind {
+            scope::ParamKind::Required => {
+                if let Some(arg) = args.get(index) {
+                    converted.push(convert_expr_or_spread(arg));
+                }
+            }
+            scope::ParamKind::Optional => match args.get(index) {
+                Some(arg) if is_undefined(&arg.expr) => converted.push(quote! { None }),
+                Some(arg) => {
+                    let value = convert_expr_or_spread(arg);
+                    converted.push(quote! { Some(#value) });
+                }
+                None => converted.push(quote! { None }),
+            },
+            scope::ParamKind::Rest => {
+                let rest = args.get(index..).unwrap_or_default();
+                if let [single] = rest {
+                    if single.spread.is_some() {
+                        // f(...items) passes the array straight through
+                        let value = convert_expr(&single.expr);
+                        converted.push(quote! { #value.clone() });
+                        continue;
+                    }
+                }
+                let pushes: Vec<_> = rest
+                    .iter()
+                    .map(|arg| {
+                        let value = convert_expr(&arg.expr);
+                        if arg.spread.is_some() {
+                            quote! { rest.extend(#value.iter().cloned()); }
+                        } else {
+                            quote! { rest.push(#value); }
+                        }
+                    })
+                    .collect();
+                if rest.iter().any(|arg| arg.spread.is_some()) {
+                    converted.push(quote! { { let mut rest = Vec::new(); #(#pushes)* rest } });
+                } else {
+                    let values: Vec<_> = rest.iter().map(convert_expr_or_spread).collect();
+                    converted.push(quote! { vec![#(#values),*] });
+                }
+            }
+        }
+    }
+    converted
+}
+
+fn is_undefined(expr: &Expr) -> bool {
+    matches!(expr, Expr::Ident(ident) if ident.sym == "undefined")
+}
+
 pub fn convert_stmt_pub(stmt: &Stmt) -> proc_macro2::TokenStream {
     convert_stmt(stmt)
 }
@@ -150,6 +629,12 @@ pub fn convert_stmt(stmt: &Stmt) -> proc_macro2::TokenStream {
             }
         }
         Stmt::Expr(expr_stmt) => {
+            // `Object.freeze(config);` on its own has nothing to do in Rust
+            if crate::stdlib::object::unwrap_freeze(&expr_stmt.expr)
+                .is_some_and(|frozen| matches!(frozen, Expr::Ident(_) | Expr::Member(_)))
+            {
+                return quote! {};
+            }
             let expr = convert_expr(&expr_stmt.expr);
             quote! { #expr; }
         }
@@ -161,8 +646,114 @@ pub fn convert_stmt(stmt: &Stmt) -> proc_macro2::TokenStream {
                     let var_name = to_snake_case(&ident.id.sym);
                     let var_ident = format_ident!("{}", var_name);
 
+                    if let Some(type_ann) = &ident.type_ann {
+                        scope::declare(&ident.id.sym, &type_ann.type_ann);
+                    } else if decl.init.as_deref().is_some_and(is_untyped_value_expr) {
+                        scope::declare_untyped(&ident.id.sym);
+                    } else if decl
+                        .init
+                        .as_deref()
+                        .is_some_and(crate::stdlib::date::is_date_expr)
+                    {
+                        scope::declare(&ident.id.sym, &crate::stdlib::date::date_type());
+                    } else if let Some(flags) = decl
+                        .init
+                        .as_deref()
+                        .and_then(crate::stdlib::regexp::declared_flags)
+                    {
+                        scope::declare_regex(&ident.id.sym, &flags);
+                    } else if decl
+                        .init
+                        .as_deref()
+                        .is_some_and(crate::stdlib::fetch::is_awaited_fetch)
+                    {
+                        scope::declare(&ident.id.sym, &crate::stdlib::fetch::response_type());
+                    } else if decl
+                        .init
+                        .as_deref()
+                        .is_some_and(crate::stdlib::axios::is_create)
+                    {
+                        scope::declare(&ident.id.sym, &crate::stdlib::axios::instance_type());
+                    } else if decl
+                        .init
+                        .as_deref()
+                        .is_some_and(crate::stdlib::buffer::is_buffer_expr)
+                    {
+                        scope::declare(&ident.id.sym, &crate::stdlib::buffer::buffer_type());
+                    } else if decl
+                        .init
+                        .as_deref()
+                        .is_some_and(crate::stdlib::url::is_url_expr)
+                    {
+                        scope::declare(&ident.id.sym, &scope::type_ref("URL"));
+                    } else if decl
+                        .init
+                        .as_deref()
+                        .is_some_and(crate::stdlib::url::is_search_params_expr)
+                    {
+                        scope::declare(&ident.id.sym, &scope::type_ref("URLSearchParams"));
+                    } else if let Some(ts_type) = decl
+                        .init
+                        .as_deref()
+                        .and_then(crate::stdlib::receiver::literal_type)
+                    {
+                        // `const ids = [3, 1, 2]` routes `ids.slice(-2)` to the array handler
+                        scope::declare(&ident.id.sym, &ts_type);
+                    } else if let Some(Expr::New(new_expr)) = decl.init.as_deref() {
+                        // `const err = new NotFoundError(id)` is a `crate::AppError`,
+                        // and `const orders = new OrderService()` may be an emitter
+                        if let Some(class) = new_expr.callee.as_ident().filter(|class| {
+                            super::error::is_error_type(&class.sym)
+                                || super::events::is_emitter(&class.sym)
+                        }) {
+                            scope::declare(&ident.id.sym, &scope::type_ref(&class.sym));
+                        }
+                    }
+
+                    // `TextEncoder`s hold no state: `encoder.encode(s)` uses `s` alone
+                    if let Some(Expr::New(new_expr)) = decl
+                        .init
+                        .as_deref()
+                        .filter(|init| crate::stdlib::buffer::codec(init).is_some())
+                    {
+                        if let Some(class) = new_expr.callee.as_ident() {
+                            scope::declare(&ident.id.sym, &scope::type_ref(&class.sym));
+                        }
+                        continue;
+                    }
+
                     if let Some(init) = &decl.init {
-                        let init_expr = convert_expr(init);
+                        let init_expr = match (&ident.type_ann, &**init) {
+                            // `const user: User = JSON.parse(s)` parses straight into `User`
+                            (Some(type_ann), Expr::Call(call)) if is_json_parse_call(call) => {
+                                crate::stdlib::json::parse(&call.args, Some(&type_ann.type_ann))
+                                    .unwrap_or_else(|| convert_expr(init))
+                            }
+                            // `const data: User = await res.json()`
+                            (Some(type_ann), Expr::Await(_)) => {
+                                crate::stdlib::fetch::typed_json(init, &type_ann.type_ann)
+                                    .unwrap_or_else(|| convert_expr(init))
+                            }
+                            _ => convert_expr(init),
+                        };
+                        // `None` alone does not say what the `Option` holds
+                        if matches!(&**init, Expr::Lit(Lit::Null(_))) || is_undefined(init) {
+                            let ty = match &ident.type_ann {
+                                Some(type_ann) => map_ts_type(Some(type_ann)),
+                                None => quote! { Option<serde_json::Value> },
+                            };
+                            declarations.push(quote! {
+                                let mut #var_ident: #ty = None;
+                            });
+                            continue;
+                        }
+                        if crate::stdlib::object::unwrap_freeze(init).is_some() {
+                            // `Object.freeze` -> immutable binding
+                            declarations.push(quote! {
+                                let #var_ident = #init_expr;
+                            });
+                            continue;
+                        }
                         // Always use `let mut` for now (safe default)
                         // In the future, we could analyze reassignments
                         declarations.push(quote! {
@@ -174,6 +765,15 @@ pub fn convert_stmt(stmt: &Stmt) -> proc_macro2::TokenStream {
                             let mut #var_ident;
                         });
                     }
+                } else if let (Pat::Array(pattern), Some(init)) = (&decl.name, &decl.init) {
+                    declarations.push(convert_array_destructuring(pattern, init));
+                } else if let (Pat::Object(pattern), Some(init)) = (&decl.name, &decl.init) {
+                    match crate::stdlib::axios::destructure(pattern, init) {
+                        Some(destructured) => declarations.push(destructured),
+                        None => crate::diagnostics::warn(
+                            "object destructuring is only supported for axios responses",
+                        ),
+                    }
                 }
             }
             quote! {
@@ -189,7 +789,7 @@ pub fn convert_stmt(stmt: &Stmt) -> proc_macro2::TokenStream {
             }
         }
         Stmt::If(if_stmt) => {
-            let test = convert_expr(&if_stmt.test);
+            let test = convert_condition(&if_stmt.test);
             let cons = convert_stmt(&if_stmt.cons);
 
             let cons_block = if matches!(*if_stmt.cons, Stmt::Block(_)) {
@@ -214,10 +814,57 @@ pub fn convert_stmt(stmt: &Stmt) -> proc_macro2::TokenStream {
                 if #test #cons_block #alt
             }
         }
+        Stmt::Throw(throw_stmt) => super::error::throw(&throw_stmt.arg),
+        Stmt::Try(try_stmt) => {
+            super::error::convert_try(try_stmt, &|ret: &swc_ecma_ast::ReturnStmt| {
+                convert_stmt(&Stmt::Return(ret.clone()))
+            })
+        }
         _ => quote! { /* unsupported statement */ },
     }
 }
 
+/// `const [a, , b] = init`. Joined promises are tuples; anything else is indexed.
+fn convert_array_destructuring(
+    pattern: &swc_ecma_ast::ArrayPat,
+    init: &Expr,
+) -> proc_macro2::TokenStream {
+    let names: Vec<_> = pattern
+        .elems
+        .iter()
+        .map(|elem| match elem {
+            Some(Pat::Ident(ident)) => {
+                let name = format_ident!("{}", to_snake_case(&ident.id.sym));
+                Some(quote! { mut #name })
+            }
+            None => None,
+            Some(_) => {
+                crate::diagnostics::warn(
+                    "only plain names are supported in array destructuring patterns",
+                );
+                None
+            }
+        })
+        .collect();
+    if let Some(tuple) = crate::stdlib::promise::joined_tuple(init) {
+        let names = names
+            .iter()
+            .map(|name| name.clone().unwrap_or_else(|| quote! { _ }));
+        return quote! { let (#(#names),*) = #tuple; };
+    }
+
+    let value = convert_expr(init);
+
+    let bindings = names.iter().enumerate().filter_map(|(index, name)| {
+        let name = name.as_ref()?;
+        Some(quote! { let #name = values[#index].clone(); })
+    });
+    quote! {
+        let values = #value;
+        #(#bindings)*
+    }
+}
+
 pub fn convert_expr(expr: &Expr) -> proc_macro2::TokenStream {
     match expr {
         Expr::Bin(bin) => convert_bin_expr(bin),
@@ -228,6 +875,18 @@ pub fn convert_expr(expr: &Expr) -> proc_macro2::TokenStream {
             if name == "undefined" {
                 return quote! { None };
             }
+            if name == "NaN" {
+                return quote! { f64::NAN };
+            }
+            if name == "Infinity" {
+                return quote! { f64::INFINITY };
+            }
+            if scope::lookup_module_static(name) == Some(StaticKind::Mutex) {
+                // Copy the value out in its own statement so the guard is released
+                // before the surrounding expression might lock it again
+                let lvalue = convert_ident_lvalue(name);
+                return quote! { { let value = (#lvalue).clone(); value } };
+            }
             // If starts with uppercase, assume Class/Type and keep as is
             // If starts with lowercase, convert to snake_case (variable/function)
             if name.chars().next().is_some_and(|c| c.is_uppercase()) {
@@ -239,20 +898,7 @@ pub fn convert_expr(expr: &Expr) -> proc_macro2::TokenStream {
                 quote! { #ident_token }
             }
         }
-        Expr::Lit(lit) => {
-            // Handle literals
-            match lit {
-                Lit::Num(num) => {
-                    let value = num.value;
-                    quote! { #value }
-                }
-                Lit::Str(str_lit) => {
-                    let s = str_lit.value.as_str().unwrap_or("");
-                    quote! { String::from(#s) }
-                }
-                _ => quote! { todo!("unsupported literal") },
-            }
-        }
+        Expr::Lit(lit) => convert_lit(lit),
         Expr::Member(member) => convert_member_expr(member),
         Expr::Await(await_expr) => convert_await_expr(await_expr),
         Expr::Call(call_expr) => convert_call_expr(call_expr),
@@ -263,18 +909,269 @@ pub fn convert_expr(expr: &Expr) -> proc_macro2::TokenStream {
         Expr::Array(arr) => convert_array_lit(arr),
         Expr::Update(update) => convert_update_expr(update),
         Expr::Assign(assign) => convert_assign_expr(assign),
+        Expr::Paren(paren) => {
+            let inner = convert_expr(&paren.expr);
+            quote! { (#inner) }
+        }
+        Expr::Unary(unary) => convert_unary_expr(unary),
+        Expr::TsNonNull(non_null) => convert_non_null_expr(non_null),
+        Expr::TsAs(as_expr) => convert_type_assertion(&as_expr.expr, &as_expr.type_ann),
+        Expr::TsTypeAssertion(assertion) => {
+            convert_type_assertion(&assertion.expr, &assertion.type_ann)
+        }
+        // `satisfies` and `as const` only affect type checking
+        Expr::TsSatisfies(satisfies) => convert_expr(&satisfies.expr),
+        Expr::TsConstAssertion(assertion) => convert_expr(&assertion.expr),
+        // `err.response?.status` on a caught axios error
+        Expr::OptChain(chain) => {
+            crate::stdlib::axios::try_handle_opt_chain(chain).unwrap_or_else(|| quote! { todo!() })
+        }
+        Expr::JSXElement(_)
+        | Expr::JSXFragment(_)
+        | Expr::JSXMember(_)
+        | Expr::JSXNamespacedName(_)
+        | Expr::JSXEmpty(_) => unsupported_jsx(),
         _ => quote! { todo!() },
     }
 }
 
+fn convert_lit(lit: &Lit) -> proc_macro2::TokenStream {
+    match lit {
+        Lit::Num(num) => {
+            // Numeric separators (`1_000`) are already resolved by the parser
+            let value = num.value;
+            quote! { #value }
+        }
+        Lit::Str(str_lit) => {
+            let s = str_lit.value.as_str().unwrap_or("");
+            quote! { String::from(#s) }
+        }
+        Lit::Bool(b) => {
+            let value = b.value;
+            quote! { #value }
+        }
+        Lit::Null(_) => quote! { None },
+        Lit::BigInt(big) => match big.value.to_string().parse::<i128>() {
+            Ok(value) => {
+                let lit = proc_macro2::Literal::i128_suffixed(value);
+                quote! { #lit }
+            }
+            Err(_) => {
+                crate::diagnostics::warn(format!(
+                    "BigInt literal {}n does not fit in i128",
+                    big.value
+                ));
+                quote! { todo!("BigInt literal out of i128 range") }
+            }
+        },
+        Lit::Regex(regex) => crate::stdlib::regexp::handle_literal(regex),
+        Lit::JSXText(_) => unsupported_jsx(),
+    }
+}
+
+fn unsupported_jsx() -> proc_macro2::TokenStream {
+    crate::diagnostics::warn("JSX is not supported; use a Rust templating crate instead");
+    quote! { todo!("JSX is not supported") }
+}
+
+/// Converts an expression passed where Rust expects `&str` (patterns,
+/// separators, ...). String literals are emitted as-is instead of allocating.
+pub fn convert_str_arg(expr: &Expr) -> proc_macro2::TokenStream {
+    match expr {
+        Expr::Lit(Lit::Str(str_lit)) => {
+            let s = str_lit.value.as_str().unwrap_or("");
+            quote! { #s }
+        }
+        Expr::Tpl(tpl) if tpl.exprs.is_empty() => {
+            let s: String = tpl
+                .quasis
+                .iter()
+                .filter_map(|q| q.cooked.as_ref().and_then(|c| c.as_str()))
+                .collect();
+            quote! { #s }
+        }
+        // `&a + b` would borrow only the left operand
+        Expr::Bin(_) | Expr::Cond(_) => {
+            let tokens = convert_expr(expr);
+            quote! { &(#tokens) }
+        }
+        _ => {
+            let tokens = convert_expr(expr);
+            quote! { &#tokens }
+        }
+    }
+}
+
+/// `typerust_runtime::loose_eq(a, b)` when `==` compares a string with a number
+fn loose_eq(bin: &BinExpr) -> Option<proc_macro2::TokenStream> {
+    use crate::stdlib::receiver::{classify, Receiver};
+
+    let kinds = (classify(&bin.left)?, classify(&bin.right)?);
+    if !matches!(
+        kinds,
+        (Receiver::String, Receiver::Number) | (Receiver::Number, Receiver::String)
+    ) {
+        return None;
+    }
+    let operand = |expr: &Expr| match expr {
+        Expr::Lit(Lit::Str(s)) => {
+            let s = s.value.as_str().unwrap_or("");
+            quote! { #s }
+        }
+        _ => {
+            let tokens = convert_expr(expr);
+            quote! { &#tokens }
+        }
+    };
+    let loose_eq = crate::runtime::path("loose_eq");
+    let (left, right) = (operand(&bin.left), operand(&bin.right));
+    Some(quote! { #loose_eq(#left, #right) })
+}
+
+/// `expr` as a Rust `bool`, with JS truthiness for strings, numbers, arrays
+/// and nullable objects
+pub fn convert_condition(expr: &Expr) -> proc_macro2::TokenStream {
+    use crate::stdlib::receiver::{truthiness, Truthiness};
+
+    match expr {
+        Expr::Paren(paren) => {
+            let inner = convert_condition(&paren.expr);
+            return quote! { (#inner) };
+        }
+        Expr::Bin(bin) if matches!(bin.op, BinaryOp::LogicalAnd | BinaryOp::LogicalOr) => {
+            let left = convert_condition(&bin.left);
+            let right = convert_condition(&bin.right);
+            return if bin.op == BinaryOp::LogicalAnd {
+                quote! { #left && #right }
+            } else {
+                quote! { #left || #right }
+            };
+        }
+        _ => {}
+    }
+    let value = convert_expr(expr);
+    match truthiness(expr) {
+        Truthiness::Bool => value,
+        Truthiness::Value => {
+            let truthy = crate::runtime::path("truthy");
+            quote! { #truthy(&#value) }
+        }
+        Truthiness::Present => quote! { #value.is_some() },
+    }
+}
+
+fn convert_unary_expr(unary: &swc_ecma_ast::UnaryExpr) -> proc_macro2::TokenStream {
+    let arg = if unary.op == swc_ecma_ast::UnaryOp::Bang {
+        convert_condition(&unary.arg)
+    } else {
+        convert_expr(&unary.arg)
+    };
+    match unary.op {
+        swc_ecma_ast::UnaryOp::Minus => quote! { -#arg },
+        swc_ecma_ast::UnaryOp::Bang => quote! { !#arg },
+        swc_ecma_ast::UnaryOp::Plus => arg,
+        _ => quote! { todo!("unsupported unary operator") },
+    }
+}
+
+/// `x!` -> `x.expect(..)`, naming the asserted expression when it is a simple path
+fn convert_non_null_expr(non_null: &TsNonNullExpr) -> proc_macro2::TokenStream {
+    let inner = convert_expr(&non_null.expr);
+    match js_path(&non_null.expr) {
+        Some(path) => {
+            let msg = format!(
+                "non-null assertion failed: `{}` was null or undefined",
+                path
+            );
+            quote! { #inner.expect(#msg) }
+        }
+        None => quote! { #inner.unwrap() },
+    }
+}
+
+/// Renders `a`, `this.a.b` style expressions back to their JS spelling
+fn js_path(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Ident(ident) => Some(ident.sym.to_string()),
+        Expr::This(_) => Some("this".to_string()),
+        Expr::Member(member) => {
+            let prop = member.prop.as_ident()?;
+            Some(format!("{}.{}", js_path(&member.obj)?, prop.sym))
+        }
+        _ => None,
+    }
+}
+
+/// `x as T` / `<T>x` are erased, except when an untyped JSON value is asserted
+/// to a concrete type, which needs a real conversion in Rust.
+fn convert_type_assertion(expr: &Expr, ts_type: &TsType) -> proc_macro2::TokenStream {
+    if let Expr::Call(call) = expr {
+        if is_json_parse_call(call) {
+            if let Some(parsed) = crate::stdlib::json::parse(&call.args, Some(ts_type)) {
+                return parsed;
+            }
+        }
+    }
+
+    let inner = convert_expr(expr);
+    let target = map_inner_type(ts_type);
+
+    if !is_value_type(ts_type) && is_untyped_value_expr(expr) {
+        let value = if matches!(expr, Expr::Ident(_)) {
+            quote! { #inner.clone() }
+        } else {
+            inner
+        };
+        quote! { serde_json::from_value::<#target>(#value).unwrap() }
+    } else {
+        inner
+    }
+}
+
+fn is_value_type(ts_type: &TsType) -> bool {
+    map_inner_type(ts_type).to_string() == "serde_json :: Value"
+}
+
+/// Whether `expr` is known to produce a `serde_json::Value` in the generated code
+pub fn is_untyped_value_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(paren) => is_untyped_value_expr(&paren.expr),
+        Expr::Object(_) => true,
+        Expr::Ident(ident) => scope::lookup(&ident.sym).is_some_and(|t| is_value_type(&t)),
+        Expr::TsAs(as_expr) => {
+            is_value_type(&as_expr.type_ann) && is_untyped_value_expr(&as_expr.expr)
+        }
+        Expr::TsTypeAssertion(assertion) => {
+            is_value_type(&assertion.type_ann) && is_untyped_value_expr(&assertion.expr)
+        }
+        Expr::Call(call) => match crate::stdlib::object::unwrap_freeze(expr) {
+            Some(frozen) => is_untyped_value_expr(frozen),
+            None => is_json_parse_call(call) && call.type_args.is_none(),
+        },
+        _ => false,
+    }
+}
+
+fn is_json_parse_call(call: &CallExpr) -> bool {
+    if let Callee::Expr(callee) = &call.callee {
+        if let Expr::Member(member) = &**callee {
+            if let (Some(obj), Some(prop)) = (member.obj.as_ident(), member.prop.as_ident()) {
+                return obj.sym == "JSON" && prop.sym == "parse";
+            }
+        }
+    }
+    false
+}
+
 fn convert_assign_expr(assign: &swc_ecma_ast::AssignExpr) -> proc_macro2::TokenStream {
+    // `url.pathname = ...` goes through the `url::Url` setters
+    if let Some(assigned) = crate::stdlib::url::try_handle_assign(assign) {
+        return assigned;
+    }
     let right = convert_expr(&assign.right);
     let left = match &assign.left {
         swc_ecma_ast::AssignTarget::Simple(simple) => match simple {
-            swc_ecma_ast::SimpleAssignTarget::Ident(ident) => {
-                let name = format_ident!("{}", to_snake_case(ident.sym.as_ref()));
-                quote! { #name }
-            }
+            swc_ecma_ast::SimpleAssignTarget::Ident(ident) => convert_ident_lvalue(&ident.sym),
             swc_ecma_ast::SimpleAssignTarget::Member(member) => {
                 // Handle LHS member access (no clone)
                 let obj = convert_expr(&member.obj);
@@ -310,8 +1207,42 @@ fn convert_assign_expr(assign: &swc_ecma_ast::AssignExpr) -> proc_macro2::TokenS
     }
 }
 
+/// Converts an identifier being assigned to. Module-level `let` bindings live
+/// behind a `Mutex`, so they are written through the lock guard.
+fn convert_ident_lvalue(name: &str) -> proc_macro2::TokenStream {
+    let is_upper = name.chars().next().is_some_and(|c| c.is_uppercase());
+    let ident = if is_upper {
+        format_ident!("{}", name)
+    } else {
+        format_ident!("{}", to_snake_case(name))
+    };
+
+    if scope::lookup_module_static(name) == Some(StaticKind::Mutex) {
+        quote! { *#ident.lock().unwrap() }
+    } else {
+        quote! { #ident }
+    }
+}
+
+/// Converts the receiver of a mutating method such as `push` or `sort`, so the
+/// call changes the binding itself rather than a temporary.
+pub fn convert_place_expr(expr: &Expr) -> proc_macro2::TokenStream {
+    match expr {
+        Expr::Ident(ident)
+            if scope::lookup_module_static(&ident.sym) == Some(StaticKind::Mutex) =>
+        {
+            let place = convert_ident_lvalue(&ident.sym);
+            quote! { (#place) }
+        }
+        _ => convert_expr(expr),
+    }
+}
+
 fn convert_update_expr(update: &UpdateExpr) -> proc_macro2::TokenStream {
-    let arg = convert_expr(&update.arg);
+    let arg = match &*update.arg {
+        Expr::Ident(ident) => convert_ident_lvalue(&ident.sym),
+        other => convert_expr(other),
+    };
     match update.op {
         UpdateOp::PlusPlus => quote! { #arg += 1.0 },
         UpdateOp::MinusMinus => quote! { #arg -= 1.0 },
@@ -374,7 +1305,10 @@ fn convert_tpl_expr(tpl: &swc_ecma_ast::Tpl) -> proc_macro2::TokenStream {
         format_str.push_str(&quasi.raw);
         if i < exprs.len() {
             format_str.push_str("{}");
-            args.push(convert_expr(&exprs[i]));
+            args.push(
+                crate::stdlib::number::js_string_of(&exprs[i])
+                    .unwrap_or_else(|| convert_expr(&exprs[i])),
+            );
         }
     }
 
@@ -382,18 +1316,113 @@ fn convert_tpl_expr(tpl: &swc_ecma_ast::Tpl) -> proc_macro2::TokenStream {
 }
 
 fn convert_new_expr(new_expr: &swc_ecma_ast::NewExpr) -> proc_macro2::TokenStream {
+    if new_expr
+        .callee
+        .as_ident()
+        .is_some_and(|callee| callee.sym == "Date")
+    {
+        return crate::stdlib::date::handle_new(new_expr.args.as_deref().unwrap_or_default());
+    }
+    if new_expr
+        .callee
+        .as_ident()
+        .is_some_and(|callee| callee.sym == "RegExp")
+    {
+        return crate::stdlib::regexp::handle_new(new_expr);
+    }
+    if new_expr
+        .callee
+        .as_ident()
+        .is_some_and(|callee| callee.sym == "Promise")
+    {
+        if let Some(promise) =
+            crate::stdlib::promise::handle_new(new_expr.args.as_deref().unwrap_or_default())
+        {
+            return promise;
+        }
+    }
+
+    // `new URL(path, base)` and `new URLSearchParams(init)`
+    if let Some(url) = crate::stdlib::url::handle_new(new_expr) {
+        return url;
+    }
+
+    // `new NotFoundError(id)` / `new Error(msg)` -> `crate::AppError`
+    if let Some(error) = super::error::new_error(new_expr) {
+        return error;
+    }
+
     // Convert new Class(args) -> Class::new(args)
     let callee = convert_expr(&new_expr.callee);
-    let args = if let Some(args) = &new_expr.args {
-        args.iter().map(convert_expr_or_spread).collect()
-    } else {
-        Vec::new()
+    let args = new_expr.args.as_deref().unwrap_or_default();
+    let known_params = new_expr
+        .callee
+        .as_ident()
+        .and_then(|class| scope::lookup_class(&class.sym))
+        .map(|class| class.constructor_params);
+    let args: Vec<_> = match known_params {
+        Some(params) => convert_call_args(&params, args),
+        None => args.iter().map(convert_expr_or_spread).collect(),
     };
 
     quote! { #callee::new(#(#args),*) }
 }
 
+/// `Billing` / `Billing.Invoices` -> `billing` / `billing::invoices` for known namespaces
+fn namespace_path(expr: &Expr) -> Option<proc_macro2::TokenStream> {
+    match expr {
+        Expr::Ident(ident) if scope::is_namespace(&ident.sym) => {
+            let module = format_ident!("{}", to_snake_case(&ident.sym));
+            Some(quote! { #module })
+        }
+        Expr::Member(member) => {
+            let prop = member.prop.as_ident()?;
+            if !scope::is_namespace(&prop.sym) {
+                return None;
+            }
+            let parent = namespace_path(&member.obj)?;
+            let module = format_ident!("{}", to_snake_case(&prop.sym));
+            Some(quote! { #parent::#module })
+        }
+        _ => None,
+    }
+}
+
 fn convert_member_expr(member: &MemberExpr) -> proc_macro2::TokenStream {
+    // Handle Namespace.item -> namespace::item
+    if let Some(ns) = namespace_path(&member.obj) {
+        if let Some(prop_ident) = member.prop.as_ident() {
+            let name = prop_ident.sym.as_ref();
+            let item = if name.chars().next().is_some_and(|c| c.is_uppercase()) {
+                format_ident!("{}", name)
+            } else {
+                format_ident!("{}", to_snake_case(name))
+            };
+            return quote! { #ns::#item };
+        }
+    }
+
+    // Handle Number.MAX_SAFE_INTEGER and friends
+    if member.obj.as_ident().is_some_and(|obj| obj.sym == "Number") {
+        if let Some(constant) = member
+            .prop
+            .as_ident()
+            .and_then(|prop| crate::stdlib::number::constant(&prop.sym))
+        {
+            return constant;
+        }
+    }
+
+    // Handle process.env.X, path.sep, ...
+    if let Some(value) = crate::stdlib::try_handle_member(member) {
+        return value;
+    }
+
+    // Handle err.message, err.name and error class fields
+    if let Some(value) = super::error::try_handle_member(member) {
+        return value;
+    }
+
     // Handle this.prop -> self.prop
     if member.obj.is_this() {
         if let Some(prop_ident) = member.prop.as_ident() {
@@ -406,6 +1435,9 @@ fn convert_member_expr(member: &MemberExpr) -> proc_macro2::TokenStream {
     let obj = convert_expr(&member.obj);
 
     match &member.prop {
+        swc_ecma_ast::MemberProp::Ident(ident) if ident.sym == "length" => {
+            crate::stdlib::string::convert_length(&member.obj)
+        }
         swc_ecma_ast::MemberProp::Ident(ident) => {
             let prop = format_ident!("{}", ident.sym.as_ref().to_string());
             quote! { #obj.#prop }
@@ -425,9 +1457,64 @@ fn convert_member_expr(member: &MemberExpr) -> proc_macro2::TokenStream {
 }
 
 pub fn convert_bin_expr(bin: &BinExpr) -> proc_macro2::TokenStream {
-    let left = convert_expr(&bin.left);
+    // `err instanceof NotFoundError` matches the `crate::AppError` variant
+    if let Some(matched) = super::error::instance_of(bin) {
+        return matched;
+    }
+
+    let mut left = convert_expr(&bin.left);
     let mut right = convert_expr(&bin.right);
 
+    // `a ?? b`: nullable values are `Option`s, e.g. `process.env.PORT ?? "80"`
+    if bin.op == BinaryOp::NullishCoalescing {
+        if crate::stdlib::receiver::is_nullable(&bin.left) {
+            return quote! { #left.unwrap_or_else(|| #right) };
+        }
+        crate::diagnostics::warn(
+            "the left side of `??` is never null or undefined here; emitted it without the fallback",
+        );
+        return left;
+    }
+
+    // Dates in arithmetic (`end - start`) are their epoch milliseconds
+    if matches!(
+        bin.op,
+        BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod
+    ) {
+        if crate::stdlib::date::is_date_expr(&bin.left) {
+            left = crate::stdlib::date::to_millis(&bin.left);
+        }
+        if crate::stdlib::date::is_date_expr(&bin.right) {
+            right = crate::stdlib::date::to_millis(&bin.right);
+        }
+    }
+
+    // `"5" == 5` converts the string to a number first
+    if matches!(bin.op, BinaryOp::EqEq | BinaryOp::NotEq) {
+        if let Some(equal) = loose_eq(bin) {
+            return if bin.op == BinaryOp::EqEq {
+                equal
+            } else {
+                quote! { !#equal }
+            };
+        }
+    }
+
+    // `String` compares directly against `&str`, no need to allocate the literal
+    if matches!(
+        bin.op,
+        BinaryOp::EqEq | BinaryOp::EqEqEq | BinaryOp::NotEq | BinaryOp::NotEqEq
+    ) {
+        if let Expr::Lit(Lit::Str(s)) = &*bin.left {
+            let s = s.value.as_str().unwrap_or("");
+            left = quote! { #s };
+        }
+        if let Expr::Lit(Lit::Str(s)) = &*bin.right {
+            let s = s.value.as_str().unwrap_or("");
+            right = quote! { #s };
+        }
+    }
+
     if bin.op == BinaryOp::Add {
         // Check if left is string
         let mut is_left_string = false;
@@ -450,12 +1537,21 @@ pub fn convert_bin_expr(bin: &BinExpr) -> proc_macro2::TokenStream {
                     }
                 }
             }
-        } else if let Expr::Lit(Lit::Str(_)) = left_expr {
+        }
+        // `String(a) + b`, `name.trim() + b`, `label + API_URL + b`
+        if crate::stdlib::string::is_string_expr(left_expr) {
             is_left_string = true;
         }
 
         let mut handled = false;
 
+        // `String + "literal"` needs no allocation for the literal
+        if let Expr::Lit(Lit::Str(s)) = &*bin.right {
+            let s = s.value.as_str().unwrap_or("");
+            right = quote! { #s };
+            handled = true;
+        }
+
         // Heuristic: If right side is a string method call, borrow it to allow String + &String
         if let Expr::Call(call) = &*bin.right {
             if let Callee::Expr(callee_expr) = &call.callee {
@@ -464,7 +1560,9 @@ pub fn convert_bin_expr(bin: &BinExpr) -> proc_macro2::TokenStream {
                         let method_name = ident.sym.as_ref();
                         match method_name {
                             "toString" | "toUpperCase" | "toLowerCase" | "trim" | "replace"
-                            | "join" | "repeat" | "slice" | "substring" | "substr" => {
+                            | "join" | "repeat" | "slice" | "substring" | "substr"
+                            | "replaceAll" | "padStart" | "padEnd" | "charAt" | "toFixed"
+                            | "digest" => {
                                 right = quote! { &#right };
                                 handled = true;
                             }
@@ -475,6 +1573,15 @@ pub fn convert_bin_expr(bin: &BinExpr) -> proc_macro2::TokenStream {
             }
         }
 
+        // `err.message`, error fields and `url.pathname` are `String`s
+        if !handled
+            && (super::error::is_error_member(&bin.right)
+                || crate::stdlib::url::is_url_property(&bin.right))
+        {
+            right = quote! { &#right };
+            handled = true;
+        }
+
         if !handled {
             if is_left_string {
                 let mut right_expr = &*bin.right;
@@ -482,18 +1589,22 @@ pub fn convert_bin_expr(bin: &BinExpr) -> proc_macro2::TokenStream {
                     right_expr = &p.expr;
                 }
 
-                // If right is LitNum, .to_string().
-                if let Expr::Lit(Lit::Num(_)) = right_expr {
-                    right = quote! { &#right.to_string() };
-                }
-                // If right is a call (e.g. round), .to_string().
-                else if let Expr::Call(_) = right_expr {
-                    right = quote! { &#right.to_string() };
-                }
-                // If right is Ident, borrow it.
-                else if let Expr::Ident(_) = right_expr {
+                // `"n = " + n` formats the number as JS does
+                if let Some(text) = crate::stdlib::number::js_string_of(right_expr) {
+                    right = quote! { &#text };
+                } else if crate::stdlib::string::is_string_expr(right_expr)
+                    || (crate::stdlib::receiver::classify(right_expr)
+                        == Some(crate::stdlib::receiver::Receiver::String)
+                        && !crate::stdlib::receiver::is_nullable(right_expr))
+                {
                     right = quote! { &#right };
                 }
+                // Anything else (`"status " + res.status`, `label + ok`) is
+                // converted the way JS converts it to a string
+                else {
+                    let to_js_string = crate::runtime::path("ToJsString");
+                    right = quote! { &#to_js_string::to_js_string(&(#right)) };
+                }
             } else if let Expr::Ident(_) = &*bin.right {
                 // Heuristic: If right side is an identifier, borrow it.
                 right = quote! { &#right };
@@ -539,7 +1650,7 @@ where
             }
         }
         Stmt::If(if_stmt) => {
-            let test = convert_expr(&if_stmt.test);
+            let test = convert_condition(&if_stmt.test);
             let cons = convert_stmt_recursive(&if_stmt.cons, handler);
             let cons_block = if matches!(*if_stmt.cons, Stmt::Block(_)) {
                 quote! { #cons }
@@ -563,6 +1674,7 @@ where
                 if #test #cons_block #alt
             }
         }
+        Stmt::Try(try_stmt) => super::error::convert_try(try_stmt, handler),
         // TODO: Add loops if needed. For now, delegate to convert_stmt for others,
         // BUT convert_stmt won't recurse with handler.
         // So we should implement loops here if we expect returns inside loops.
@@ -572,81 +1684,55 @@ where
 }
 
 fn convert_await_expr(await_expr: &AwaitExpr) -> proc_macro2::TokenStream {
-    let arg = convert_expr(&await_expr.arg);
-    quote! { #arg.await? }
+    crate::stdlib::promise::await_expr(&await_expr.arg)
 }
 
 fn convert_call_expr(call: &CallExpr) -> proc_macro2::TokenStream {
     let callee = &call.callee;
     let args = &call.args;
 
-    // Handle JSON.stringify
+    // Functions inside a namespace are plain calls, never stdlib methods
     if let Callee::Expr(expr) = callee {
         if let Expr::Member(member) = &**expr {
-            if let Expr::Ident(obj) = &*member.obj {
-                if obj.sym == "JSON" {
-                    if let Some(prop) = member.prop.as_ident() {
-                        if prop.sym == "stringify" {
-                            if let Some(arg) = args.first() {
-                                let val = convert_expr_or_spread(arg);
-                                return quote! { serde_json::to_string(&#val).unwrap() };
-                            }
-                        } else if prop.sym == "parse" {
-                            if let Some(arg) = args.first() {
-                                let val = convert_expr_or_spread(arg);
-                                return quote! { serde_json::from_str::<serde_json::Value>(&#val).unwrap() };
-                            }
-                        }
-                    }
-                }
+            if namespace_path(&member.obj).is_some() {
+                let callee = convert_member_expr(member);
+                let args: Vec<_> = args.iter().map(convert_expr_or_spread).collect();
+                return quote! { #callee(#(#args),*) };
             }
         }
     }
 
-    // Handle axios.get<T>(...)
-    if let Callee::Expr(expr) = callee {
-        if let Expr::Member(member) = &**expr {
-            if let Expr::Ident(obj) = &*member.obj {
-                if obj.sym == "axios" {
-                    if let Some(prop) = member.prop.as_ident() {
-                        if prop.sym == "get" {
-                            if let Some(arg) = args.first() {
-                                let url = convert_expr_or_spread(arg);
-                                // Handle generics: axios.get<T>(...)
-                                let generic_type = if let Some(type_params) = &call.type_args {
-                                    if let Some(param) = type_params.params.first() {
-                                        let t = super::type_mapper::map_inner_type(param);
-                                        Some(t)
-                                    } else {
-                                        None
-                                    }
-                                } else {
-                                    None
-                                };
-
-                                if let Some(t) = generic_type {
-                                    return quote! {
-                                        reqwest::Client::new().get(#url).send().await?.json::<#t>()
-                                    };
-                                } else {
-                                    return quote! {
-                                        reqwest::Client::new().get(#url).send().await?
-                                    };
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    // `JSON.parse<T>(s)`
+    if is_json_parse_call(call) {
+        let target = call
+            .type_args
+            .as_ref()
+            .and_then(|type_args| type_args.params.first());
+        if let Some(parsed) = crate::stdlib::json::parse(args, target.map(|t| &**t)) {
+            return parsed;
         }
     }
 
+    // `fetch(url, init)` and the `Response` API
+    if let Some(fetched) = crate::stdlib::fetch::try_handle_call(call) {
+        return fetched;
+    }
+
+    // `axios.get(...)`, `axios.create(config)` and instance requests
+    if let Some(request) = crate::stdlib::axios::try_handle_call(call) {
+        return request;
+    }
+
+    // `emitter.on("name", listener)`, `this.emit("name", payload)`
+    if let Some(handled) = super::events::try_handle_call(call) {
+        return handled;
+    }
+
     // Try stdlib handlers first
     if let Some(stdlib_code) = crate::stdlib::try_handle_stdlib_call(&call.callee, &call.args) {
         return stdlib_code;
     }
 
-    // Check for axios calls (axios.get, axios.post, etc.)
     if let Callee::Expr(expr) = &call.callee {
         if let Expr::Member(member) = &**expr {
             // Try stdlib method call
@@ -658,24 +1744,6 @@ fn convert_call_expr(call: &CallExpr) -> proc_macro2::TokenStream {
                     return stdlib_code;
                 }
             }
-
-            // Check if object is "axios"
-            if let Expr::Ident(obj_ident) = &*member.obj {
-                if obj_ident.sym == "axios" {
-                    // Get the HTTP method
-                    if let Some(method_ident) = member.prop.as_ident() {
-                        let method = method_ident.sym.to_string();
-                        return convert_axios_call(&method, &call.args);
-                    }
-                }
-            }
-        }
-
-        // Check for fetch calls
-        if let Expr::Ident(ident) = &**expr {
-            if ident.sym == "fetch" {
-                return convert_fetch_call(&call.args);
-            }
         }
     }
 
@@ -685,52 +1753,20 @@ fn convert_call_expr(call: &CallExpr) -> proc_macro2::TokenStream {
         _ => quote! { unknown_callee },
     };
 
-    let args: Vec<_> = call.args.iter().map(convert_expr_or_spread).collect();
+    let known_params = match &call.callee {
+        Callee::Expr(expr) => expr
+            .as_ident()
+            .and_then(|ident| scope::lookup_function(&ident.sym)),
+        _ => None,
+    };
+    let args: Vec<_> = match known_params {
+        Some(params) => convert_call_args(&params, &call.args),
+        None => call.args.iter().map(convert_expr_or_spread).collect(),
+    };
 
     quote! { #callee(#(#args),*) }
 }
 
-fn convert_axios_call(method: &str, args: &[ExprOrSpread]) -> proc_macro2::TokenStream {
-    let method_lower = method.to_lowercase();
-    let method_ident = format_ident!("{}", method_lower);
-
-    if args.is_empty() {
-        return quote! { reqwest::Client::new().#method_ident("").send().await? };
-    }
-
-    // First argument is the URL
-    let url = convert_expr_or_spread(&args[0]);
-
-    // For POST/PUT, second argument might be data
-    if (method_lower == "post" || method_lower == "put") && args.len() > 1 {
-        let data = convert_expr_or_spread(&args[1]);
-        quote! {
-            reqwest::Client::new()
-            .#method_ident(#url)
-            .json(&#data)
-            .send()
-            .await?
-        }
-    } else {
-        // GET/DELETE or POST/PUT without body
-        quote! {
-            reqwest::Client::new()
-            .#method_ident(#url)
-            .send()
-            .await?
-        }
-    }
-}
-
-fn convert_fetch_call(args: &[ExprOrSpread]) -> proc_macro2::TokenStream {
-    if args.is_empty() {
-        return quote! { reqwest::get("") };
-    }
-
-    let url = convert_expr_or_spread(&args[0]);
-    quote! { reqwest::get(#url) }
-}
-
 fn convert_arrow_expr(arrow: &swc_ecma_ast::ArrowExpr) -> proc_macro2::TokenStream {
     let params = &arrow.params;
     let body = &arrow.body;
@@ -747,6 +1783,45 @@ fn convert_arrow_expr(arrow: &swc_ecma_ast::ArrowExpr) -> proc_macro2::TokenStre
         })
         .collect();
 
+    // An async arrow is a closure returning an `async` block, which can use `?`
+    if arrow.is_async {
+        let was_fallible = scope::set_fallible(true);
+        let outer_return_type = scope::set_return_type(
+            arrow
+                .return_type
+                .as_ref()
+                .map(|type_ann| (*type_ann.type_ann).clone()),
+        );
+        let body_code = match &**body {
+            swc_ecma_ast::BlockStmtOrExpr::BlockStmt(block) => {
+                let stmts: Vec<_> = block
+                    .stmts
+                    .iter()
+                    .map(|stmt| convert_stmt_recursive(stmt, &async_return))
+                    .collect();
+                let fallback = if matches!(block.stmts.last(), Some(Stmt::Return(_))) {
+                    quote! {}
+                } else {
+                    quote! { Ok::<_, crate::AppError>(()) }
+                };
+                quote! { #(#stmts)* #fallback }
+            }
+            swc_ecma_ast::BlockStmtOrExpr::Expr(expr) => {
+                let value = if crate::stdlib::promise::is_promise_expr(expr) {
+                    crate::stdlib::promise::await_expr(expr)
+                } else {
+                    convert_expr(expr)
+                };
+                quote! { Ok::<_, crate::AppError>(#value) }
+            }
+        };
+        scope::set_fallible(was_fallible);
+        scope::set_return_type(outer_return_type);
+        return quote! { move |#(#param_idents),*| async move { #body_code } };
+    }
+
+    // `?` inside a closure would return from the closure, not the function
+    let was_fallible = scope::set_fallible(false);
     let body_code = match &**body {
         swc_ecma_ast::BlockStmtOrExpr::BlockStmt(block) => {
             let stmts: Vec<_> = block.stmts.iter().map(convert_stmt).collect();
@@ -757,15 +1832,7 @@ fn convert_arrow_expr(arrow: &swc_ecma_ast::ArrowExpr) -> proc_macro2::TokenStre
             quote! { #expr_code }
         }
     };
-
-    let is_async = arrow.is_async;
-    let _async_kw = if is_async {
-        quote! { async }
-    } else {
-        quote! {}
-    };
-    // Rust closures don't support async easily without blocks, but for map/filter they are usually sync.
-    // If async, we might need `async move`.
+    scope::set_fallible(was_fallible);
 
     quote! { |#(#param_idents),*| #body_code }
 }
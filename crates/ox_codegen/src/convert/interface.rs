@@ -1,7 +1,11 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
 use quote::{format_ident, quote};
 use swc_ecma_ast::{TsInterfaceDecl, TsTypeElement};
 use swc_ecma_visit::{Visit, VisitWith};
 
+use super::scope;
 use super::type_mapper::map_ts_type;
 
 use crate::ControllerMetadata;
@@ -13,6 +17,13 @@ pub struct RustGenerator {
     pub is_index: bool,
     pub controllers: Vec<ControllerMetadata>,
     pub main_body: String,
+    /// Inside a `declare namespace`, where declarations have no implementation
+    pub is_ambient: bool,
+    /// Overload signatures waiting for their implementation
+    pub pending_overloads: Vec<swc_ecma_ast::FnDecl>,
+    /// Modules emitted for namespaces, by name: where their body sits in
+    /// `code`, and the generator that produced it
+    pub namespaces: HashMap<String, (Range<usize>, RustGenerator)>,
 }
 
 impl RustGenerator {
@@ -23,10 +34,37 @@ impl RustGenerator {
             is_index,
             controllers: Vec::new(),
             main_body: String::new(),
+            is_ambient: false,
+            pending_overloads: Vec::new(),
+            namespaces: HashMap::new(),
         }
     }
 }
 
+/// Records the fields of every top-level interface, so `Object.keys(user)`
+/// can list them without reflection.
+pub fn collect_struct_fields(program: &swc_ecma_ast::Program) {
+    for decl in scope::top_level_decls(program) {
+        let swc_ecma_ast::Decl::TsInterface(interface) = decl else {
+            continue;
+        };
+        let fields = interface
+            .body
+            .body
+            .iter()
+            .filter_map(|member| match member {
+                TsTypeElement::TsPropertySignature(prop) => Some(scope::StructField {
+                    name: prop.key.as_ident()?.sym.to_string(),
+                    ts_type: prop.type_ann.as_ref()?.type_ann.as_ref().clone(),
+                    optional: prop.optional,
+                }),
+                _ => None,
+            })
+            .collect();
+        scope::declare_struct(&interface.id.sym, fields);
+    }
+}
+
 impl Visit for RustGenerator {
     fn visit_ts_interface_decl(&mut self, n: &TsInterfaceDecl) {
         let interface_name = n.id.sym.to_string();
@@ -110,6 +148,15 @@ impl Visit for RustGenerator {
         self.code.push('\n');
     }
 
+    fn visit_ts_module_decl(&mut self, n: &swc_ecma_ast::TsModuleDecl) {
+        self.process_ts_module_decl(n);
+    }
+
+    fn visit_var_decl(&mut self, n: &swc_ecma_ast::VarDecl) {
+        // Only reached for `export const`/`export let`; other statements go through visit_stmt
+        self.process_module_var_decl(n);
+    }
+
     fn visit_module_item(&mut self, n: &swc_ecma_ast::ModuleItem) {
         self.process_module_item(n);
     }
@@ -121,10 +168,17 @@ impl Visit for RustGenerator {
             | swc_ecma_ast::Stmt::Decl(swc_ecma_ast::Decl::Class(_))
             | swc_ecma_ast::Stmt::Decl(swc_ecma_ast::Decl::TsInterface(_))
             | swc_ecma_ast::Stmt::Decl(swc_ecma_ast::Decl::TsTypeAlias(_))
-            | swc_ecma_ast::Stmt::Decl(swc_ecma_ast::Decl::TsEnum(_)) => {
+            | swc_ecma_ast::Stmt::Decl(swc_ecma_ast::Decl::TsEnum(_))
+            | swc_ecma_ast::Stmt::Decl(swc_ecma_ast::Decl::TsModule(_)) => {
                 // Top-level declarations: let visitor handle them (writes to self.code)
                 n.visit_children_with(self);
             }
+            swc_ecma_ast::Stmt::Decl(swc_ecma_ast::Decl::Var(var))
+                if !self.is_index || var.declare =>
+            {
+                // Module-level bindings outside index.ts become consts/statics
+                self.process_module_var_decl(var);
+            }
             _ => {
                 // Script statements (ExprStmt, VarDecl, If, Loop, etc.): write to self.main_body
                 let stmt_code = super::func::convert_stmt(n);
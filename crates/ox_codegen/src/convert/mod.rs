@@ -1,5 +1,9 @@
 pub mod class;
+pub mod error;
+pub mod events;
 pub mod func;
 pub mod interface;
 pub mod module;
+pub mod namespace;
+pub mod scope;
 pub mod type_mapper;
@@ -1,7 +1,16 @@
-use swc_ecma_ast::{ModuleDecl, ModuleItem};
+use std::collections::HashSet;
+
+use quote::{format_ident, quote};
+use swc_ecma_ast::{
+    BinaryOp, Decl, Expr, Lit, ModuleDecl, ModuleItem, Pat, Program, Stmt, TsType, UnaryOp,
+    VarDecl, VarDeclKind,
+};
 use swc_ecma_visit::VisitWith;
 
+use super::func::{convert_expr, to_snake_case};
 use super::interface::RustGenerator;
+use super::scope::{self, StaticKind};
+use super::type_mapper::{map_inner_type, map_ts_type};
 
 impl RustGenerator {
     pub fn process_module_item(&mut self, n: &ModuleItem) {
@@ -85,6 +94,33 @@ impl RustGenerator {
             return; // Changed from `continue` to `return` as it's a function
         }
 
+        // `EventEmitter` subclasses get a `crate::events::EventBus`
+        if super::events::is_events_module(src_value) {
+            return;
+        }
+
+        // Calls into a mapped library are translated by its `StdlibMapping`
+        if crate::stdlib::registry::is_mapped_module(src_value) {
+            for (local, member) in import_bindings(n) {
+                scope::declare_mapped_import(
+                    &local.sym,
+                    scope::MappedImport {
+                        module: src_value.to_string(),
+                        member,
+                    },
+                );
+            }
+            return;
+        }
+
+        // Node built-ins are shimmed by `stdlib`, so no `use` is emitted
+        if let Some(module) = node_module(src_value) {
+            for (local, member) in import_bindings(n) {
+                scope::declare_node_import(&local.sym, scope::NodeImport { module, member });
+            }
+            return;
+        }
+
         // Path resolution
         let module_path = if src_value.starts_with("./") {
             let path_str = src_value.trim_start_matches("./");
@@ -121,6 +157,11 @@ impl RustGenerator {
                         named.local.sym.to_string()
                     };
 
+                    // Error classes are variants of `crate::AppError`, not items
+                    if super::error::lookup(&imported_name).is_some() {
+                        continue;
+                    }
+
                     // Apply casing logic to imported name
                     // If starts with Uppercase, keep it (Class/Type)
                     // If lowercase, convert to snake_case (Function/Var)
@@ -153,6 +194,7 @@ impl RustGenerator {
 
                     self.code.push_str(&use_stmt);
                     self.code.push('\n');
+                    self.import_event_enum(&module_path, &imported_name, &local_rust_name);
                 }
                 swc_ecma_ast::ImportSpecifier::Default(default) => {
                     let local_name = default.local.sym.to_string();
@@ -173,6 +215,7 @@ impl RustGenerator {
                     let use_stmt = format!("use {}::{};", module_path, local_rust_name);
                     self.code.push_str(&use_stmt);
                     self.code.push('\n');
+                    self.import_event_enum(&module_path, &local_name, &local_rust_name);
                 }
                 swc_ecma_ast::ImportSpecifier::Namespace(ns) => {
                     let local_name = ns.local.sym.to_string();
@@ -189,4 +232,424 @@ impl RustGenerator {
             }
         }
     }
+
+    /// An emitter class from another file comes with its event enum, which
+    /// `emit` and listeners name
+    fn import_event_enum(&mut self, module_path: &str, imported: &str, local: &str) {
+        if !super::events::is_emitter(imported) {
+            return;
+        }
+        let use_stmt = if imported == local {
+            format!("use {}::{}Event;\n", module_path, imported)
+        } else {
+            format!(
+                "use {}::{}Event as {}Event;\n",
+                module_path, imported, local
+            )
+        };
+        self.code.push_str(&use_stmt);
+    }
+}
+
+/// The local names an import binds, each with the export it imports (`None`
+/// for the module object of default and namespace imports)
+pub fn import_bindings(
+    n: &swc_ecma_ast::ImportDecl,
+) -> Vec<(&swc_ecma_ast::Ident, Option<String>)> {
+    n.specifiers
+        .iter()
+        .map(|specifier| match specifier {
+            swc_ecma_ast::ImportSpecifier::Named(named) => {
+                let imported = match &named.imported {
+                    Some(swc_ecma_ast::ModuleExportName::Ident(ident)) => ident.sym.to_string(),
+                    Some(swc_ecma_ast::ModuleExportName::Str(s)) => {
+                        s.value.to_string_lossy().into_owned()
+                    }
+                    None => named.local.sym.to_string(),
+                };
+                (&named.local, Some(imported))
+            }
+            swc_ecma_ast::ImportSpecifier::Default(default) => (&default.local, None),
+            swc_ecma_ast::ImportSpecifier::Namespace(ns) => (&ns.local, None),
+        })
+        .collect()
+}
+
+/// An exported module-level binding, as the files importing it access it
+#[derive(Debug, Clone)]
+pub struct ModuleStatic {
+    pub name: String,
+    pub kind: StaticKind,
+    pub ts_type: Option<TsType>,
+}
+
+/// The `const`/`let` bindings every file of the project exports. A name
+/// exported as different kinds by two files is left out, since imports are
+/// matched by name alone.
+pub fn collect_exported_statics(programs: &[Program]) -> Vec<ModuleStatic> {
+    let mut statics: Vec<ModuleStatic> = Vec::new();
+    for program in programs {
+        let Program::Module(module) = program else {
+            continue;
+        };
+        for item in &module.body {
+            let ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) = item else {
+                continue;
+            };
+            let Decl::Var(var) = &export.decl else {
+                continue;
+            };
+            for decl in var.decls.iter().filter(|_| !var.declare) {
+                if let Pat::Ident(ident) = &decl.name {
+                    statics.push(ModuleStatic {
+                        name: ident.id.sym.to_string(),
+                        kind: static_kind(var.kind, decl.init.as_deref()),
+                        ts_type: binding_type(ident, decl.init.as_deref()),
+                    });
+                }
+            }
+        }
+    }
+    let ambiguous: HashSet<String> = statics
+        .iter()
+        .filter(|a| statics.iter().any(|b| a.name == b.name && a.kind != b.kind))
+        .map(|binding| binding.name.clone())
+        .collect();
+    statics.retain(|binding| !ambiguous.contains(&binding.name));
+    scope::reset();
+    statics
+}
+
+/// Registers module-level `const`/`let` bindings up front, so that functions
+/// declared before them already know how to access them. Bindings imported
+/// from other files of the project take their kind from `project`, so an
+/// imported `let` is read and written through its lock too.
+pub fn collect_module_statics(program: &Program, is_index: bool, project: &[ModuleStatic]) {
+    if let Program::Module(module) = program {
+        for item in &module.body {
+            let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+                continue;
+            };
+            if !import
+                .src
+                .value
+                .as_str()
+                .is_some_and(|src| src.starts_with('.'))
+            {
+                continue;
+            }
+            for (local, imported) in import_bindings(import) {
+                let Some(binding) = imported
+                    .and_then(|imported| project.iter().find(|binding| binding.name == imported))
+                else {
+                    continue;
+                };
+                scope::declare_module_static(&local.sym, binding.kind);
+                if let Some(ts_type) = &binding.ts_type {
+                    scope::declare_module_type(&local.sym, ts_type);
+                }
+            }
+        }
+    }
+
+    let vars: Vec<&VarDecl> = match program {
+        Program::Module(module) => module
+            .body
+            .iter()
+            .filter_map(|item| match item {
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => match &export.decl {
+                    Decl::Var(var) if !var.declare => Some(&**var),
+                    _ => None,
+                },
+                // In index.ts, non-exported bindings belong to the generated `main`
+                ModuleItem::Stmt(Stmt::Decl(Decl::Var(var))) if !is_index && !var.declare => {
+                    Some(&**var)
+                }
+                _ => None,
+            })
+            .collect(),
+        Program::Script(script) => script
+            .body
+            .iter()
+            .filter_map(|stmt| match stmt {
+                Stmt::Decl(Decl::Var(var)) if !is_index && !var.declare => Some(&**var),
+                _ => None,
+            })
+            .collect(),
+    };
+
+    for var in vars {
+        for decl in &var.decls {
+            if let Pat::Ident(ident) = &decl.name {
+                let kind = static_kind(var.kind, decl.init.as_deref());
+                scope::declare_module_static(&ident.id.sym, kind);
+                if let Some(flags) = decl
+                    .init
+                    .as_deref()
+                    .and_then(crate::stdlib::regexp::declared_flags)
+                {
+                    scope::declare_module_regex(&ident.id.sym, &flags);
+                }
+                declare_binding_type(ident, decl.init.as_deref());
+            }
+        }
+    }
+
+    // The other bindings of index.ts are locals of `main`, converted between
+    // functions that reset the locals; keep their types at module level
+    if is_index {
+        let stmts: Vec<&Stmt> = match program {
+            Program::Module(module) => module
+                .body
+                .iter()
+                .filter_map(|item| item.as_stmt())
+                .collect(),
+            Program::Script(script) => script.body.iter().collect(),
+        };
+        for stmt in stmts {
+            let Stmt::Decl(Decl::Var(var)) = stmt else {
+                continue;
+            };
+            for decl in &var.decls {
+                if let Pat::Ident(ident) = &decl.name {
+                    declare_binding_type(ident, decl.init.as_deref());
+                }
+            }
+        }
+    }
+}
+
+/// The type of a module-level binding: an axios instance, its annotation, or
+/// the type of its literal initializer
+fn binding_type(ident: &swc_ecma_ast::BindingIdent, init: Option<&Expr>) -> Option<TsType> {
+    if init.is_some_and(crate::stdlib::axios::is_create) {
+        Some(crate::stdlib::axios::instance_type())
+    } else if let Some(type_ann) = &ident.type_ann {
+        Some((*type_ann.type_ann).clone())
+    } else {
+        init.and_then(crate::stdlib::receiver::literal_type)
+    }
+}
+
+fn declare_binding_type(ident: &swc_ecma_ast::BindingIdent, init: Option<&Expr>) {
+    if let Some(ts_type) = binding_type(ident, init) {
+        scope::declare_module_type(&ident.id.sym, &ts_type);
+    }
+}
+
+/// The Node built-in an import source refers to, with or without `node:`
+fn node_module(src: &str) -> Option<scope::NodeModule> {
+    match src.strip_prefix("node:").unwrap_or(src) {
+        "fs" => Some(scope::NodeModule::Fs),
+        "fs/promises" => Some(scope::NodeModule::FsPromises),
+        "path" => Some(scope::NodeModule::Path),
+        "process" => Some(scope::NodeModule::Process),
+        "crypto" => Some(scope::NodeModule::Crypto),
+        _ => None,
+    }
+}
+
+fn static_kind(kind: VarDeclKind, init: Option<&Expr>) -> StaticKind {
+    if kind != VarDeclKind::Const {
+        StaticKind::Mutex
+    } else if init.is_some_and(is_const_evaluable) {
+        StaticKind::Const
+    } else {
+        StaticKind::Lazy
+    }
+}
+
+/// Whether an initializer can be written in a Rust `const` item
+fn is_const_evaluable(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(Lit::Num(_) | Lit::Str(_) | Lit::Bool(_)) => true,
+        Expr::Tpl(tpl) => tpl.exprs.is_empty(),
+        Expr::Paren(paren) => is_const_evaluable(&paren.expr),
+        Expr::TsConstAssertion(assertion) => is_const_evaluable(&assertion.expr),
+        Expr::Unary(unary) => {
+            matches!(unary.op, UnaryOp::Minus | UnaryOp::Bang) && is_const_evaluable(&unary.arg)
+        }
+        Expr::Bin(bin) => {
+            // `"a" + "b"` would need `concat!`, only allow numeric arithmetic
+            matches!(
+                bin.op,
+                BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div
+            ) && is_numeric_const(&bin.left)
+                && is_numeric_const(&bin.right)
+        }
+        _ => false,
+    }
+}
+
+fn is_numeric_const(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(Lit::Num(_)) => true,
+        Expr::Paren(paren) => is_numeric_const(&paren.expr),
+        Expr::Unary(unary) => unary.op == UnaryOp::Minus && is_numeric_const(&unary.arg),
+        Expr::Bin(bin) => {
+            matches!(
+                bin.op,
+                BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div
+            ) && is_numeric_const(&bin.left)
+                && is_numeric_const(&bin.right)
+        }
+        _ => false,
+    }
+}
+
+/// Best-effort Rust type for an unannotated binding, from its initializer
+pub fn infer_expr_type(expr: &Expr) -> Option<proc_macro2::TokenStream> {
+    match expr {
+        Expr::Lit(Lit::Num(_)) => Some(quote! { f64 }),
+        Expr::Lit(Lit::Str(_)) | Expr::Tpl(_) => Some(quote! { String }),
+        Expr::Lit(Lit::Bool(_)) => Some(quote! { bool }),
+        Expr::Paren(paren) => infer_expr_type(&paren.expr),
+        Expr::Call(_) if crate::stdlib::axios::is_create(expr) => {
+            crate::support::require("axios");
+            Some(quote! { crate::axios::Instance })
+        }
+        Expr::Call(_) => infer_expr_type(crate::stdlib::object::unwrap_freeze(expr)?),
+        Expr::TsConstAssertion(assertion) => infer_expr_type(&assertion.expr),
+        Expr::TsAs(as_expr) => Some(map_inner_type(&as_expr.type_ann)),
+        Expr::Unary(unary) => match unary.op {
+            UnaryOp::Minus => Some(quote! { f64 }),
+            UnaryOp::Bang => Some(quote! { bool }),
+            _ => None,
+        },
+        Expr::Bin(bin) => match bin.op {
+            BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => Some(quote! { f64 }),
+            BinaryOp::Add => infer_expr_type(&bin.left),
+            BinaryOp::EqEq
+            | BinaryOp::EqEqEq
+            | BinaryOp::NotEq
+            | BinaryOp::NotEqEq
+            | BinaryOp::Lt
+            | BinaryOp::LtEq
+            | BinaryOp::Gt
+            | BinaryOp::GtEq
+            | BinaryOp::LogicalAnd
+            | BinaryOp::LogicalOr => Some(quote! { bool }),
+            _ => None,
+        },
+        Expr::Array(arr) => {
+            let first = arr.elems.iter().flatten().next()?;
+            let elem = infer_expr_type(&first.expr)?;
+            Some(quote! { Vec<#elem> })
+        }
+        Expr::Object(_) => Some(quote! { serde_json::Value }),
+        Expr::Lit(Lit::Regex(_)) => Some(quote! { regex::Regex }),
+        Expr::New(_) if crate::stdlib::regexp::is_regex_expr(expr) => Some(quote! { regex::Regex }),
+        Expr::New(new_expr) => {
+            let class = new_expr.callee.as_ident()?;
+            let class = format_ident!("{}", class.sym.to_string());
+            Some(quote! { #class })
+        }
+        _ => None,
+    }
+}
+
+impl RustGenerator {
+    /// Module-level `const`/`let` -> `const`, `static LazyLock<T>` or
+    /// `static LazyLock<Mutex<T>>`, matching what `collect_module_statics` registered
+    pub fn process_module_var_decl(&mut self, n: &VarDecl) {
+        if n.declare || self.is_ambient {
+            // `declare const X: T` is provided by the host environment
+            for decl in &n.decls {
+                if let Pat::Ident(ident) = &decl.name {
+                    crate::diagnostics::warn(format!(
+                        "ambient variable `{}` was skipped; provide it in Rust",
+                        ident.id.sym
+                    ));
+                    self.code.push_str(&format!(
+                        "// declare {}: ambient declaration skipped\n",
+                        ident.id.sym
+                    ));
+                }
+            }
+            return;
+        }
+
+        let vis = if self.is_exporting {
+            quote! { pub }
+        } else {
+            quote! {}
+        };
+
+        for decl in &n.decls {
+            let Pat::Ident(ident) = &decl.name else {
+                crate::diagnostics::warn(
+                    "destructuring is not supported in module-level declarations",
+                );
+                continue;
+            };
+            let ts_name = ident.id.sym.to_string();
+            let Some(init) = decl.init.as_deref() else {
+                crate::diagnostics::warn(format!(
+                    "module-level `{}` has no initializer and was skipped",
+                    ts_name
+                ));
+                continue;
+            };
+
+            // Keep the name used by references (`API_URL`, `defaultLimit` -> `default_limit`)
+            let is_upper = ts_name.chars().next().is_some_and(|c| c.is_uppercase());
+            let name = if is_upper {
+                format_ident!("{}", ts_name)
+            } else {
+                format_ident!("{}", to_snake_case(&ts_name))
+            };
+            let allow = if ts_name.chars().any(|c| c.is_lowercase()) {
+                quote! { #[allow(non_upper_case_globals)] }
+            } else {
+                quote! {}
+            };
+
+            let kind = static_kind(n.kind, Some(init));
+            scope::declare_module_static(&ts_name, kind);
+
+            let ty = match ident.type_ann.as_ref() {
+                Some(type_ann) => Some(map_ts_type(Some(type_ann))),
+                None => infer_expr_type(init),
+            };
+
+            let item = match kind {
+                StaticKind::Const => {
+                    // String constants stay `&'static str` so they need no allocation
+                    let is_str = matches!(init, Expr::Lit(Lit::Str(_)) | Expr::Tpl(_));
+                    let (ty, value) = if is_str {
+                        (quote! { &str }, super::func::convert_str_arg(init))
+                    } else {
+                        (ty.unwrap_or_else(|| quote! { f64 }), convert_expr(init))
+                    };
+                    quote! { #allow #vis const #name: #ty = #value; }
+                }
+                StaticKind::Lazy | StaticKind::Mutex => {
+                    let ty = ty.unwrap_or_else(|| {
+                        crate::diagnostics::warn(format!(
+                            "could not infer the type of module-level `{}`; add a type annotation",
+                            ts_name
+                        ));
+                        quote! { serde_json::Value }
+                    });
+                    // A regex static is the compiled `Regex` itself
+                    let value = crate::stdlib::regexp::compile_static(init)
+                        .unwrap_or_else(|| convert_expr(init));
+                    if kind == StaticKind::Lazy {
+                        quote! {
+                            #allow #vis static #name: std::sync::LazyLock<#ty> =
+                                std::sync::LazyLock::new(|| #value);
+                        }
+                    } else {
+                        quote! {
+                            #allow #vis static #name: std::sync::LazyLock<std::sync::Mutex<#ty>> =
+                                std::sync::LazyLock::new(|| std::sync::Mutex::new(#value));
+                        }
+                    }
+                }
+            };
+
+            self.code.push_str(&item.to_string());
+            self.code.push('\n');
+        }
+    }
 }
@@ -0,0 +1,95 @@
+use quote::{format_ident, quote};
+use swc_ecma_ast::{TsModuleDecl, TsModuleName, TsNamespaceBody};
+use swc_ecma_visit::VisitWith;
+
+use super::func::to_snake_case;
+use super::interface::RustGenerator;
+use super::scope;
+
+impl RustGenerator {
+    /// `namespace Billing { ... }` -> `mod billing { ... }`.
+    /// `declare module 'pkg'` / `declare global` only describe external code and are skipped.
+    pub fn process_ts_module_decl(&mut self, n: &TsModuleDecl) {
+        let name = match &n.id {
+            TsModuleName::Ident(ident) if !n.global => ident.sym.to_string(),
+            TsModuleName::Ident(_) => {
+                self.code
+                    .push_str("// `declare global` block skipped (type declarations only)\n");
+                return;
+            }
+            TsModuleName::Str(s) => {
+                self.code.push_str(&format!(
+                    "// `declare module {:?}` block skipped (type declarations only)\n",
+                    s.value.as_str().unwrap_or_default()
+                ));
+                return;
+            }
+        };
+
+        if let Some(body) = &n.body {
+            let is_ambient = self.is_ambient || n.declare;
+            self.emit_namespace(&name, body, is_ambient);
+        }
+    }
+
+    fn emit_namespace(&mut self, name: &str, body: &TsNamespaceBody, is_ambient: bool) {
+        scope::declare_namespace(name);
+
+        let vis = if self.is_exporting {
+            quote! { pub }
+        } else {
+            quote! {}
+        };
+        let mod_name = to_snake_case(name);
+
+        // Generate the body with a separate generator so exports inside the
+        // namespace decide the visibility of its items. A namespace declared
+        // again continues the generator of its first declaration, so the
+        // bodies merge into one module as in TS.
+        let (existing, mut inner) = match self.namespaces.remove(&mod_name) {
+            Some((range, inner)) => (Some(range), inner),
+            None => (None, RustGenerator::new(false)),
+        };
+        inner.is_ambient = is_ambient;
+        inner.is_exporting = false;
+        match body {
+            TsNamespaceBody::TsModuleBlock(block) => {
+                for item in &block.body {
+                    item.visit_with(&mut inner);
+                }
+            }
+            // `namespace A.B { ... }` nests `B` inside `A`, and `B` is always exported
+            TsNamespaceBody::TsNamespaceDecl(nested) => {
+                inner.is_exporting = true;
+                inner.emit_namespace(&nested.id.sym, &nested.body, is_ambient || nested.declare);
+            }
+        }
+        self.controllers.append(&mut inner.controllers);
+
+        let range = match existing {
+            Some(range) => {
+                self.code.replace_range(range.clone(), &inner.code);
+                let grown = inner.code.len() - range.len();
+                for (later, _) in self.namespaces.values_mut() {
+                    if later.start >= range.end {
+                        *later = later.start + grown..later.end + grown;
+                    }
+                }
+                range.start..range.start + inner.code.len()
+            }
+            None => {
+                self.code.push_str(&format!(
+                    "{} mod {} {{\n#[allow(unused_imports)]\nuse super::*;\n",
+                    vis,
+                    format_ident!("{}", mod_name)
+                ));
+                let start = self.code.len();
+                self.code.push_str(&inner.code);
+                let end = self.code.len();
+                self.code.push_str("}\n");
+                start..end
+            }
+        };
+        self.namespaces.insert(mod_name, (range, inner));
+    }
+}
@@ -0,0 +1,385 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+
+use swc_ecma_ast::{
+    Decl, Ident, ModuleDecl, ModuleItem, Program, TsEntityName, TsKeywordType, TsKeywordTypeKind,
+    TsType, TsTypeRef, TsUnionOrIntersectionType, TsUnionType,
+};
+
+thread_local! {
+    static LOCALS: RefCell<HashMap<String, TsType>> = RefCell::new(HashMap::new());
+    static MODULE_STATICS: RefCell<HashMap<String, StaticKind>> = RefCell::new(HashMap::new());
+    static NAMESPACES: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+    static FUNCTIONS: RefCell<HashMap<String, Vec<ParamKind>>> = RefCell::new(HashMap::new());
+    static ASYNC_FUNCTIONS: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+    static FALLIBLE: Cell<bool> = const { Cell::new(false) };
+    static RETURN_TYPE: RefCell<Option<TsType>> = const { RefCell::new(None) };
+    static STRUCTS: RefCell<HashMap<String, Vec<StructField>>> = RefCell::new(HashMap::new());
+    static LOCAL_REGEXES: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+    static MODULE_REGEXES: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+    static NODE_IMPORTS: RefCell<HashMap<String, NodeImport>> = RefCell::new(HashMap::new());
+    static MODULE_TYPES: RefCell<HashMap<String, TsType>> = RefCell::new(HashMap::new());
+    static MAPPED_IMPORTS: RefCell<HashMap<String, MappedImport>> = RefCell::new(HashMap::new());
+    static FUNCTION_RETURNS: RefCell<HashMap<String, TsType>> = RefCell::new(HashMap::new());
+    static CLASSES: RefCell<HashMap<String, ClassMembers>> = RefCell::new(HashMap::new());
+    static CURRENT_CLASS: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// How a function parameter is passed at call sites
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamKind {
+    Required,
+    /// `x?: T` or `x = default`, passed as `Option<T>`
+    Optional,
+    /// `...xs: T[]`, passed as `Vec<T>`
+    Rest,
+}
+
+/// A field of an interface, as emitted on the generated struct
+#[derive(Debug, Clone)]
+pub struct StructField {
+    pub name: String,
+    pub ts_type: TsType,
+    /// `name?: T`, emitted as `Option<T>`
+    pub optional: bool,
+}
+
+/// The typed members of a class, for typing `this.items` and `user.name()`
+#[derive(Debug, Clone, Default)]
+pub struct ClassMembers {
+    /// Annotated properties and parameter properties
+    pub fields: HashMap<String, TsType>,
+    /// Every method, with its declared return type if annotated
+    pub methods: HashMap<String, Option<TsType>>,
+    /// How each method's parameters are passed
+    pub method_params: HashMap<String, Vec<ParamKind>>,
+    /// How the constructor's parameters are passed
+    pub constructor_params: Vec<ParamKind>,
+}
+
+/// A Node built-in module with a Rust shim
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeModule {
+    Fs,
+    /// `fs/promises`, or `fs.promises`
+    FsPromises,
+    Path,
+    Process,
+    /// `crypto`, or the Web Crypto global
+    Crypto,
+}
+
+/// A binding imported from a Node built-in module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeImport {
+    pub module: NodeModule,
+    /// The imported export for `import { join } from "path"`; `None` for the
+    /// module object itself (default and namespace imports)
+    pub member: Option<String>,
+}
+
+/// A binding imported from a module translated by a
+/// [`crate::stdlib::registry::StdlibMapping`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappedImport {
+    pub module: String,
+    /// The imported export for `import { chunk } from "lodash"`; `None` for
+    /// the module object itself (default and namespace imports)
+    pub member: Option<String>,
+}
+
+/// How a module-level binding is represented in Rust
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StaticKind {
+    /// `const NAME: T = ...;`
+    Const,
+    /// `static NAME: LazyLock<T>`, read through auto-deref
+    Lazy,
+    /// `static NAME: LazyLock<Mutex<T>>`, for module-level `let`
+    Mutex,
+}
+
+/// Forget all locals. Called when entering a new function or method body.
+pub fn reset() {
+    LOCALS.with(|l| l.borrow_mut().clear());
+    LOCAL_REGEXES.with(|r| r.borrow_mut().clear());
+}
+
+/// Remember the declared TS type of a parameter or local variable.
+pub fn declare(name: &str, ts_type: &TsType) {
+    LOCALS.with(|l| {
+        l.borrow_mut().insert(name.to_string(), ts_type.clone());
+    });
+}
+
+/// Remember a local whose value is known to be an untyped `serde_json::Value`.
+pub fn declare_untyped(name: &str) {
+    declare(
+        name,
+        &TsType::TsKeywordType(TsKeywordType {
+            span: swc_common::DUMMY_SP,
+            kind: TsKeywordTypeKind::TsUnknownKeyword,
+        }),
+    );
+}
+
+/// Look up the declared TS type of a local, if any.
+pub fn lookup(name: &str) -> Option<TsType> {
+    LOCALS.with(|l| l.borrow().get(name).cloned())
+}
+
+/// Mark whether the code being generated returns `Result`, so errors can be
+/// propagated with `?`. Returns the previous setting for nested closures.
+pub fn set_fallible(fallible: bool) -> bool {
+    FALLIBLE.with(|f| f.replace(fallible))
+}
+
+pub fn is_fallible() -> bool {
+    FALLIBLE.with(|f| f.get())
+}
+
+/// Set the declared return type of the function being generated (`None` when
+/// unannotated). Returns the previous one for nested functions.
+pub fn set_return_type(return_type: Option<TsType>) -> Option<TsType> {
+    RETURN_TYPE.with(|r| r.replace(return_type))
+}
+
+/// The declared return type of the function being generated, if any.
+pub fn return_type() -> Option<TsType> {
+    RETURN_TYPE.with(|r| r.borrow().clone())
+}
+
+/// A reference to a named type without type arguments, e.g. `RegExp`.
+pub fn type_ref(name: &str) -> TsType {
+    TsType::TsTypeRef(TsTypeRef {
+        span: swc_common::DUMMY_SP,
+        type_name: TsEntityName::Ident(Ident::new_no_ctxt(name.into(), swc_common::DUMMY_SP)),
+        type_params: None,
+    })
+}
+
+/// Forget all module-level bindings. Called before generating a new file.
+pub fn reset_module() {
+    MODULE_STATICS.with(|m| m.borrow_mut().clear());
+    NAMESPACES.with(|n| n.borrow_mut().clear());
+    FUNCTIONS.with(|f| f.borrow_mut().clear());
+    ASYNC_FUNCTIONS.with(|f| f.borrow_mut().clear());
+    STRUCTS.with(|s| s.borrow_mut().clear());
+    MODULE_REGEXES.with(|r| r.borrow_mut().clear());
+    NODE_IMPORTS.with(|n| n.borrow_mut().clear());
+    MODULE_TYPES.with(|t| t.borrow_mut().clear());
+    MAPPED_IMPORTS.with(|m| m.borrow_mut().clear());
+    FUNCTION_RETURNS.with(|f| f.borrow_mut().clear());
+    CLASSES.with(|c| c.borrow_mut().clear());
+}
+
+/// Remember how a module-level `const`/`let` was emitted.
+pub fn declare_module_static(name: &str, kind: StaticKind) {
+    MODULE_STATICS.with(|m| {
+        m.borrow_mut().insert(name.to_string(), kind);
+    });
+}
+
+/// Look up a module-level binding, unless a local of the same name shadows it.
+pub fn lookup_module_static(name: &str) -> Option<StaticKind> {
+    if lookup(name).is_some() {
+        return None;
+    }
+    MODULE_STATICS.with(|m| m.borrow().get(name).copied())
+}
+
+/// `ts_type | undefined`, the type of an optional parameter or field
+pub fn nullable(ts_type: &TsType) -> TsType {
+    TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(TsUnionType {
+        span: swc_common::DUMMY_SP,
+        types: vec![
+            Box::new(ts_type.clone()),
+            Box::new(TsType::TsKeywordType(TsKeywordType {
+                span: swc_common::DUMMY_SP,
+                kind: TsKeywordTypeKind::TsUndefinedKeyword,
+            })),
+        ],
+    }))
+}
+
+/// Remember a TS namespace, so `Billing.charge()` becomes `billing::charge()`.
+pub fn declare_namespace(name: &str) {
+    NAMESPACES.with(|n| {
+        n.borrow_mut().insert(name.to_string());
+    });
+}
+
+pub fn is_namespace(name: &str) -> bool {
+    NAMESPACES.with(|n| n.borrow().contains(name))
+}
+
+/// Remember the parameter shape of a module-level function.
+pub fn declare_function(name: &str, params: Vec<ParamKind>) {
+    FUNCTIONS.with(|f| {
+        f.borrow_mut().insert(name.to_string(), params);
+    });
+}
+
+/// Parameter shape of a module-level function, unless a local shadows it.
+pub fn lookup_function(name: &str) -> Option<Vec<ParamKind>> {
+    if lookup(name).is_some() {
+        return None;
+    }
+    FUNCTIONS.with(|f| f.borrow().get(name).cloned())
+}
+
+/// Remember the declared return type of a module-level function.
+pub fn declare_function_return(name: &str, ts_type: &TsType) {
+    FUNCTION_RETURNS.with(|f| {
+        f.borrow_mut().insert(name.to_string(), ts_type.clone());
+    });
+}
+
+/// Declared return type of a module-level function, unless a local shadows it.
+pub fn lookup_function_return(name: &str) -> Option<TsType> {
+    if lookup(name).is_some() {
+        return None;
+    }
+    FUNCTION_RETURNS.with(|f| f.borrow().get(name).cloned())
+}
+
+/// Remember a module-level function emitted as `async fn`.
+pub fn declare_async_function(name: &str) {
+    ASYNC_FUNCTIONS.with(|f| {
+        f.borrow_mut().insert(name.to_string());
+    });
+}
+
+/// Whether calling `name` yields a future, unless a local shadows it.
+pub fn is_async_function(name: &str) -> bool {
+    lookup(name).is_none() && ASYNC_FUNCTIONS.with(|f| f.borrow().contains(name))
+}
+
+/// Remember a local bound to a regex, with the JS flags it was created with.
+pub fn declare_regex(name: &str, flags: &str) {
+    declare(name, &type_ref("RegExp"));
+    LOCAL_REGEXES.with(|r| {
+        r.borrow_mut().insert(name.to_string(), flags.to_string());
+    });
+}
+
+/// Remember a module-level regex static and its JS flags.
+pub fn declare_module_regex(name: &str, flags: &str) {
+    MODULE_REGEXES.with(|r| {
+        r.borrow_mut().insert(name.to_string(), flags.to_string());
+    });
+}
+
+/// The JS flags of a regex binding, or `None` if `name` is not a regex.
+/// Parameters typed `RegExp` have unknown, empty flags.
+pub fn regex_flags(name: &str) -> Option<String> {
+    match lookup(name) {
+        Some(TsType::TsTypeRef(type_ref))
+            if type_ref
+                .type_name
+                .as_ident()
+                .is_some_and(|ident| ident.sym == "RegExp") =>
+        {
+            Some(LOCAL_REGEXES.with(|r| r.borrow().get(name).cloned().unwrap_or_default()))
+        }
+        Some(_) => None,
+        None => MODULE_REGEXES.with(|r| r.borrow().get(name).cloned()),
+    }
+}
+
+/// Remember a binding imported from `fs`, `path` or `process`.
+pub fn declare_node_import(name: &str, import: NodeImport) {
+    NODE_IMPORTS.with(|n| {
+        n.borrow_mut().insert(name.to_string(), import);
+    });
+}
+
+/// Look up a Node built-in import, unless a local of the same name shadows it.
+pub fn lookup_node_import(name: &str) -> Option<NodeImport> {
+    if lookup(name).is_some() {
+        return None;
+    }
+    NODE_IMPORTS.with(|n| n.borrow().get(name).cloned())
+}
+
+/// Remember a binding imported from a module with a stdlib mapping.
+pub fn declare_mapped_import(name: &str, import: MappedImport) {
+    MAPPED_IMPORTS.with(|m| {
+        m.borrow_mut().insert(name.to_string(), import);
+    });
+}
+
+/// Look up a mapped import, unless a local of the same name shadows it.
+pub fn lookup_mapped_import(name: &str) -> Option<MappedImport> {
+    if lookup(name).is_some() {
+        return None;
+    }
+    MAPPED_IMPORTS.with(|m| m.borrow().get(name).cloned())
+}
+
+/// Remember the type of a module-level binding whose uses need it, such as an
+/// axios instance.
+pub fn declare_module_type(name: &str, ts_type: &TsType) {
+    MODULE_TYPES.with(|t| {
+        t.borrow_mut().insert(name.to_string(), ts_type.clone());
+    });
+}
+
+/// The type of a local, or else of a module-level binding of that name.
+pub fn lookup_type(name: &str) -> Option<TsType> {
+    lookup(name).or_else(|| MODULE_TYPES.with(|t| t.borrow().get(name).cloned()))
+}
+
+/// Remember the fields of an interface, in declaration order.
+pub fn declare_struct(name: &str, fields: Vec<StructField>) {
+    STRUCTS.with(|s| {
+        s.borrow_mut().insert(name.to_string(), fields);
+    });
+}
+
+/// Fields of an interface declared in the current file.
+pub fn lookup_struct(name: &str) -> Option<Vec<StructField>> {
+    STRUCTS.with(|s| s.borrow().get(name).cloned())
+}
+
+/// Remember the members of a class declared in the current file.
+pub fn declare_class(name: &str, members: ClassMembers) {
+    CLASSES.with(|c| {
+        c.borrow_mut().insert(name.to_string(), members);
+    });
+}
+
+/// Members of a class declared in the current file.
+pub fn lookup_class(name: &str) -> Option<ClassMembers> {
+    CLASSES.with(|c| c.borrow().get(name).cloned())
+}
+
+/// Set the class whose members are being generated, for `this`. Returns the
+/// previous one for nested classes.
+pub fn set_current_class(name: Option<String>) -> Option<String> {
+    CURRENT_CLASS.with(|c| c.replace(name))
+}
+
+pub fn current_class() -> Option<String> {
+    CURRENT_CLASS.with(|c| c.borrow().clone())
+}
+
+/// Top-level declarations of a file, exported or not.
+pub fn top_level_decls(program: &Program) -> Vec<&Decl> {
+    match program {
+        Program::Module(module) => module
+            .body
+            .iter()
+            .filter_map(|item| match item {
+                ModuleItem::Stmt(swc_ecma_ast::Stmt::Decl(decl)) => Some(decl),
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => Some(&export.decl),
+                _ => None,
+            })
+            .collect(),
+        Program::Script(script) => script
+            .body
+            .iter()
+            .filter_map(|stmt| stmt.as_decl())
+            .collect(),
+    }
+}
@@ -1,6 +1,6 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use swc_ecma_ast::{TsType, TsTypeAnn};
+use swc_ecma_ast::{TsEntityName, TsType, TsTypeAnn, TsTypeRef};
 
 /// Maps TypeScript types to Rust types
 #[allow(clippy::borrowed_box)]
@@ -22,7 +22,30 @@ pub fn map_ts_type(type_ann: Option<&Box<TsTypeAnn>>) -> TokenStream {
                 if let Some(ident) = t.type_name.as_ident() {
                     let name = ident.sym.as_str();
                     match name {
-                        "Date" => quote! { String },
+                        "Date" => {
+                            crate::stdlib::date::require_chrono();
+                            quote! { chrono::DateTime<chrono::Utc> }
+                        }
+                        "Response"
+                            if crate::stdlib::fetch::is_response_type(&TsType::TsTypeRef(
+                                t.clone(),
+                            )) =>
+                        {
+                            quote! { reqwest::Response }
+                        }
+                        name if crate::stdlib::buffer::is_bytes_type_name(name) => {
+                            quote! { Vec<u8> }
+                        }
+                        "URL" | "URLSearchParams" => crate::stdlib::url::map_type(name),
+                        "RegExp" => {
+                            crate::dependencies::require("regex", "\"1\"");
+                            quote! { regex::Regex }
+                        }
+                        name if super::error::is_error_type(name) => quote! { crate::AppError },
+                        name if crate::stdlib::axios::is_axios_type(name) => {
+                            crate::stdlib::axios::map_type(t)
+                        }
+                        "Record" | "Map" => map_hash_map_type(t),
                         "Array" => {
                             if let Some(type_params) = &t.type_params {
                                 if let Some(first_param) = type_params.params.first() {
@@ -53,7 +76,7 @@ pub fn map_ts_type(type_ann: Option<&Box<TsTypeAnn>>) -> TokenStream {
                         }
                     }
                 } else {
-                    quote! { serde_json::Value }
+                    qualified_type_path(&t.type_name)
                 }
             }
             TsType::TsUnionOrIntersectionType(union_or_intersection) => {
@@ -125,6 +148,17 @@ pub fn unwrap_promise_type(type_ann: Option<&Box<TsTypeAnn>>) -> TokenStream {
     map_ts_type(type_ann)
 }
 
+/// Whether a return type is `Promise<T>`; such functions are emitted as `async fn`
+pub fn is_promise_type(type_ann: Option<&TsTypeAnn>) -> bool {
+    type_ann.is_some_and(|type_ann| {
+        matches!(
+            &*type_ann.type_ann,
+            TsType::TsTypeRef(TsTypeRef { type_name: TsEntityName::Ident(ident), .. })
+                if ident.sym == "Promise"
+        )
+    })
+}
+
 pub fn is_optional_type(type_ann: Option<&TsTypeAnn>) -> bool {
     if let Some(type_ann) = type_ann {
         if let TsType::TsUnionOrIntersectionType(
@@ -195,7 +229,28 @@ pub fn map_inner_type(ts_type: &swc_ecma_ast::TsType) -> TokenStream {
             if let Some(ident) = t.type_name.as_ident() {
                 let name = ident.sym.as_str();
                 match name {
-                    "Date" => quote! { String },
+                    "Date" => {
+                        crate::stdlib::date::require_chrono();
+                        quote! { chrono::DateTime<chrono::Utc> }
+                    }
+                    "Response"
+                        if crate::stdlib::fetch::is_response_type(&TsType::TsTypeRef(
+                            t.clone(),
+                        )) =>
+                    {
+                        quote! { reqwest::Response }
+                    }
+                    name if crate::stdlib::buffer::is_bytes_type_name(name) => quote! { Vec<u8> },
+                    "URL" | "URLSearchParams" => crate::stdlib::url::map_type(name),
+                    "RegExp" => {
+                        crate::dependencies::require("regex", "\"1\"");
+                        quote! { regex::Regex }
+                    }
+                    name if super::error::is_error_type(name) => quote! { crate::AppError },
+                    name if crate::stdlib::axios::is_axios_type(name) => {
+                        crate::stdlib::axios::map_type(t)
+                    }
+                    "Record" | "Map" => map_hash_map_type(t),
                     "Array" => {
                         if let Some(type_params) = &t.type_params {
                             if let Some(first_param) = type_params.params.first() {
@@ -215,13 +270,60 @@ pub fn map_inner_type(ts_type: &swc_ecma_ast::TsType) -> TokenStream {
                     }
                 }
             } else {
-                quote! { serde_json::Value }
+                qualified_type_path(&t.type_name)
             }
         }
         _ => quote! { serde_json::Value },
     }
 }
 
+/// `Record<K, V>` and `Map<K, V>` -> `HashMap<K, V>`
+fn map_hash_map_type(t: &swc_ecma_ast::TsTypeRef) -> TokenStream {
+    let mut params = t
+        .type_params
+        .iter()
+        .flat_map(|p| p.params.iter())
+        .map(|p| map_inner_type(p));
+    let key = params.next().unwrap_or_else(|| quote! { String });
+    let value = params
+        .next()
+        .unwrap_or_else(|| quote! { serde_json::Value });
+    quote! { std::collections::HashMap<#key, #value> }
+}
+
+/// `Billing.Invoice` -> `billing::Invoice` (namespaces become snake_case modules)
+fn qualified_type_path(name: &TsEntityName) -> TokenStream {
+    match name {
+        TsEntityName::Ident(ident) => {
+            let ident = proc_macro2::Ident::new(&ident.sym, proc_macro2::Span::call_site());
+            quote! { #ident }
+        }
+        TsEntityName::TsQualifiedName(qualified) => {
+            let mut modules = Vec::new();
+            let mut current = &qualified.left;
+            loop {
+                match current {
+                    TsEntityName::Ident(ident) => {
+                        modules.push(super::func::to_snake_case(&ident.sym));
+                        break;
+                    }
+                    TsEntityName::TsQualifiedName(inner) => {
+                        modules.push(super::func::to_snake_case(&inner.right.sym));
+                        current = &inner.left;
+                    }
+                }
+            }
+            let modules = modules
+                .iter()
+                .rev()
+                .map(|m| proc_macro2::Ident::new(m, proc_macro2::Span::call_site()));
+            let item =
+                proc_macro2::Ident::new(&qualified.right.sym, proc_macro2::Span::call_site());
+            quote! { #(#modules::)* #item }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,21 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+thread_local! {
+    static REQUIRED: RefCell<BTreeMap<String, String>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Record that the generated code uses an optional crate.
+///
+/// `spec` is the right-hand side of the `Cargo.toml` entry, e.g. `"\"1\""` or
+/// `"{ version = \"0.4\", features = [\"serde\"] }"`.
+pub fn require(name: &str, spec: &str) {
+    REQUIRED.with(|r| {
+        r.borrow_mut().insert(name.to_string(), spec.to_string());
+    });
+}
+
+/// Take all crates required since the last call.
+pub fn take() -> BTreeMap<String, String> {
+    REQUIRED.with(|r| std::mem::take(&mut *r.borrow_mut()))
+}
@@ -0,0 +1,26 @@
+use std::cell::RefCell;
+
+thread_local! {
+    static DIAGNOSTICS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Record a warning about a construct that could not be translated faithfully.
+///
+/// The converters are free functions without access to the generator, so
+/// warnings are collected per thread and drained by `generate`.
+pub fn warn(message: impl Into<String>) {
+    let message = message.into();
+    DIAGNOSTICS.with(|d| {
+        let mut d = d.borrow_mut();
+        // The same construct is often converted more than once (e.g. when a
+        // heuristic re-renders an expression), so avoid duplicate reports.
+        if !d.contains(&message) {
+            d.push(message);
+        }
+    });
+}
+
+/// Take all warnings collected since the last call.
+pub fn take() -> Vec<String> {
+    DIAGNOSTICS.with(|d| std::mem::take(&mut *d.borrow_mut()))
+}
@@ -1,5 +1,12 @@
 pub mod convert;
+pub mod dependencies;
+pub mod diagnostics;
+pub mod runtime;
 pub mod stdlib;
+pub mod support;
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
 
 use convert::interface::RustGenerator;
 use swc_ecma_ast::Program;
@@ -11,12 +18,77 @@ pub struct ControllerMetadata {
     pub route_path: String,
 }
 
+/// Settings that change how some JS APIs are translated
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// Emit `console.*` calls as `tracing` events instead of printing them
+    pub console_tracing: bool,
+    /// Translations of library calls besides the built-in ones, e.g. loaded
+    /// from the project's mapping files
+    pub mappings: stdlib::registry::Registry,
+}
+
+thread_local! {
+    static OPTIONS: RefCell<Options> = RefCell::new(Options::default());
+}
+
+/// The options of the file being generated on this thread
+pub(crate) fn options() -> Options {
+    OPTIONS.with(|o| o.borrow().clone())
+}
+
 pub struct GeneratedCode {
     pub code: String,
     pub controllers: Vec<ControllerMetadata>,
+    /// Warnings about constructs that were translated approximately or not at all
+    pub diagnostics: Vec<String>,
+    /// Optional crates used by the generated code (name -> Cargo.toml spec)
+    pub dependencies: BTreeMap<String, String>,
+    /// Support modules to emit into the generated crate (see [`support::source`])
+    pub support: BTreeSet<&'static str>,
 }
 
 pub fn generate(program: &Program, is_index: bool) -> GeneratedCode {
+    generate_with_options(program, is_index, &Options::default())
+}
+
+pub fn generate_with_options(
+    program: &Program,
+    is_index: bool,
+    options: &Options,
+) -> GeneratedCode {
+    generate_in_project(program, is_index, options, &[], &[], &[], &[])
+}
+
+/// Generate one file of a project whose error classes (see
+/// [`convert::error::collect_error_classes`]), emitter classes (see
+/// [`convert::events::collect_emitter_classes`]), exported functions (see
+/// [`convert::func::collect_exported_functions`]) and exported bindings (see
+/// [`convert::module::collect_exported_statics`]) are shared by every file.
+pub fn generate_in_project(
+    program: &Program,
+    is_index: bool,
+    options: &Options,
+    error_classes: &[convert::error::ErrorClass],
+    emitter_classes: &[convert::events::EmitterClass],
+    functions: &[convert::func::FunctionSignature],
+    statics: &[convert::module::ModuleStatic],
+) -> GeneratedCode {
+    OPTIONS.with(|o| *o.borrow_mut() = options.clone());
+    // Drop anything left over from a previous file on this thread
+    diagnostics::take();
+    dependencies::take();
+    support::take();
+    convert::scope::reset();
+    convert::scope::reset_module();
+    convert::module::collect_module_statics(program, is_index, statics);
+    convert::func::collect_function_signatures(program, functions);
+    convert::interface::collect_struct_fields(program);
+    convert::class::collect_class_members(program);
+    convert::error::register(program, error_classes);
+    convert::events::register(program, emitter_classes);
+    stdlib::lodash::register(program);
+
     let mut generator = RustGenerator::new(is_index);
     program.visit_with(&mut generator);
 
@@ -29,5 +101,8 @@ pub fn generate(program: &Program, is_index: bool) -> GeneratedCode {
     GeneratedCode {
         code: generator.code,
         controllers: generator.controllers,
+        diagnostics: diagnostics::take(),
+        dependencies: dependencies::take(),
+        support: support::take(),
     }
 }
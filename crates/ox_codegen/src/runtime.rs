@@ -0,0 +1,30 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::dependencies;
+
+// The `typerust_runtime` crate holds the JS semantics that do not fit in an
+// expression. A generated project that uses it gets a copy next to its own
+// `Cargo.toml`, depended on by path.
+
+/// The crate name, and the directory it is copied to in the generated project
+pub const CRATE: &str = "typerust_runtime";
+
+/// The files of the crate, relative to its directory
+pub const FILES: &[(&str, &str)] = &[
+    (
+        "Cargo.toml",
+        include_str!("../../typerust_runtime/Cargo.toml"),
+    ),
+    (
+        "src/lib.rs",
+        include_str!("../../typerust_runtime/src/lib.rs"),
+    ),
+];
+
+/// `typerust_runtime::<function>`, recording that the crate is needed
+pub fn path(function: &str) -> TokenStream {
+    dependencies::require(CRATE, &format!("{{ path = {:?} }}", CRATE));
+    let function = format_ident!("{}", function);
+    quote! { typerust_runtime::#function }
+}
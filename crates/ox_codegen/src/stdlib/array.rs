@@ -2,7 +2,124 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use swc_ecma_ast::*;
 
-use super::super::convert::func::{convert_expr, convert_expr_or_spread};
+use super::super::convert::func::{
+    convert_expr, convert_expr_or_spread, convert_place_expr, convert_str_arg,
+};
+use super::super::convert::scope;
+
+/// Methods that only exist on arrays
+pub const ARRAY_METHODS: &[&str] = &[
+    "push",
+    "map",
+    "filter",
+    "join",
+    "reduce",
+    "find",
+    "findIndex",
+    "some",
+    "every",
+    "forEach",
+    "splice",
+    "sort",
+    "reverse",
+    "flat",
+    "flatMap",
+    "pop",
+    "shift",
+    "unshift",
+    "fill",
+];
+
+/// Methods shared by strings and arrays; routed here only for known arrays
+pub const SHARED_METHODS: &[&str] = &["includes", "indexOf", "slice", "concat", "at"];
+
+/// Whether `expr` is known to evaluate to a `Vec` in the generated code
+pub fn is_array_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Array(_) => true,
+        Expr::Paren(paren) => is_array_expr(&paren.expr),
+        Expr::Ident(ident) => scope::lookup(&ident.sym).is_some_and(|t| is_array_type(&t)),
+        Expr::Call(call) => {
+            // `xs.map(...).includes(...)`: array-only methods returning a new array
+            if let Callee::Expr(callee) = &call.callee {
+                if let Expr::Member(member) = &**callee {
+                    if let Some(prop) = member.prop.as_ident() {
+                        return match prop.sym.as_ref() {
+                            "map" | "filter" | "flat" | "flatMap" | "splice" | "sort"
+                            | "reverse" => true,
+                            "slice" | "concat" => is_array_expr(&member.obj),
+                            _ => false,
+                        };
+                    }
+                }
+            }
+            false
+        }
+        _ => false,
+    }
+}
+
+pub fn is_array_type(ts_type: &TsType) -> bool {
+    match ts_type {
+        TsType::TsArrayType(_) => true,
+        TsType::TsTypeRef(type_ref) => matches!(
+            &type_ref.type_name,
+            TsEntityName::Ident(ident) if ident.sym == "Array" || ident.sym == "ReadonlyArray"
+        ),
+        TsType::TsTypeOperator(op) => is_array_type(&op.type_ann),
+        _ => false,
+    }
+}
+
+/// Number of parameters the callback declares, so index arguments are only
+/// passed when the callback asks for them.
+fn callback_arity(arg: &ExprOrSpread) -> usize {
+    match &*arg.expr {
+        Expr::Arrow(arrow) => arrow.params.len(),
+        Expr::Fn(f) => f.function.params.len(),
+        _ => 1,
+    }
+}
+
+/// JS relative index (`-1` is the last element) clamped to `0..=len`
+fn relative_index() -> TokenStream {
+    quote! {
+        let relative = |i: f64| (if i < 0.0 { (len + i).max(0.0) } else { i.min(len) }) as usize;
+    }
+}
+
+/// Whether `expr` names storage that a mutating method can change
+fn is_place(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(paren) => is_place(&paren.expr),
+        Expr::Ident(_) => true,
+        Expr::Member(member) => member.obj.is_this() || is_place(&member.obj),
+        _ => false,
+    }
+}
+
+/// `xs.sort()` and `xs.reverse()` change the array and return it. A receiver
+/// that is not a variable or field (`Object.keys(m).sort()`) is evaluated
+/// once into a temporary, which is changed and returned.
+fn in_place(obj: &Expr, call: TokenStream) -> TokenStream {
+    if is_place(obj) {
+        let place = convert_place_expr(obj);
+        return quote! {
+            {
+                #place.#call;
+                #place.clone()
+            }
+        };
+    }
+    let items = convert_expr(obj);
+    quote! {
+        {
+            let mut items = #items;
+            items.#call;
+            items
+        }
+    }
+}
 
 /// Handle array method calls
 pub fn handle_method(obj: &Expr, method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
@@ -10,21 +127,23 @@ pub fn handle_method(obj: &Expr, method: &str, args: &[ExprOrSpread]) -> Option<
 
     match method {
         "push" => {
-            if args.len() == 1 {
-                let arg = convert_expr_or_spread(&args[0]);
-                Some(quote! { #obj_tokens.push(#arg) })
-            } else {
-                None
+            let place = convert_place_expr(obj);
+            match args {
+                [arg] if arg.spread.is_none() => {
+                    let arg = convert_expr_or_spread(arg);
+                    Some(quote! { #place.push(#arg) })
+                }
+                [arg] => {
+                    let items = convert_expr_or_spread(arg);
+                    Some(quote! { #place.extend(#items.iter().cloned()) })
+                }
+                _ => None,
             }
         }
         "map" => {
             if args.len() == 1 {
                 let callback = convert_expr_or_spread(&args[0]);
-                let param_count = if let Expr::Arrow(arrow) = &*args[0].expr {
-                    arrow.params.len()
-                } else {
-                    1
-                };
+                let param_count = callback_arity(&args[0]);
 
                 if param_count > 1 {
                     Some(quote! {
@@ -49,12 +168,265 @@ pub fn handle_method(obj: &Expr, method: &str, args: &[ExprOrSpread]) -> Option<
             }
         }
         "join" => {
-            if args.len() == 1 {
-                let separator = convert_expr_or_spread(&args[0]);
-                Some(quote! { #obj_tokens.join(&#separator) })
+            let separator = match args.first() {
+                Some(arg) => convert_str_arg(&arg.expr),
+                None => quote! { "," },
+            };
+            if super::receiver::classify_element(obj) == Some(super::receiver::Receiver::String) {
+                return Some(quote! { #obj_tokens.join(#separator) });
+            }
+            // Numbers and booleans join by their JS string forms
+            let to_js_string = crate::runtime::path("ToJsString");
+            Some(quote! {
+                #obj_tokens.iter().map(#to_js_string::to_js_element).collect::<Vec<_>>().join(#separator)
+            })
+        }
+        "reduce" => {
+            let callback = convert_expr_or_spread(args.first()?);
+            let with_index = callback_arity(&args[0]) > 2;
+            match (args.get(1), with_index) {
+                (Some(init), false) => {
+                    let init = convert_expr_or_spread(init);
+                    Some(quote! { #obj_tokens.iter().cloned().fold(#init, #callback) })
+                }
+                (Some(init), true) => {
+                    let init = convert_expr_or_spread(init);
+                    Some(quote! {
+                        #obj_tokens.iter().cloned().enumerate().fold(#init, |acc, (idx, val)| (#callback)(acc, val, idx as f64))
+                    })
+                }
+                // Without an initial value JS throws on an empty array
+                (None, _) => Some(quote! {
+                    #obj_tokens.iter().cloned().reduce(#callback).expect("reduce of empty array with no initial value")
+                }),
+            }
+        }
+        "find" => {
+            let callback = convert_expr_or_spread(args.first()?);
+            Some(quote! { #obj_tokens.iter().find(|x| (#callback)((*x).clone())).cloned() })
+        }
+        "findIndex" => {
+            let callback = convert_expr_or_spread(args.first()?);
+            Some(quote! {
+                #obj_tokens.iter().position(|x| (#callback)(x.clone())).map(|i| i as f64).unwrap_or(-1.0)
+            })
+        }
+        "some" => {
+            let callback = convert_expr_or_spread(args.first()?);
+            Some(quote! { #obj_tokens.iter().any(|x| (#callback)(x.clone())) })
+        }
+        "every" => {
+            let callback = convert_expr_or_spread(args.first()?);
+            Some(quote! { #obj_tokens.iter().all(|x| (#callback)(x.clone())) })
+        }
+        "forEach" => {
+            let callback = convert_expr_or_spread(args.first()?);
+            if callback_arity(&args[0]) > 1 {
+                Some(quote! {
+                    #obj_tokens.iter().cloned().enumerate().for_each(|(idx, val)| (#callback)(val, idx as f64))
+                })
             } else {
-                None
+                Some(quote! { #obj_tokens.iter().cloned().for_each(#callback) })
+            }
+        }
+        "includes" => {
+            let value = convert_expr_or_spread(args.first()?);
+            Some(quote! { #obj_tokens.iter().any(|x| *x == #value) })
+        }
+        "indexOf" => {
+            let value = convert_expr_or_spread(args.first()?);
+            Some(quote! {
+                #obj_tokens.iter().position(|x| *x == #value).map(|i| i as f64).unwrap_or(-1.0)
+            })
+        }
+        "slice" => {
+            let relative = relative_index();
+            let start = args
+                .first()
+                .map(convert_expr_or_spread)
+                .unwrap_or_else(|| quote! { 0.0 });
+            let end = args
+                .get(1)
+                .map(convert_expr_or_spread)
+                .unwrap_or_else(|| quote! { len });
+            Some(quote! {
+                {
+                    let items = &#obj_tokens;
+                    let len = items.len() as f64;
+                    #relative
+                    let start = relative(#start);
+                    let end = relative(#end).max(start);
+                    items[start..end].to_vec()
+                }
+            })
+        }
+        "splice" => {
+            let place = convert_place_expr(obj);
+            let relative = relative_index();
+            let start = convert_expr_or_spread(args.first()?);
+            let delete_count = args
+                .get(1)
+                .map(convert_expr_or_spread)
+                .unwrap_or_else(|| quote! { len });
+            let inserted: Vec<_> = args.iter().skip(2).map(convert_expr_or_spread).collect();
+            Some(quote! {
+                {
+                    let len = #place.len() as f64;
+                    #relative
+                    let start = relative(#start);
+                    let end = (start + (#delete_count).max(0.0) as usize).min(#place.len());
+                    #place.splice(start..end, vec![#(#inserted),*]).collect::<Vec<_>>()
+                }
+            })
+        }
+        "concat" => {
+            let others: Vec<_> = args.iter().map(convert_expr_or_spread).collect();
+            Some(quote! { [#obj_tokens.clone(), #(#others.clone()),*].concat() })
+        }
+        "sort" => {
+            // `sort_by` is stable, like `Array.prototype.sort`
+            let compare = match args.first() {
+                Some(comparator) => {
+                    let comparator = convert_expr_or_spread(comparator);
+                    quote! {
+                        |a, b| (#comparator)(a.clone(), b.clone()).partial_cmp(&0.0).unwrap_or(std::cmp::Ordering::Equal)
+                    }
+                }
+                // Without a comparator JS compares the string forms of the elements
+                None => crate::runtime::path("compare_default"),
+            };
+            Some(in_place(obj, quote! { sort_by(#compare) }))
+        }
+        "reverse" => Some(in_place(obj, quote! { reverse() })),
+        "flat" => Some(quote! { #obj_tokens.iter().flatten().cloned().collect::<Vec<_>>() }),
+        "flatMap" => {
+            let callback = convert_expr_or_spread(args.first()?);
+            Some(quote! { #obj_tokens.iter().cloned().flat_map(#callback).collect::<Vec<_>>() })
+        }
+        "pop" => {
+            let place = convert_place_expr(obj);
+            Some(quote! { #place.pop() })
+        }
+        "shift" => {
+            let place = convert_place_expr(obj);
+            Some(quote! {
+                (if #place.is_empty() { None } else { Some(#place.remove(0)) })
+            })
+        }
+        "unshift" => {
+            let place = convert_place_expr(obj);
+            let items: Vec<_> = args.iter().map(convert_expr_or_spread).collect();
+            Some(quote! {
+                {
+                    #place.splice(0..0, vec![#(#items),*]);
+                    #place.len() as f64
+                }
+            })
+        }
+        "at" => {
+            let index = convert_expr_or_spread(args.first()?);
+            Some(quote! {
+                {
+                    let items = &#obj_tokens;
+                    let index: f64 = #index;
+                    let index = if index < 0.0 { items.len() as f64 + index } else { index };
+                    if index >= 0.0 { items.get(index as usize).cloned() } else { None }
+                }
+            })
+        }
+        "fill" => {
+            let place = convert_place_expr(obj);
+            let relative = relative_index();
+            let value = convert_expr_or_spread(args.first()?);
+            let start = args
+                .get(1)
+                .map(convert_expr_or_spread)
+                .unwrap_or_else(|| quote! { 0.0 });
+            let end = args
+                .get(2)
+                .map(convert_expr_or_spread)
+                .unwrap_or_else(|| quote! { len });
+            Some(quote! {
+                {
+                    let len = #place.len() as f64;
+                    #relative
+                    let start = relative(#start);
+                    let end = relative(#end).max(start);
+                    #place[start..end].fill(#value);
+                    #place.clone()
+                }
+            })
+        }
+        _ => None,
+    }
+}
+
+/// `Array.from(...)` and `Array.isArray(...)`
+pub fn handle_static(method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    match method {
+        "from" => {
+            let source = args.first()?;
+            let map_fn = args.get(1);
+
+            // `Array.from({ length: n }, (_, i) => ...)`
+            if let Expr::Object(obj) = &*source.expr {
+                let length = obj.props.iter().find_map(|prop| match prop {
+                    PropOrSpread::Prop(prop) => match &**prop {
+                        Prop::KeyValue(kv)
+                            if kv.key.as_ident().is_some_and(|k| k.sym == "length") =>
+                        {
+                            Some(convert_expr(&kv.value))
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                })?;
+                return Some(match map_fn {
+                    Some(map_fn) => {
+                        let map_fn = convert_expr_or_spread(map_fn);
+                        quote! {
+                            (0..(#length) as usize).map(|i| (#map_fn)((), i as f64)).collect::<Vec<_>>()
+                        }
+                    }
+                    None => quote! { vec![serde_json::Value::Null; (#length) as usize] },
+                });
+            }
+
+            // Strings iterate by character
+            let items = match &*source.expr {
+                Expr::Lit(Lit::Str(_)) | Expr::Tpl(_) => {
+                    let s = convert_expr_or_spread(source);
+                    quote! { #s.chars().map(String::from) }
+                }
+                _ => {
+                    let s = convert_expr_or_spread(source);
+                    quote! { #s.clone().into_iter() }
+                }
+            };
+            Some(match map_fn {
+                Some(map_fn) if callback_arity(map_fn) > 1 => {
+                    let map_fn = convert_expr_or_spread(map_fn);
+                    quote! {
+                        #items.enumerate().map(|(idx, val)| (#map_fn)(val, idx as f64)).collect::<Vec<_>>()
+                    }
+                }
+                Some(map_fn) => {
+                    let map_fn = convert_expr_or_spread(map_fn);
+                    quote! { #items.map(#map_fn).collect::<Vec<_>>() }
+                }
+                None => quote! { #items.collect::<Vec<_>>() },
+            })
+        }
+        "isArray" => {
+            let value = args.first()?;
+            if is_array_expr(&value.expr) {
+                return Some(quote! { true });
             }
+            // Types are erased by now, so check the serialized shape
+            let value = convert_expr_or_spread(value);
+            Some(quote! {
+                serde_json::to_value(&#value).map(|v| v.is_array()).unwrap_or(false)
+            })
         }
         _ => None,
     }
@@ -0,0 +1,444 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use swc_ecma_ast::*;
+
+use super::super::convert::func::{convert_expr, convert_str_arg, to_snake_case};
+use super::super::convert::scope;
+use super::super::convert::type_mapper::map_inner_type;
+use super::fetch;
+use crate::diagnostics;
+
+// axios goes through the `crate::axios` support module: an instance owns a
+// `reqwest::Client` built from its config, and `crate::axios::send` resolves to
+// a `Response<T>` whose `data` is the typed body. As in axios, a status outside
+// 2xx rejects with a `crate::axios::Error` carrying `response.status` and the body.
+// Once caught it is an `AppError::Other`, and `err.response` downcasts it back.
+
+/// `AxiosInstance`, `AxiosResponse<T>` and `AxiosError`, unless user-defined
+pub fn is_axios_type(name: &str) -> bool {
+    matches!(name, "AxiosInstance" | "AxiosResponse" | "AxiosError")
+        && scope::lookup_struct(name).is_none()
+}
+
+/// The `crate::axios` type for an axios type reference
+pub fn map_type(type_ref: &TsTypeRef) -> TokenStream {
+    crate::support::require("axios");
+    let name = type_ref
+        .type_name
+        .as_ident()
+        .map(|ident| ident.sym.as_ref())
+        .unwrap_or_default();
+    match name {
+        "AxiosInstance" => quote! { crate::axios::Instance },
+        "AxiosError" => quote! { crate::axios::Error },
+        _ => {
+            let data = type_ref
+                .type_params
+                .as_ref()
+                .and_then(|params| params.params.first())
+                .map(|param| map_inner_type(param))
+                .unwrap_or_else(|| quote! { serde_json::Value });
+            quote! { crate::axios::Response<#data> }
+        }
+    }
+}
+
+pub fn instance_type() -> TsType {
+    scope::type_ref("AxiosInstance")
+}
+
+fn is_type_named(ts_type: &TsType, name: &str) -> bool {
+    matches!(
+        ts_type,
+        TsType::TsTypeRef(type_ref)
+            if type_ref.type_name.as_ident().is_some_and(|ident| ident.sym == name)
+    )
+}
+
+/// Whether `expr` is the default `axios` export
+fn is_default(expr: &Expr) -> bool {
+    expr.as_ident()
+        .is_some_and(|ident| ident.sym == "axios" && scope::lookup("axios").is_none())
+}
+
+/// The instance a request is made on: `axios` itself or an `AxiosInstance`
+fn instance(expr: &Expr) -> Option<TokenStream> {
+    if is_default(expr) {
+        crate::support::require("axios");
+        return Some(quote! { crate::axios::default() });
+    }
+    let ident = expr.as_ident()?;
+    scope::lookup_type(&ident.sym)
+        .is_some_and(|t| is_type_named(&t, "AxiosInstance"))
+        .then(|| convert_expr(expr))
+}
+
+/// Whether `expr` is `axios.create(...)`
+pub fn is_create(expr: &Expr) -> bool {
+    let Expr::Call(call) = expr else {
+        return false;
+    };
+    call.callee
+        .as_expr()
+        .and_then(|callee| callee.as_member())
+        .is_some_and(|member| {
+            is_default(&member.obj) && member.prop.as_ident().is_some_and(|p| p.sym == "create")
+        })
+}
+
+fn is_request_method(method: &str) -> bool {
+    matches!(
+        method,
+        "get" | "delete" | "head" | "options" | "post" | "put" | "patch" | "request"
+    )
+}
+
+/// `axios.get(...)`, `api.post(...)`, ..., optionally awaited
+fn request_call(expr: &Expr) -> Option<&CallExpr> {
+    match expr {
+        Expr::Paren(paren) => request_call(&paren.expr),
+        Expr::Await(await_expr) => request_call(&await_expr.arg),
+        Expr::Call(call) => {
+            let member = call.callee.as_expr()?.as_member()?;
+            let is_request = is_request_method(&member.prop.as_ident()?.sym)
+                && (is_default(&member.obj) || instance(&member.obj).is_some());
+            is_request.then_some(call)
+        }
+        _ => None,
+    }
+}
+
+/// `axios.create(config)`, requests on `axios` or an instance, and
+/// `instance.interceptors.*.use(...)`
+pub fn try_handle_call(call: &CallExpr) -> Option<TokenStream> {
+    let member = call.callee.as_expr()?.as_member()?;
+    let method = member.prop.as_ident()?.sym.as_ref();
+    if is_interceptors(&member.obj) {
+        diagnostics::warn(
+            "axios interceptors are not supported; set shared headers with `axios.create` \
+             or wrap the requests in a function",
+        );
+        return Some(quote! { () });
+    }
+    let instance = instance(&member.obj)?;
+    if is_default(&member.obj) && method == "create" {
+        return Some(create(call.args.first().map(|arg| &*arg.expr)));
+    }
+    if is_default(&member.obj) && method == "isAxiosError" {
+        let err = convert_expr(&call.args.first()?.expr);
+        return Some(quote! { crate::axios::is_error(&#err) });
+    }
+    let data_type = call
+        .type_args
+        .as_ref()
+        .and_then(|type_args| type_args.params.first())
+        .map(|param| map_inner_type(param));
+    let request = match method {
+        "get" | "delete" | "head" | "options" => {
+            let url = convert_str_arg(&call.args.first()?.expr);
+            let config_arg = call.args.get(1).map(|arg| &*arg.expr);
+            let start = start(instance, http_method(method), url, config_arg);
+            let config = config(config_arg, false);
+            quote! { #start #(#config)* }
+        }
+        "post" | "put" | "patch" => {
+            let url = convert_str_arg(&call.args.first()?.expr);
+            let config_arg = call.args.get(2).map(|arg| &*arg.expr);
+            let start = start(instance, http_method(method), url, config_arg);
+            let data = call.args.get(1).map(|data| {
+                let data = convert_expr(&data.expr);
+                quote! { .json(&#data) }
+            });
+            let config = config(config_arg, false);
+            quote! { #start #data #(#config)* }
+        }
+        "request" => request_config(instance, &call.args.first()?.expr)?,
+        _ => {
+            let name = format!("axios.{}", method);
+            diagnostics::warn(format!(
+                "`{}` is not supported; use `reqwest` directly",
+                name
+            ));
+            return Some(quote! { todo!(#name) });
+        }
+    };
+    let data_type = data_type.map(|t| quote! { ::<#t> });
+    Some(quote! { crate::axios::send #data_type(#request) })
+}
+
+/// `"get"` -> `reqwest::Method::GET`
+fn http_method(name: &str) -> TokenStream {
+    let ident = format_ident!("{}", name.to_uppercase());
+    quote! { reqwest::Method::#ident }
+}
+
+/// `instance.request(method, url)`, or `request_at` when the config of the
+/// request sets its own `baseURL`
+fn start(
+    instance: TokenStream,
+    method: TokenStream,
+    url: TokenStream,
+    config: Option<&Expr>,
+) -> TokenStream {
+    let base_url = config
+        .map(config_props)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|(name, _)| name == "baseURL");
+    match base_url {
+        Some((_, base_url)) => {
+            let base_url = convert_str_arg(&base_url);
+            quote! { #instance.request_at(#base_url, #method, #url) }
+        }
+        None => quote! { #instance.request(#method, #url) },
+    }
+}
+
+/// `axios.interceptors.request` and friends
+fn is_interceptors(expr: &Expr) -> bool {
+    let Expr::Member(member) = expr else {
+        return false;
+    };
+    let Expr::Member(interceptors) = &*member.obj else {
+        return false;
+    };
+    interceptors
+        .prop
+        .as_ident()
+        .is_some_and(|prop| prop.sym == "interceptors")
+        && instance(&interceptors.obj).is_some()
+}
+
+/// The properties of a config object literal, `{ headers }` included
+fn config_props(config: &Expr) -> Vec<(String, Expr)> {
+    let Expr::Object(object) = config else {
+        diagnostics::warn("axios config must be an object literal; it was ignored");
+        return Vec::new();
+    };
+    object
+        .props
+        .iter()
+        .filter_map(|prop| match prop.as_prop().map(|prop| &**prop) {
+            Some(Prop::KeyValue(kv)) => Some((fetch::prop_name(&kv.key)?, (*kv.value).clone())),
+            Some(Prop::Shorthand(ident)) => {
+                Some((ident.sym.to_string(), Expr::Ident(ident.clone())))
+            }
+            _ => {
+                diagnostics::warn("spread axios config is not supported and was ignored");
+                None
+            }
+        })
+        .collect()
+}
+
+fn timeout(ms: &Expr) -> TokenStream {
+    let ms = convert_expr(ms);
+    quote! { std::time::Duration::from_millis((#ms) as u64) }
+}
+
+/// The per-request options of a config, as builder calls. `url`, `method` and
+/// `data` are only read here for `axios.request(config)`, and `baseURL` by
+/// `start`.
+fn config(config: Option<&Expr>, is_request: bool) -> Vec<TokenStream> {
+    let Some(config) = config else {
+        return Vec::new();
+    };
+    let mut calls = Vec::new();
+    for (name, value) in config_props(config) {
+        match name.as_str() {
+            "params" => {
+                let params = convert_expr(&value);
+                calls.push(quote! { .query(&#params) });
+            }
+            "headers" => calls.push(fetch::headers(&value)),
+            "timeout" => {
+                let timeout = timeout(&value);
+                calls.push(quote! { .timeout(#timeout) });
+            }
+            "data" if is_request => {
+                let data = convert_expr(&value);
+                calls.push(quote! { .json(&#data) });
+            }
+            "url" | "method" if is_request => {}
+            "baseURL" => {}
+            other => diagnostics::warn(format!("the `{}` axios option was ignored", other)),
+        }
+    }
+    calls
+}
+
+/// `axios.request({ url, method, baseURL, data, params, headers, timeout })`
+fn request_config(instance: TokenStream, config: &Expr) -> Option<TokenStream> {
+    let props = config_props(config);
+    let find = |name: &str| props.iter().find(|(key, _)| key == name).map(|(_, v)| v);
+    let Some(url) = find("url") else {
+        diagnostics::warn("`axios.request` needs a `url` in its config");
+        return None;
+    };
+    let url = convert_str_arg(url);
+    let method = find("method")
+        .map(fetch::method)
+        .unwrap_or_else(|| quote! { reqwest::Method::GET });
+    let start = start(instance, method, url, Some(config));
+    let calls = self::config(Some(config), true);
+    Some(quote! { #start #(#calls)* })
+}
+
+/// `axios.create({ baseURL, timeout, headers })`
+fn create(config: Option<&Expr>) -> TokenStream {
+    let mut base_url = quote! { "" };
+    let mut timeout = quote! { None };
+    let mut headers = quote! { reqwest::header::HeaderMap::new() };
+    for (name, value) in config.map(config_props).unwrap_or_default() {
+        match name.as_str() {
+            "baseURL" => base_url = convert_expr(&value),
+            "timeout" => {
+                let duration = self::timeout(&value);
+                timeout = quote! { Some(#duration) };
+            }
+            "headers" => headers = fetch::header_map(&value),
+            other => diagnostics::warn(format!("the `{}` axios option was ignored", other)),
+        }
+    }
+    quote! { crate::axios::Instance::new(#base_url, #timeout, #headers) }
+}
+
+/// `return [await] axios.get(...)` resolves to the response body, unless the
+/// function is declared to return the `AxiosResponse` itself
+pub fn return_value(expr: &Expr) -> Option<TokenStream> {
+    let call = request_call(expr)?;
+    let future = try_handle_call(call)?;
+    let returns_response = scope::return_type().is_some_and(|return_type| {
+        let inner = match &return_type {
+            TsType::TsTypeRef(type_ref)
+                if type_ref
+                    .type_name
+                    .as_ident()
+                    .is_some_and(|ident| ident.sym == "Promise") =>
+            {
+                type_ref
+                    .type_params
+                    .as_ref()
+                    .and_then(|params| params.params.first())
+                    .map(|param| (**param).clone())
+            }
+            _ => Some(return_type.clone()),
+        };
+        inner.is_some_and(|inner| is_type_named(&inner, "AxiosResponse"))
+    });
+    if returns_response {
+        Some(quote! { return Ok(#future.await?); })
+    } else {
+        Some(quote! { return Ok(#future.await?.data); })
+    }
+}
+
+/// `const { data, status } = await axios.get<T>(...)`; `data` is known to be a `T`
+pub fn destructure(pattern: &ObjectPat, init: &Expr) -> Option<TokenStream> {
+    let call = request_call(init)?;
+    let data_type = call
+        .type_args
+        .as_ref()
+        .and_then(|type_args| type_args.params.first());
+    let bind = |field: &str, binding: &str| {
+        match (field, data_type) {
+            ("data", Some(data_type)) => scope::declare(binding, data_type),
+            ("status", _) => scope::declare(
+                binding,
+                &TsType::TsKeywordType(TsKeywordType {
+                    span: swc_common::DUMMY_SP,
+                    kind: TsKeywordTypeKind::TsNumberKeyword,
+                }),
+            ),
+            _ => {}
+        }
+        format_ident!("{}", to_snake_case(binding))
+    };
+    let fields: Vec<_> = pattern
+        .props
+        .iter()
+        .filter_map(|prop| match prop {
+            ObjectPatProp::Assign(assign) if assign.value.is_none() => {
+                let name = bind(&assign.key.sym, &assign.key.sym);
+                Some(quote! { mut #name })
+            }
+            ObjectPatProp::KeyValue(kv) => {
+                let field = fetch::prop_name(&kv.key)?;
+                let Pat::Ident(binding) = &*kv.value else {
+                    diagnostics::warn("nested destructuring of an axios response is not supported");
+                    return None;
+                };
+                let binding = bind(&field, &binding.id.sym);
+                let field = format_ident!("{}", field);
+                Some(quote! { #field: mut #binding })
+            }
+            _ => {
+                diagnostics::warn(
+                    "defaults and rest patterns are not supported when destructuring an axios response",
+                );
+                None
+            }
+        })
+        .collect();
+    let init = convert_expr(init);
+    Some(quote! { let crate::axios::Response { #(#fields,)* .. } = #init; })
+}
+
+/// `err.response` on a caught error, through parentheses and `!`
+fn error_response_member(expr: &Expr) -> Option<&MemberExpr> {
+    match expr {
+        Expr::Paren(paren) => error_response_member(&paren.expr),
+        Expr::TsNonNull(non_null) => error_response_member(&non_null.expr),
+        Expr::Member(member)
+            if super::super::convert::error::is_error_member(expr)
+                && member
+                    .prop
+                    .as_ident()
+                    .is_some_and(|prop| prop.sym == "response") =>
+        {
+            Some(member)
+        }
+        _ => None,
+    }
+}
+
+/// Whether `expr` is `err.response` on a caught error, which is `None` unless
+/// the error is an axios error carrying the server's answer
+pub fn is_error_response(expr: &Expr) -> bool {
+    error_response_member(expr).is_some()
+}
+
+/// `err.response`, an `Option<crate::axios::ErrorResponse>`
+pub fn error_response(err: TokenStream) -> TokenStream {
+    crate::support::require("axios");
+    quote! { crate::axios::response(&#err) }
+}
+
+/// `.status`, `.data` or `.headers` of `err.response`, and the `err` it is on
+fn error_response_field(
+    obj: &Expr,
+    prop: &MemberProp,
+) -> Option<(TokenStream, proc_macro2::Ident)> {
+    let response = error_response_member(obj)?;
+    let prop = prop.as_ident()?;
+    if !matches!(prop.sym.as_ref(), "status" | "data" | "headers") {
+        return None;
+    }
+    let response = error_response(convert_expr(&response.obj));
+    Some((response, format_ident!("{}", prop.sym.as_ref())))
+}
+
+/// `err.response.status` after a guard such as `if (err.response)`
+pub fn try_handle_member(member: &MemberExpr) -> Option<TokenStream> {
+    let (response, field) = error_response_field(&member.obj, &member.prop)?;
+    Some(quote! { #response.expect("error.response is undefined").#field })
+}
+
+/// `err.response?.status`, `None` when the request got no response
+pub fn try_handle_opt_chain(chain: &OptChainExpr) -> Option<TokenStream> {
+    let OptChainBase::Member(member) = &*chain.base else {
+        return None;
+    };
+    let (response, field) = error_response_field(&member.obj, &member.prop)?;
+    Some(quote! { #response.map(|response| response.#field) })
+}
@@ -0,0 +1,304 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use swc_ecma_ast::*;
+
+use super::super::convert::func::{convert_expr, convert_expr_or_spread};
+use super::super::convert::scope;
+use crate::{dependencies, diagnostics};
+
+// `Buffer`, `Uint8Array` and `ArrayBuffer` are `Vec<u8>`. Encodings are
+// `base64` and `hex` from the crates of the same name; `utf8` strings are
+// decoded lossily, like Node does.
+
+fn require_base64() {
+    dependencies::require("base64", "\"0.22\"");
+}
+
+fn require_hex() {
+    dependencies::require("hex", "\"0.4\"");
+}
+
+/// The TS `Buffer` type, for locals initialised with bytes
+pub fn buffer_type() -> TsType {
+    scope::type_ref("Buffer")
+}
+
+/// Whether `name` is a TS type represented as `Vec<u8>`
+pub fn is_bytes_type_name(name: &str) -> bool {
+    matches!(name, "Buffer" | "Uint8Array" | "ArrayBuffer")
+}
+
+fn type_name(ts_type: &TsType) -> Option<String> {
+    match ts_type {
+        TsType::TsTypeRef(type_ref) => Some(type_ref.type_name.as_ident()?.sym.to_string()),
+        _ => None,
+    }
+}
+
+/// Whether `name` is the global `Buffer`, not shadowed by a local
+fn is_global(expr: &Expr, name: &str) -> bool {
+    expr.as_ident()
+        .is_some_and(|ident| ident.sym == name && scope::lookup_type(name).is_none())
+}
+
+/// Whether `expr` is known to evaluate to a `Vec<u8>`
+pub fn is_buffer_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(paren) => is_buffer_expr(&paren.expr),
+        Expr::Ident(ident) => scope::lookup_type(&ident.sym)
+            .and_then(|ts_type| type_name(&ts_type))
+            .is_some_and(|name| is_bytes_type_name(&name)),
+        Expr::Call(call) => {
+            let Some(member) = call.callee.as_expr().and_then(|callee| callee.as_member()) else {
+                return super::crypto::is_bytes_call(call);
+            };
+            let Some(method) = member.prop.as_ident() else {
+                return false;
+            };
+            match method.sym.as_ref() {
+                "from" | "alloc" | "concat" => is_global(&member.obj, "Buffer"),
+                "subarray" | "slice" => is_buffer_expr(&member.obj),
+                "encode" => codec(&member.obj) == Some(Codec::Encoder),
+                _ => super::crypto::is_bytes_call(call),
+            }
+        }
+        _ => false,
+    }
+}
+
+/// A `TextEncoder` or `TextDecoder`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    Encoder,
+    Decoder,
+}
+
+fn codec_named(name: &str) -> Option<Codec> {
+    match name {
+        "TextEncoder" => Some(Codec::Encoder),
+        "TextDecoder" => Some(Codec::Decoder),
+        _ => None,
+    }
+}
+
+/// The codec `expr` is: `new TextEncoder()`, or a local holding one
+pub fn codec(expr: &Expr) -> Option<Codec> {
+    match expr {
+        Expr::Paren(paren) => codec(&paren.expr),
+        Expr::New(new_expr) => codec_named(&new_expr.callee.as_ident()?.sym),
+        Expr::Ident(ident) => codec_named(&type_name(&scope::lookup_type(&ident.sym)?)?),
+        _ => None,
+    }
+}
+
+/// The literal encoding argument of `Buffer.from` or `buf.toString`
+fn encoding(arg: Option<&ExprOrSpread>) -> String {
+    match arg.map(|arg| &*arg.expr) {
+        None => "utf8".to_string(),
+        Some(Expr::Lit(Lit::Str(s))) => s.value.to_string_lossy().to_lowercase(),
+        Some(_) => {
+            diagnostics::warn("only literal buffer encodings are supported; using utf8");
+            "utf8".to_string()
+        }
+    }
+}
+
+/// A string as a `&[u8]`
+fn str_bytes(expr: &Expr) -> TokenStream {
+    match expr {
+        Expr::Lit(Lit::Str(s)) => {
+            let s = s.value.as_str().unwrap_or("");
+            quote! { #s.as_bytes() }
+        }
+        Expr::Ident(_) | Expr::Member(_) | Expr::Call(_) => {
+            let expr = convert_expr(expr);
+            quote! { #expr.as_bytes() }
+        }
+        _ => {
+            let expr = convert_expr(expr);
+            quote! { (#expr).as_bytes() }
+        }
+    }
+}
+
+/// `bytes` (anything `AsRef<[u8]>`) as a string in `encoding`
+pub fn encode(bytes: TokenStream, encoding: &str) -> TokenStream {
+    match encoding {
+        "hex" => {
+            require_hex();
+            quote! { hex::encode(&#bytes) }
+        }
+        "base64" => {
+            require_base64();
+            quote! {
+                base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &#bytes)
+            }
+        }
+        "base64url" => {
+            require_base64();
+            quote! {
+                base64::Engine::encode(&base64::engine::general_purpose::URL_SAFE_NO_PAD, &#bytes)
+            }
+        }
+        "latin1" | "binary" | "ascii" => {
+            quote! { #bytes.iter().map(|&byte| byte as char).collect::<String>() }
+        }
+        encoding => {
+            if !matches!(encoding, "utf8" | "utf-8") {
+                diagnostics::warn(format!(
+                    "the `{}` encoding is not supported; decoding as utf8",
+                    encoding
+                ));
+            }
+            quote! { String::from_utf8_lossy(&#bytes).into_owned() }
+        }
+    }
+}
+
+/// A string in `encoding` as a `Vec<u8>`; invalid input decodes to no bytes
+fn decode(expr: &Expr, encoding: &str) -> TokenStream {
+    match encoding {
+        "hex" => {
+            require_hex();
+            let s = convert_expr(expr);
+            quote! { hex::decode(&#s).unwrap_or_default() }
+        }
+        "base64" | "base64url" => {
+            require_base64();
+            let s = convert_expr(expr);
+            let engine = if encoding == "base64" {
+                quote! { STANDARD }
+            } else {
+                quote! { URL_SAFE_NO_PAD }
+            };
+            quote! {
+                base64::Engine::decode(&base64::engine::general_purpose::#engine, &#s)
+                    .unwrap_or_default()
+            }
+        }
+        "latin1" | "binary" | "ascii" => {
+            let s = convert_expr(expr);
+            quote! { #s.chars().map(|c| c as u8).collect::<Vec<u8>>() }
+        }
+        encoding => {
+            if !matches!(encoding, "utf8" | "utf-8") {
+                diagnostics::warn(format!(
+                    "the `{}` encoding is not supported; encoding as utf8",
+                    encoding
+                ));
+            }
+            let bytes = str_bytes(expr);
+            quote! { #bytes.to_vec() }
+        }
+    }
+}
+
+/// Handle `Buffer.*` calls
+pub fn handle_static(method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    match method {
+        "from" => {
+            let source = &*args.first()?.expr;
+            if is_buffer_expr(source) {
+                let source = convert_expr(source);
+                return Some(quote! { #source.clone() });
+            }
+            if matches!(source, Expr::Array(_)) || super::array::is_array_expr(source) {
+                let source = convert_expr(source);
+                return Some(
+                    quote! { #source.iter().map(|&byte| byte as u8).collect::<Vec<u8>>() },
+                );
+            }
+            Some(decode(source, &encoding(args.get(1))))
+        }
+        "alloc" => {
+            let size = convert_expr_or_spread(args.first()?);
+            let fill = match args.get(1) {
+                Some(fill) => {
+                    let fill = convert_expr_or_spread(fill);
+                    quote! { (#fill) as u8 }
+                }
+                None => quote! { 0u8 },
+            };
+            Some(quote! { vec![#fill; (#size) as usize] })
+        }
+        "concat" => match &*args.first()?.expr {
+            Expr::Array(list) => {
+                let parts = list.elems.iter().flatten().map(|elem| {
+                    let elem = convert_expr(&elem.expr);
+                    quote! { #elem.as_slice() }
+                });
+                Some(quote! { [#(#parts),*].concat() })
+            }
+            list => {
+                let list = convert_expr(list);
+                Some(quote! { #list.concat() })
+            }
+        },
+        "byteLength" => {
+            let value = convert_expr_or_spread(args.first()?);
+            Some(quote! { (#value.len() as f64) })
+        }
+        "isBuffer" => {
+            let is_buffer = is_buffer_expr(&args.first()?.expr);
+            Some(quote! { #is_buffer })
+        }
+        _ => None,
+    }
+}
+
+/// Handle methods of buffers, `TextEncoder` and `TextDecoder`
+pub fn handle_method(obj: &Expr, method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    match (codec(obj), method) {
+        (Some(Codec::Encoder), "encode") => {
+            let bytes = match args.first() {
+                Some(s) => str_bytes(&s.expr),
+                None => quote! { b"" },
+            };
+            return Some(quote! { #bytes.to_vec() });
+        }
+        (Some(Codec::Decoder), "decode") => {
+            let bytes = convert_expr_or_spread(args.first()?);
+            return Some(quote! { String::from_utf8_lossy(&#bytes).into_owned() });
+        }
+        (Some(_), _) => return None,
+        (None, _) => {}
+    }
+    if !is_buffer_expr(obj) {
+        return None;
+    }
+    let buf = convert_expr(obj);
+    match method {
+        "toString" => Some(encode(buf, &encoding(args.first()))),
+        "equals" => {
+            let other = convert_expr_or_spread(args.first()?);
+            Some(quote! { #buf == #other })
+        }
+        "subarray" | "slice" => {
+            let start = match args.first() {
+                Some(start) => {
+                    let start = convert_expr_or_spread(start);
+                    quote! { (#start) as usize }
+                }
+                None => quote! { 0 },
+            };
+            match args.get(1) {
+                Some(end) => {
+                    let end = convert_expr_or_spread(end);
+                    Some(quote! { #buf[#start..(#end) as usize].to_vec() })
+                }
+                None => Some(quote! { #buf[#start..].to_vec() }),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The global `btoa` and `atob`
+pub fn handle_global(name: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    let arg = &*args.first()?.expr;
+    match name {
+        "btoa" => Some(encode(str_bytes(arg), "base64")),
+        "atob" => Some(encode(decode(arg, "base64"), "utf8")),
+        _ => None,
+    }
+}
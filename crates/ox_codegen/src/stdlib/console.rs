@@ -1,22 +1,312 @@
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use swc_ecma_ast::*;
 
-use super::super::convert::func::convert_expr_or_spread;
+use super::super::convert::func::convert_expr;
+use super::super::convert::scope;
+use super::{array, number, receiver};
+use crate::{dependencies, diagnostics};
+
+/// Severity of a console method, mapped to a stream or a `tracing` level
+#[derive(Clone, Copy)]
+enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
 
 /// Handle console.* calls
 pub fn handle(method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
     match method {
-        "log" => {
-            let args_tokens: Vec<_> = args.iter().map(convert_expr_or_spread).collect();
-            let fmt_str = "{} ".repeat(args.len()).trim_end().to_string();
-            Some(quote! { println!(#fmt_str, #(#args_tokens),*) })
+        "log" | "info" => Some(print(Level::Info, args)),
+        "debug" => Some(print(Level::Debug, args)),
+        "warn" => Some(print(Level::Warn, args)),
+        "error" => Some(print(Level::Error, args)),
+        "trace" => Some(print(Level::Trace, args)),
+        "table" => Some(table(args)),
+        "time" => {
+            let timer = timer_ident(args.first());
+            Some(quote! { let #timer = std::time::Instant::now() })
+        }
+        "timeEnd" | "timeLog" => {
+            let label = timer_label(args.first());
+            let timer = timer_ident(args.first());
+            Some(emit(
+                Level::Info,
+                "{}: {:.3}ms",
+                &[
+                    quote! { #label },
+                    quote! { #timer.elapsed().as_secs_f64() * 1000.0 },
+                ],
+            ))
         }
-        "error" => {
-            let args_tokens: Vec<_> = args.iter().map(convert_expr_or_spread).collect();
-            let fmt_str = "{} ".repeat(args.len()).trim_end().to_string();
-            Some(quote! { eprintln!(#fmt_str, #(#args_tokens),*) })
+        "assert" => {
+            let (condition, message) = args.split_first()?;
+            let condition = convert_expr(&condition.expr);
+            let (format, values) = format_args(message);
+            let format = if format.is_empty() {
+                "Assertion failed".to_string()
+            } else {
+                format!("Assertion failed: {}", format)
+            };
+            let report = emit(Level::Error, &format, &values);
+            // Like JS, a failed assertion is reported but does not throw
+            Some(quote! {
+                if !(#condition) {
+                    #report;
+                }
+            })
         }
         _ => None,
     }
 }
+
+fn print(level: Level, args: &[ExprOrSpread]) -> TokenStream {
+    let (format, values) = format_args(args);
+    emit(level, &format, &values)
+}
+
+/// `println!`/`eprintln!`, or a `tracing` event when that option is set
+fn emit(level: Level, format: &str, values: &[TokenStream]) -> TokenStream {
+    if crate::options().console_tracing {
+        dependencies::require("tracing", "\"0.1\"");
+        let level = match level {
+            Level::Trace => quote! { tracing::trace! },
+            Level::Debug => quote! { tracing::debug! },
+            Level::Info => quote! { tracing::info! },
+            Level::Warn => quote! { tracing::warn! },
+            Level::Error => quote! { tracing::error! },
+        };
+        return quote! { #level(#format #(, #values)*) };
+    }
+    match level {
+        Level::Debug | Level::Info => quote! { println!(#format #(, #values)*) },
+        Level::Warn | Level::Error => quote! { eprintln!(#format #(, #values)*) },
+        Level::Trace => {
+            let format = format!("Trace: {}", format);
+            quote! { eprintln!(#format #(, #values)*) }
+        }
+    }
+}
+
+/// Values without a JS-like `Display` impl (arrays, structs, maps, options)
+/// are printed as JSON, which every generated type supports
+fn needs_json(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(paren) => needs_json(&paren.expr),
+        Expr::Ident(ident) => match scope::lookup(&ident.sym) {
+            Some(TsType::TsTypeRef(type_ref)) => !type_ref
+                .type_name
+                .as_ident()
+                .is_some_and(|name| matches!(name.sym.as_ref(), "Date" | "String" | "Number")),
+            Some(TsType::TsUnionOrIntersectionType(_)) => true,
+            Some(ts_type) => array::is_array_type(&ts_type) || ts_type.is_ts_tuple_type(),
+            None => false,
+        },
+        _ => array::is_array_expr(expr),
+    }
+}
+
+/// Appends one argument to the format string, using its default representation
+fn push_value(expr: &Expr, format: &mut String, values: &mut Vec<TokenStream>) {
+    match expr {
+        // Literal text goes straight into the format string
+        Expr::Lit(Lit::Str(s)) => format.push_str(&escape(&s.value.to_string_lossy())),
+        Expr::Lit(Lit::Null(_)) => format.push_str("null"),
+        Expr::Ident(ident) if ident.sym == "undefined" => format.push_str("undefined"),
+        _ if needs_json(expr) => {
+            let value = convert_expr(expr);
+            format.push_str("{}");
+            values.push(json(&value));
+        }
+        _ => {
+            format.push_str("{}");
+            values.push(number::js_string_of(expr).unwrap_or_else(|| convert_expr(expr)));
+        }
+    }
+}
+
+/// `value` as JSON, with integral numbers written without a fraction as JS does
+fn json(value: &TokenStream) -> TokenStream {
+    let json_value = crate::runtime::path("json_value");
+    quote! { serde_json::to_string(&#json_value(&#value)).unwrap_or_default() }
+}
+
+/// `arg` as an `f64`; strings and other values convert as `Number(arg)` does
+fn numeric(arg: &Expr) -> TokenStream {
+    if receiver::classify(arg) == Some(receiver::Receiver::Number) {
+        let value = convert_expr(arg);
+        quote! { ((#value) as f64) }
+    } else {
+        number::to_number(arg)
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('{', "{{").replace('}', "}}")
+}
+
+/// Builds a format string from console arguments. A string literal first
+/// argument may use `%s`, `%d`/`%i`, `%f`, `%o`/`%O`/`%j` and `%c`; the
+/// remaining arguments are appended separated by spaces, as in JS.
+fn format_args(args: &[ExprOrSpread]) -> (String, Vec<TokenStream>) {
+    let mut format = String::new();
+    let mut values = Vec::new();
+    let mut rest = args.iter().map(|arg| &*arg.expr).peekable();
+
+    let template = match args.first().map(|arg| &*arg.expr) {
+        Some(Expr::Lit(Lit::Str(s))) if s.value.to_string_lossy().contains('%') => {
+            rest.next();
+            Some(s.value.to_string_lossy().into_owned())
+        }
+        _ => None,
+    };
+
+    if let Some(template) = template {
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            let spec = match (c, chars.peek().copied()) {
+                ('%', Some('%')) => {
+                    chars.next();
+                    format.push('%');
+                    continue;
+                }
+                ('%', Some(spec @ ('s' | 'd' | 'i' | 'f' | 'o' | 'O' | 'j' | 'c')))
+                    if rest.peek().is_some() =>
+                {
+                    chars.next();
+                    spec
+                }
+                ('{', _) => {
+                    format.push_str("{{");
+                    continue;
+                }
+                ('}', _) => {
+                    format.push_str("}}");
+                    continue;
+                }
+                (c, _) => {
+                    format.push(c);
+                    continue;
+                }
+            };
+            let Some(arg) = rest.next() else { break };
+            match spec {
+                's' => push_value(arg, &mut format, &mut values),
+                'd' | 'i' => {
+                    let value = numeric(arg);
+                    format.push_str("{}");
+                    values.push(quote! { (#value).trunc() });
+                }
+                'f' => {
+                    format.push_str("{}");
+                    values.push(numeric(arg));
+                }
+                'o' | 'O' | 'j' => {
+                    let value = convert_expr(arg);
+                    format.push_str("{}");
+                    values.push(json(&value));
+                }
+                // `%c` applies CSS, which a terminal cannot show
+                _ => {}
+            }
+        }
+    }
+
+    for arg in rest {
+        if !format.is_empty() {
+            format.push(' ');
+        }
+        push_value(arg, &mut format, &mut values);
+    }
+    (format, values)
+}
+
+/// `console.table(data)`: rows are array elements or object entries, and
+/// columns the union of the rows' keys
+fn table(args: &[ExprOrSpread]) -> TokenStream {
+    if args.len() > 1 {
+        diagnostics::warn("the `console.table` columns argument is not supported and was ignored");
+    }
+    let Some(data) = args.first() else {
+        return emit(Level::Info, "", &[]);
+    };
+    let data = convert_expr(&data.expr);
+    let output = emit(Level::Info, "{}", &[quote! { table }]);
+    let json_value = crate::runtime::path("json_value");
+    quote! {
+        {
+            let rows: Vec<(String, serde_json::Value)> =
+                match #json_value(&#data) {
+                    serde_json::Value::Array(items) => items
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, row)| (index.to_string(), row))
+                        .collect(),
+                    serde_json::Value::Object(entries) => entries.into_iter().collect(),
+                    value => vec![(String::new(), value)],
+                };
+            let mut columns: Vec<String> = Vec::new();
+            for key in rows.iter().filter_map(|(_, row)| row.as_object()).flat_map(|row| row.keys()) {
+                if !columns.contains(key) {
+                    columns.push(key.clone());
+                }
+            }
+            let mut table = String::from("(index)");
+            for column in &columns {
+                table.push('\t');
+                table.push_str(column);
+            }
+            if columns.is_empty() {
+                table.push_str("\tValues");
+            }
+            for (index, row) in &rows {
+                table.push('\n');
+                table.push_str(index);
+                match row.as_object() {
+                    Some(row) => {
+                        for column in &columns {
+                            table.push('\t');
+                            table.push_str(&row.get(column).map(|v| v.to_string()).unwrap_or_default());
+                        }
+                    }
+                    None => {
+                        table.push('\t');
+                        table.push_str(&row.to_string());
+                    }
+                }
+            }
+            #output;
+        }
+    }
+}
+
+/// The label of `console.time(label)`, `"default"` when omitted
+fn timer_label(arg: Option<&ExprOrSpread>) -> String {
+    match arg.map(|arg| &*arg.expr) {
+        Some(Expr::Lit(Lit::Str(s))) => s.value.to_string_lossy().into_owned(),
+        None => "default".to_string(),
+        Some(_) => {
+            diagnostics::warn("`console.time` labels must be string literals; using \"default\"");
+            "default".to_string()
+        }
+    }
+}
+
+/// Timers are locals named after their label, so `console.time` and
+/// `console.timeEnd` must be called in the same function
+fn timer_ident(arg: Option<&ExprOrSpread>) -> proc_macro2::Ident {
+    let label: String = timer_label(arg)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format_ident!("console_timer_{}", label)
+}
@@ -0,0 +1,197 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use swc_ecma_ast::*;
+
+use super::super::convert::func::{convert_expr, convert_expr_or_spread, convert_str_arg};
+use super::super::convert::scope::{self, NodeModule};
+use crate::{dependencies, diagnostics};
+
+// `crypto` from Node and the Web Crypto global. Hashes and HMACs are only
+// supported as a single chain, `createHash(alg).update(data).digest(enc)`,
+// which becomes one `sha2`/`hmac` expression.
+
+/// Whether `callee` is the `crypto` function `name`, imported or as a member
+fn is_crypto_function(callee: &Expr, name: &str) -> bool {
+    match callee {
+        Expr::Ident(ident) => scope::lookup_node_import(&ident.sym).is_some_and(|import| {
+            import.module == NodeModule::Crypto && import.member.as_deref() == Some(name)
+        }),
+        Expr::Member(member) => {
+            member.prop.as_ident().is_some_and(|prop| prop.sym == name)
+                && super::node_module(&member.obj) == Some(NodeModule::Crypto)
+        }
+        _ => false,
+    }
+}
+
+/// The crate and hasher type for a Node digest algorithm name, adding the crate
+fn hasher(algorithm: &Expr) -> Option<(TokenStream, TokenStream)> {
+    let Expr::Lit(Lit::Str(algorithm)) = algorithm else {
+        diagnostics::warn("only literal hash algorithm names are supported");
+        return None;
+    };
+    let algorithm = algorithm.value.to_string_lossy().to_lowercase();
+    let hasher = match algorithm.as_str() {
+        "sha224" => quote! { Sha224 },
+        "sha256" => quote! { Sha256 },
+        "sha384" => quote! { Sha384 },
+        "sha512" => quote! { Sha512 },
+        "sha1" => {
+            dependencies::require("sha1", "\"0.10\"");
+            return Some((quote! { sha1 }, quote! { Sha1 }));
+        }
+        "md5" => {
+            dependencies::require("md-5", "\"0.10\"");
+            return Some((quote! { md5 }, quote! { Md5 }));
+        }
+        algorithm => {
+            diagnostics::warn(format!(
+                "the `{}` hash algorithm is not supported",
+                algorithm
+            ));
+            return None;
+        }
+    };
+    dependencies::require("sha2", "\"0.10\"");
+    Some((quote! { sha2 }, hasher))
+}
+
+/// A `createHash(...)` or `createHmac(...)` call followed by `.update(...)`s
+struct Chain<'a> {
+    hmac: bool,
+    args: &'a [ExprOrSpread],
+    updates: Vec<&'a ExprOrSpread>,
+}
+
+fn chain(expr: &Expr) -> Option<Chain<'_>> {
+    let call = expr.as_call()?;
+    let callee = call.callee.as_expr()?;
+    if is_crypto_function(callee, "createHash") || is_crypto_function(callee, "createHmac") {
+        return Some(Chain {
+            hmac: is_crypto_function(callee, "createHmac"),
+            args: &call.args,
+            updates: Vec::new(),
+        });
+    }
+    let member = callee.as_member()?;
+    if member.prop.as_ident()?.sym != "update" {
+        return None;
+    }
+    let mut chain = chain(&member.obj)?;
+    chain.updates.push(call.args.first()?);
+    Some(chain)
+}
+
+/// `createHash(alg).update(data).digest(enc)` as a `sha2` (or `hmac`) digest
+fn digest(chain: &Chain, encoding: Option<&ExprOrSpread>) -> Option<TokenStream> {
+    let (krate, hasher) = hasher(&chain.args.first()?.expr)?;
+    let updates = chain.updates.iter().map(|data| convert_str_arg(&data.expr));
+    let digest = if chain.hmac {
+        dependencies::require("hmac", "\"0.12\"");
+        let key = match &*chain.args.get(1)?.expr {
+            Expr::Lit(Lit::Str(key)) => {
+                let key = key.value.as_str().unwrap_or("");
+                quote! { #key.as_bytes() }
+            }
+            key => {
+                let key = convert_expr(key);
+                quote! { #key.as_ref() }
+            }
+        };
+        quote! {
+            use hmac::Mac;
+            let digest = <hmac::Hmac<#krate::#hasher>>::new_from_slice(#key)
+                .expect("HMAC accepts keys of any length")
+                #(.chain_update(#updates))*
+                .finalize()
+                .into_bytes();
+        }
+    } else {
+        quote! {
+            use #krate::Digest;
+            let digest = #krate::#hasher::new()#(.chain_update(#updates))*.finalize();
+        }
+    };
+    let value = match encoding {
+        Some(encoding) => match &*encoding.expr {
+            Expr::Lit(Lit::Str(s)) => {
+                super::buffer::encode(quote! { digest }, &s.value.to_string_lossy())
+            }
+            _ => {
+                diagnostics::warn("only literal digest encodings are supported");
+                return None;
+            }
+        },
+        None => quote! { digest.to_vec() },
+    };
+    Some(quote! {
+        {
+            #digest
+            #value
+        }
+    })
+}
+
+/// Whether `call` returns bytes: `randomBytes(n)` or an unencoded digest
+pub fn is_bytes_call(call: &CallExpr) -> bool {
+    let Some(callee) = call.callee.as_expr() else {
+        return false;
+    };
+    if is_crypto_function(callee, "randomBytes") {
+        return true;
+    }
+    callee.as_member().is_some_and(|member| {
+        call.args.is_empty()
+            && member
+                .prop
+                .as_ident()
+                .is_some_and(|prop| prop.sym == "digest")
+            && chain(&member.obj).is_some()
+    })
+}
+
+/// `hash.digest(enc)` at the end of a `createHash` chain
+pub fn handle_method(obj: &Expr, method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    if method != "digest" {
+        return None;
+    }
+    digest(&chain(obj)?, args.first())
+}
+
+/// Handle `crypto.*` calls
+pub fn handle(method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    match method {
+        "randomUUID" => {
+            dependencies::require("uuid", "{ version = \"1\", features = [\"v4\"] }");
+            Some(quote! { uuid::Uuid::new_v4().to_string() })
+        }
+        "randomBytes" => {
+            let size = convert_expr_or_spread(args.first()?);
+            Some(quote! {
+                {
+                    let mut bytes = vec![0u8; (#size) as usize];
+                    rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut bytes);
+                    bytes
+                }
+            })
+        }
+        "randomInt" => {
+            let (min, max) = match args {
+                [max] => (quote! { 0 }, convert_expr_or_spread(max)),
+                [min, max, ..] => (convert_expr_or_spread(min), convert_expr_or_spread(max)),
+                [] => return None,
+            };
+            Some(quote! {
+                (rand::Rng::gen_range(&mut rand::thread_rng(), (#min) as i64..(#max) as i64) as f64)
+            })
+        }
+        "createHash" | "createHmac" => {
+            diagnostics::warn(format!(
+                "`{}` is only supported chained as `{}(...).update(...).digest(...)`",
+                method, method
+            ));
+            None
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,299 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use swc_ecma_ast::*;
+
+use super::super::convert::func::{convert_expr, convert_expr_or_spread, convert_place_expr};
+use super::super::convert::scope;
+use crate::{dependencies, diagnostics};
+
+/// `chrono` with ISO-8601 serde support, added to `Cargo.toml` once a `Date` is used
+pub fn require_chrono() {
+    dependencies::require("chrono", "{ version = \"0.4\", features = [\"serde\"] }");
+}
+
+/// The TS `Date` type, for locals initialised with a date
+pub fn date_type() -> TsType {
+    TsType::TsTypeRef(TsTypeRef {
+        span: swc_common::DUMMY_SP,
+        type_name: TsEntityName::Ident(Ident::new_no_ctxt("Date".into(), swc_common::DUMMY_SP)),
+        type_params: None,
+    })
+}
+
+fn is_date_type(ts_type: &TsType) -> bool {
+    matches!(
+        ts_type,
+        TsType::TsTypeRef(TsTypeRef { type_name: TsEntityName::Ident(ident), .. }) if ident.sym == "Date"
+    )
+}
+
+/// Whether `expr` is known to evaluate to a `chrono::DateTime<Utc>`
+pub fn is_date_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(paren) => is_date_expr(&paren.expr),
+        Expr::New(new_expr) => new_expr
+            .callee
+            .as_ident()
+            .is_some_and(|callee| callee.sym == "Date"),
+        Expr::Ident(ident) => scope::lookup(&ident.sym).is_some_and(|t| is_date_type(&t)),
+        // `order.createdAt` where `Order` is an interface with a `Date` field
+        Expr::Member(member) => {
+            let (Some(obj), Some(prop)) = (member.obj.as_ident(), member.prop.as_ident()) else {
+                return false;
+            };
+            let Some(TsType::TsTypeRef(type_ref)) = scope::lookup(&obj.sym) else {
+                return false;
+            };
+            type_ref
+                .type_name
+                .as_ident()
+                .and_then(|name| scope::lookup_struct(&name.sym))
+                .is_some_and(|fields| {
+                    fields
+                        .iter()
+                        .any(|f| f.name == prop.sym.as_ref() && is_date_type(&f.ts_type))
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Expressions that clearly produce a number (milliseconds when passed to `new Date`)
+fn is_number_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(paren) => is_number_expr(&paren.expr),
+        Expr::Lit(Lit::Num(_)) => true,
+        Expr::Unary(unary) => unary.op == UnaryOp::Minus,
+        Expr::Bin(bin) => {
+            matches!(bin.op, BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div)
+                || (bin.op == BinaryOp::Add
+                    && is_number_expr(&bin.left)
+                    && is_number_expr(&bin.right))
+        }
+        Expr::Ident(ident) => scope::lookup(&ident.sym).is_some_and(|t| {
+            matches!(
+                t,
+                TsType::TsKeywordType(TsKeywordType {
+                    kind: TsKeywordTypeKind::TsNumberKeyword,
+                    ..
+                })
+            )
+        }),
+        Expr::Call(call) => {
+            let Callee::Expr(callee) = &call.callee else {
+                return false;
+            };
+            let Expr::Member(member) = &**callee else {
+                return false;
+            };
+            member
+                .prop
+                .as_ident()
+                .is_some_and(|prop| matches!(prop.sym.as_ref(), "getTime" | "valueOf" | "now"))
+        }
+        _ => false,
+    }
+}
+
+/// A date used as a number (`end - start`) is its epoch milliseconds
+pub fn to_millis(expr: &Expr) -> TokenStream {
+    let date = convert_expr(expr);
+    quote! { (#date.timestamp_millis() as f64) }
+}
+
+/// `new Date(...)`
+pub fn handle_new(args: &[ExprOrSpread]) -> TokenStream {
+    require_chrono();
+    match args {
+        [] => quote! { chrono::Utc::now() },
+        [arg] if is_date_expr(&arg.expr) => {
+            let date = convert_expr_or_spread(arg);
+            quote! { #date.clone() }
+        }
+        [arg] if is_number_expr(&arg.expr) => {
+            let millis = convert_expr_or_spread(arg);
+            quote! {
+                chrono::DateTime::from_timestamp_millis((#millis) as i64).expect("invalid date")
+            }
+        }
+        [arg] => {
+            let text = convert_expr_or_spread(arg);
+            let parsed = parse_date(&text);
+            quote! { #parsed.expect("invalid date") }
+        }
+        // `new Date(year, monthIndex, day, ...)`
+        parts => {
+            diagnostics::warn(
+                "`new Date(year, month, ...)` is interpreted in UTC, not in the local time zone",
+            );
+            let defaults = [1970.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0];
+            let parts: Vec<_> = defaults
+                .iter()
+                .enumerate()
+                .map(|(i, default)| match parts.get(i) {
+                    Some(part) => convert_expr_or_spread(part),
+                    None => quote! { #default },
+                })
+                .collect();
+            make_date(&parts)
+        }
+    }
+}
+
+/// The components of a date in `new Date(...)` order, which is also the
+/// order a setter's arguments replace them in
+const COMPONENTS: [&str; 7] = [
+    "FullYear",
+    "Month",
+    "Date",
+    "Hours",
+    "Minutes",
+    "Seconds",
+    "Milliseconds",
+];
+
+/// JS `MakeDate`: components outside their range roll over into the next
+/// unit (`setDate(32)` is in the next month), so the date is computed as the
+/// start of the month plus an offset rather than by setting fields
+fn make_date(parts: &[TokenStream]) -> TokenStream {
+    let [year, month, day, hour, minute, second, millis] = parts else {
+        unreachable!("a date has seven components");
+    };
+    quote! {
+        {
+            let months = ((#year) as f64).trunc() as i64 * 12 + ((#month) as f64).trunc() as i64;
+            let offset = (((#day) as f64).trunc() - 1.0) * 86_400_000.0
+                + ((#hour) as f64).trunc() * 3_600_000.0
+                + ((#minute) as f64).trunc() * 60_000.0
+                + ((#second) as f64).trunc() * 1000.0
+                + ((#millis) as f64).trunc();
+            chrono::NaiveDate::from_ymd_opt(
+                months.div_euclid(12) as i32,
+                months.rem_euclid(12) as u32 + 1,
+                1,
+            )
+            .expect("invalid date")
+            .and_time(chrono::NaiveTime::MIN)
+            .and_utc()
+                + chrono::Duration::milliseconds(offset as i64)
+        }
+    }
+}
+
+/// Parses the formats `Date` accepts in practice: RFC 3339, and date-only
+/// ISO strings, which JS reads as UTC midnight. Yields an `Option`.
+fn parse_date(text: &TokenStream) -> TokenStream {
+    quote! {
+        {
+            let text: &str = &#text;
+            chrono::DateTime::parse_from_rfc3339(text)
+                .map(|d| d.with_timezone(&chrono::Utc))
+                .ok()
+                .or_else(|| {
+                    chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                        .ok()
+                        .and_then(|d| d.and_hms_opt(0, 0, 0))
+                        .map(|d| d.and_utc())
+                })
+        }
+    }
+}
+
+/// Handle Date.* calls
+pub fn handle_static(method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    match method {
+        "now" => {
+            require_chrono();
+            Some(quote! { (chrono::Utc::now().timestamp_millis() as f64) })
+        }
+        "parse" => {
+            require_chrono();
+            let parsed = parse_date(&convert_expr_or_spread(args.first()?));
+            Some(quote! { #parsed.map_or(f64::NAN, |d| d.timestamp_millis() as f64) })
+        }
+        _ => None,
+    }
+}
+
+/// Reads one of [`COMPONENTS`] (or the weekday or offset) as an `f64`. The
+/// local-time accessors read UTC: the generated service runs in UTC.
+fn component(date: &TokenStream, name: &str) -> Option<TokenStream> {
+    Some(match name {
+        "FullYear" => quote! { chrono::Datelike::year(&#date) as f64 },
+        "Month" => quote! { chrono::Datelike::month0(&#date) as f64 },
+        "Date" => quote! { chrono::Datelike::day(&#date) as f64 },
+        "Day" => {
+            quote! { chrono::Datelike::weekday(&#date).num_days_from_sunday() as f64 }
+        }
+        "Hours" => quote! { chrono::Timelike::hour(&#date) as f64 },
+        "Minutes" => quote! { chrono::Timelike::minute(&#date) as f64 },
+        "Seconds" => quote! { chrono::Timelike::second(&#date) as f64 },
+        "Milliseconds" => quote! { #date.timestamp_subsec_millis() as f64 },
+        "TimezoneOffset" => quote! { 0.0 },
+        _ => return None,
+    })
+}
+
+/// Whether `method` is a `Date` setter, which reassigns its receiver
+pub fn is_setter(method: &str) -> bool {
+    method == "setTime"
+        || method
+            .strip_prefix("setUTC")
+            .or_else(|| method.strip_prefix("set"))
+            .is_some_and(|name| COMPONENTS.contains(&name))
+}
+
+/// Handle method calls on a known date
+pub fn handle_method(obj: &Expr, method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    let date = convert_expr(obj);
+
+    match method {
+        "getTime" | "valueOf" => Some(quote! { (#date.timestamp_millis() as f64) }),
+        "toISOString" | "toJSON" => Some(quote! {
+            #date.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+        }),
+        "setTime" => {
+            let place = convert_place_expr(obj);
+            let millis = convert_expr_or_spread(args.first()?);
+            Some(quote! {
+                {
+                    #place = chrono::DateTime::from_timestamp_millis((#millis) as i64).expect("invalid date");
+                    #place.timestamp_millis() as f64
+                }
+            })
+        }
+        _ => {
+            if let Some(name) = method
+                .strip_prefix("getUTC")
+                .or_else(|| method.strip_prefix("get"))
+            {
+                return component(&date, name);
+            }
+            let name = method
+                .strip_prefix("setUTC")
+                .or_else(|| method.strip_prefix("set"))?;
+            let first = COMPONENTS.iter().position(|c| *c == name)?;
+            // `setFullYear(y, m, d)` and `setHours(h, m, s, ms)` take the
+            // following components up to the end of the date or the time
+            let last = if first < 3 { 2 } else { 6 };
+            let current = quote! { date };
+            let mut parts: Vec<_> = COMPONENTS
+                .iter()
+                .filter_map(|c| component(&current, c))
+                .collect();
+            for (index, arg) in (first..=last).zip(args) {
+                parts[index] = convert_expr_or_spread(arg);
+            }
+            let made = make_date(&parts);
+            let place = convert_place_expr(obj);
+            // JS setters mutate the date and return the new timestamp
+            Some(quote! {
+                {
+                    let date = #date;
+                    #place = #made;
+                    #place.timestamp_millis() as f64
+                }
+            })
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use swc_ecma_ast::*;
+
+use super::super::convert::func::{convert_expr, convert_str_arg};
+use super::super::convert::scope;
+use super::super::convert::type_mapper::map_inner_type;
+use super::timers;
+use crate::diagnostics;
+
+// `fetch(url, init)` becomes a `reqwest::RequestBuilder` on a client shared by
+// the whole program, and `.send()` is its promise: `await fetch(...)` yields a
+// `reqwest::Response`, whose error converts into `crate::AppError` with `?`.
+
+pub fn response_type() -> TsType {
+    TsType::TsTypeRef(TsTypeRef {
+        span: swc_common::DUMMY_SP,
+        type_name: TsEntityName::Ident(Ident::new_no_ctxt("Response".into(), swc_common::DUMMY_SP)),
+        type_params: None,
+    })
+}
+
+/// Whether `callee` is the global `fetch`
+fn is_fetch(callee: &Callee) -> bool {
+    callee
+        .as_expr()
+        .and_then(|callee| callee.as_ident())
+        .is_some_and(|callee| callee.sym == "fetch" && scope::lookup("fetch").is_none())
+}
+
+/// Whether `expr` is `await fetch(...)`, which yields a `Response`
+pub fn is_awaited_fetch(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(paren) => is_awaited_fetch(&paren.expr),
+        Expr::Await(await_expr) => await_expr
+            .arg
+            .as_call()
+            .is_some_and(|call| is_fetch(&call.callee)),
+        _ => false,
+    }
+}
+
+/// Whether `expr` is a promise settled by `reqwest`, whose error is a
+/// `reqwest::Error` rather than a `crate::AppError`: `fetch(...)` or a body read
+pub fn is_fetch_promise(expr: &Expr) -> bool {
+    let Expr::Call(call) = expr else {
+        return false;
+    };
+    if is_fetch(&call.callee) {
+        return true;
+    }
+    call.callee
+        .as_expr()
+        .and_then(|callee| callee.as_member())
+        .is_some_and(|member| {
+            is_response_expr(&member.obj)
+                && member.prop.as_ident().is_some_and(|prop| {
+                    matches!(prop.sym.as_ref(), "json" | "text" | "arrayBuffer" | "bytes")
+                })
+        })
+}
+
+/// Whether `Response` is the fetch API type rather than a user interface
+pub fn is_response_type(ts_type: &TsType) -> bool {
+    matches!(
+        ts_type,
+        TsType::TsTypeRef(type_ref)
+            if type_ref.type_name.as_ident().is_some_and(|name| name.sym == "Response")
+    ) && scope::lookup_struct("Response").is_none()
+}
+
+/// Whether `expr` is known to be a `reqwest::Response`
+pub fn is_response_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(paren) => is_response_expr(&paren.expr),
+        Expr::Ident(ident) => scope::lookup(&ident.sym).is_some_and(|t| is_response_type(&t)),
+        _ => false,
+    }
+}
+
+/// `fetch(url, init)`, `res.json<T>()`, `res.text()` and `res.headers.get(name)`
+pub fn try_handle_call(call: &CallExpr) -> Option<TokenStream> {
+    if is_fetch(&call.callee) {
+        return handle(&call.args);
+    }
+    let member = call.callee.as_expr()?.as_member()?;
+    let method = member.prop.as_ident()?.sym.as_ref();
+    if is_response_expr(&member.obj) {
+        return handle_method(&member.obj, method, call.type_args.as_deref());
+    }
+    let headers = member.obj.as_member()?;
+    if is_response_expr(&headers.obj) && headers.prop.as_ident()?.sym == "headers" {
+        return handle_headers_method(&headers.obj, method, &call.args);
+    }
+    None
+}
+
+/// The key of an object literal property, as written
+pub fn prop_name(key: &PropName) -> Option<String> {
+    match key {
+        PropName::Ident(ident) => Some(ident.sym.to_string()),
+        PropName::Str(s) => Some(s.value.to_string_lossy().into_owned()),
+        _ => None,
+    }
+}
+
+/// `"POST"` -> `reqwest::Method::POST`; other expressions are parsed at runtime
+pub fn method(expr: &Expr) -> TokenStream {
+    if let Expr::Lit(Lit::Str(s)) = expr {
+        let name = s.value.to_string_lossy().to_uppercase();
+        if matches!(
+            name.as_str(),
+            "GET" | "POST" | "PUT" | "DELETE" | "HEAD" | "OPTIONS" | "CONNECT" | "PATCH" | "TRACE"
+        ) {
+            let ident = quote::format_ident!("{}", name);
+            return quote! { reqwest::Method::#ident };
+        }
+    }
+    let method = convert_expr(expr);
+    quote! {
+        reqwest::Method::from_bytes(#method.to_uppercase().as_bytes()).unwrap_or(reqwest::Method::GET)
+    }
+}
+
+/// A `reqwest::header::HeaderMap` from any headers object (a literal, a
+/// `Record<string, string>`, an object), read through serde and skipping
+/// entries that are not valid header text
+pub fn header_map(expr: &Expr) -> TokenStream {
+    let headers = convert_expr(expr);
+    quote! {
+        serde_json::to_value(&#headers)
+            .ok()
+            .and_then(|value| value.as_object().cloned())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((
+                    reqwest::header::HeaderName::from_bytes(name.as_bytes()).ok()?,
+                    reqwest::header::HeaderValue::from_str(value.as_str()?).ok()?,
+                ))
+            })
+            .collect::<reqwest::header::HeaderMap>()
+    }
+}
+
+/// `headers: { "Content-Type": "application/json" }` adds each header; any
+/// other value goes through [`header_map`]
+pub fn headers(expr: &Expr) -> TokenStream {
+    let Expr::Object(object) = expr else {
+        let headers = header_map(expr);
+        return quote! { .headers(#headers) };
+    };
+    let headers = object.props.iter().filter_map(|prop| {
+        let kv = prop.as_prop().and_then(|prop| prop.as_key_value());
+        let Some((name, value)) = kv.and_then(|kv| Some((prop_name(&kv.key)?, &kv.value))) else {
+            diagnostics::warn(
+                "only `name: value` request headers are supported; others were ignored",
+            );
+            return None;
+        };
+        let value = convert_str_arg(value);
+        Some(quote! { .header(#name, #value) })
+    });
+    quote! { #(#headers)* }
+}
+
+/// `signal: AbortSignal.timeout(ms)` -> a request timeout
+fn signal(expr: &Expr) -> Option<TokenStream> {
+    let Expr::Call(call) = expr else {
+        return None;
+    };
+    let member = call.callee.as_expr()?.as_member()?;
+    let is_timeout =
+        member.obj.as_ident()?.sym == "AbortSignal" && member.prop.as_ident()?.sym == "timeout";
+    is_timeout.then(|| {
+        let duration = timers::duration(call.args.first());
+        quote! { .timeout(#duration) }
+    })
+}
+
+/// The `RequestInit` options of `fetch(url, init)`, as builder calls
+fn request_init(init: &Expr) -> (Option<TokenStream>, Vec<TokenStream>) {
+    let Expr::Object(object) = init else {
+        diagnostics::warn("fetch options must be an object literal; they were ignored");
+        return (None, Vec::new());
+    };
+    let mut method_name = None;
+    let mut calls = Vec::new();
+    for prop in &object.props {
+        // `{ headers }` is `{ headers: headers }`
+        let (name, value) = match prop.as_prop().map(|prop| &**prop) {
+            Some(Prop::KeyValue(kv)) => match prop_name(&kv.key) {
+                Some(name) => (name, (*kv.value).clone()),
+                None => continue,
+            },
+            Some(Prop::Shorthand(ident)) => (ident.sym.to_string(), Expr::Ident(ident.clone())),
+            _ => {
+                diagnostics::warn("spread fetch options are not supported and were ignored");
+                continue;
+            }
+        };
+        match name.as_str() {
+            "method" => method_name = Some(method(&value)),
+            "headers" => calls.push(headers(&value)),
+            "body" => {
+                let body = convert_expr(&value);
+                calls.push(quote! { .body(#body) });
+            }
+            "timeout" => {
+                let duration = convert_expr(&value);
+                calls.push(
+                    quote! { .timeout(std::time::Duration::from_millis((#duration) as u64)) },
+                );
+            }
+            "signal" => match signal(&value) {
+                Some(timeout) => calls.push(timeout),
+                None => diagnostics::warn(
+                    "only `AbortSignal.timeout(ms)` fetch signals are supported; \
+                     cancel by dropping the future instead",
+                ),
+            },
+            other => diagnostics::warn(format!("the `{}` fetch option was ignored", other)),
+        }
+    }
+    (method_name, calls)
+}
+
+/// `fetch(url, init)`
+fn handle(args: &[ExprOrSpread]) -> Option<TokenStream> {
+    let url = convert_str_arg(&args.first()?.expr);
+    let (method, calls) = match args.get(1) {
+        Some(init) => request_init(&init.expr),
+        None => (None, Vec::new()),
+    };
+    let method = method.unwrap_or_else(|| quote! { reqwest::Method::GET });
+    Some(quote! {
+        {
+            static CLIENT: std::sync::LazyLock<reqwest::Client> =
+                std::sync::LazyLock::new(reqwest::Client::new);
+            &*CLIENT
+        }
+        .request(#method, #url)
+        #(#calls)*
+        .send()
+    })
+}
+
+/// `res.ok`, `res.status`, `res.statusText` and `res.headers`
+pub fn property(response: &Expr, name: &str) -> Option<TokenStream> {
+    let response = convert_expr(response);
+    match name {
+        "ok" => Some(quote! { #response.status().is_success() }),
+        "status" => Some(quote! { (#response.status().as_u16() as f64) }),
+        "statusText" => Some(quote! {
+            #response.status().canonical_reason().unwrap_or_default().to_string()
+        }),
+        "headers" => Some(quote! { #response.headers() }),
+        "url" => Some(quote! { #response.url().to_string() }),
+        _ => None,
+    }
+}
+
+/// `res.json<T>()` and `res.text()`, which read the body and resolve to a value
+fn handle_method(
+    response: &Expr,
+    method: &str,
+    type_args: Option<&TsTypeParamInstantiation>,
+) -> Option<TokenStream> {
+    let response = convert_expr(response);
+    match method {
+        "json" => Some(json(
+            response,
+            type_args.and_then(|t| t.params.first()).map(|t| &**t),
+        )),
+        "text" => Some(quote! { #response.text() }),
+        "arrayBuffer" | "bytes" => Some(quote! {
+            async { #response.bytes().await.map(|bytes| bytes.to_vec()) }
+        }),
+        _ => None,
+    }
+}
+
+/// `res.json<T>()` deserializes into `T`. Without a type argument the target
+/// is left to inference, like an untyped `JSON.parse`.
+fn json(response: TokenStream, target: Option<&TsType>) -> TokenStream {
+    match target {
+        Some(target) => {
+            let target = map_inner_type(target);
+            quote! { #response.json::<#target>() }
+        }
+        None => quote! { #response.json() },
+    }
+}
+
+/// `const data: T = await res.json()`: the annotation picks the target type
+pub fn typed_json(init: &Expr, target: &TsType) -> Option<TokenStream> {
+    let Expr::Await(await_expr) = init else {
+        return None;
+    };
+    let Expr::Call(call) = &*await_expr.arg else {
+        return None;
+    };
+    let member = call.callee.as_expr()?.as_member()?;
+    if !is_response_expr(&member.obj) || member.prop.as_ident()?.sym != "json" {
+        return None;
+    }
+    let response = convert_expr(&member.obj);
+    let target = map_inner_type(target);
+    Some(quote! { #response.json::<#target>().await? })
+}
+
+/// `res.headers.get(name)`, `null` when the header is absent or not text
+fn handle_headers_method(
+    response: &Expr,
+    method: &str,
+    args: &[ExprOrSpread],
+) -> Option<TokenStream> {
+    let response = convert_expr(response);
+    let name = convert_str_arg(&args.first()?.expr);
+    match method {
+        "get" => Some(quote! {
+            #response
+                .headers()
+                .get(#name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        }),
+        "has" => Some(quote! { #response.headers().contains_key(#name) }),
+        _ => None,
+    }
+}
@@ -0,0 +1,160 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use swc_ecma_ast::*;
+
+use super::super::convert::func::convert_str_arg;
+use super::super::convert::scope;
+use crate::diagnostics;
+
+/// Sync `fs` calls throw in JS: errors are propagated with `?` where the
+/// enclosing function returns `Result`, and unwrapped elsewhere
+fn propagate(op: TokenStream) -> TokenStream {
+    if scope::is_fallible() {
+        quote! { #op? }
+    } else {
+        quote! { #op.unwrap() }
+    }
+}
+
+/// Whether an options argument is `{ recursive: true }`
+fn is_recursive(options: Option<&ExprOrSpread>) -> bool {
+    let Some(Expr::Object(object)) = options.map(|arg| &*arg.expr) else {
+        return false;
+    };
+    object.props.iter().any(|prop| {
+        prop.as_prop()
+            .and_then(|prop| prop.as_key_value())
+            .is_some_and(|kv| {
+                kv.key.as_ident().is_some_and(|key| key.sym == "recursive")
+                    && matches!(&*kv.value, Expr::Lit(Lit::Bool(b)) if b.value)
+            })
+    })
+}
+
+/// Whether a read is decoded to a string (`"utf8"` or `{ encoding }`), rather
+/// than returning the raw bytes of a `Buffer`
+fn has_encoding(options: Option<&ExprOrSpread>) -> bool {
+    match options.map(|arg| &*arg.expr) {
+        None => false,
+        Some(Expr::Object(object)) => object.props.iter().any(|prop| {
+            prop.as_prop()
+                .and_then(|prop| prop.as_key_value())
+                .and_then(|kv| kv.key.as_ident())
+                .is_some_and(|key| key.sym == "encoding")
+        }),
+        Some(_) => true,
+    }
+}
+
+fn path_arg(args: &[ExprOrSpread], index: usize) -> Option<TokenStream> {
+    args.get(index).map(|arg| convert_str_arg(&arg.expr))
+}
+
+fn unsupported(method: &str) -> TokenStream {
+    let name = format!("fs.{}", method);
+    diagnostics::warn(format!(
+        "`{}` has no Rust shim; use `std::fs` or `tokio::fs` directly",
+        name
+    ));
+    quote! { todo!(#name) }
+}
+
+/// Handle the synchronous `fs.*Sync` functions with `std::fs`
+pub fn handle_sync(method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    let path = path_arg(args, 0)?;
+    let op = match method {
+        "readFileSync" if has_encoding(args.get(1)) => quote! { std::fs::read_to_string(#path) },
+        "readFileSync" => quote! { std::fs::read(#path) },
+        "writeFileSync" => {
+            let data = path_arg(args, 1)?;
+            quote! { std::fs::write(#path, #data) }
+        }
+        "appendFileSync" => {
+            let data = path_arg(args, 1)?;
+            quote! {
+                std::fs::OpenOptions::new()
+                    .append(true)
+                    .create(true)
+                    .open(#path)
+                    .and_then(|mut file| std::io::Write::write_all(&mut file, (#data).as_bytes()))
+            }
+        }
+        "existsSync" => return Some(quote! { std::path::Path::new(#path).exists() }),
+        "mkdirSync" if is_recursive(args.get(1)) => quote! { std::fs::create_dir_all(#path) },
+        "mkdirSync" => quote! { std::fs::create_dir(#path) },
+        "readdirSync" => quote! {
+            std::fs::read_dir(#path).and_then(|entries| {
+                entries
+                    .map(|entry| entry.map(|entry| entry.file_name().to_string_lossy().into_owned()))
+                    .collect::<std::io::Result<Vec<String>>>()
+            })
+        },
+        "rmSync" if is_recursive(args.get(1)) => quote! { std::fs::remove_dir_all(#path) },
+        "unlinkSync" | "rmSync" => quote! { std::fs::remove_file(#path) },
+        "rmdirSync" => quote! { std::fs::remove_dir(#path) },
+        "renameSync" => {
+            let to = path_arg(args, 1)?;
+            quote! { std::fs::rename(#path, #to) }
+        }
+        "copyFileSync" => {
+            let to = path_arg(args, 1)?;
+            quote! { std::fs::copy(#path, #to).map(|_| ()) }
+        }
+        _ => return Some(unsupported(method)),
+    };
+    Some(propagate(op))
+}
+
+/// Handle `fs.promises.*` and `fs/promises` with `tokio::fs`. Each call is a
+/// future of `std::io::Result`, so `await` propagates the error with `?`.
+pub fn handle_async(method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    let path = path_arg(args, 0)?;
+    Some(match method {
+        "readFile" if has_encoding(args.get(1)) => quote! { tokio::fs::read_to_string(#path) },
+        "readFile" => quote! { tokio::fs::read(#path) },
+        "writeFile" => {
+            let data = path_arg(args, 1)?;
+            quote! { tokio::fs::write(#path, #data) }
+        }
+        "appendFile" => {
+            let data = path_arg(args, 1)?;
+            quote! {
+                async {
+                    use tokio::io::AsyncWriteExt;
+                    tokio::fs::OpenOptions::new()
+                        .append(true)
+                        .create(true)
+                        .open(#path)
+                        .await?
+                        .write_all((#data).as_bytes())
+                        .await
+                }
+            }
+        }
+        "access" => quote! { async { tokio::fs::metadata(#path).await.map(|_| ()) } },
+        "mkdir" if is_recursive(args.get(1)) => quote! { tokio::fs::create_dir_all(#path) },
+        "mkdir" => quote! { tokio::fs::create_dir(#path) },
+        "readdir" => quote! {
+            async {
+                let mut entries = tokio::fs::read_dir(#path).await?;
+                let mut names = Vec::new();
+                while let Some(entry) = entries.next_entry().await? {
+                    names.push(entry.file_name().to_string_lossy().into_owned());
+                }
+                Ok::<_, std::io::Error>(names)
+            }
+        },
+        "rm" if is_recursive(args.get(1)) => quote! { tokio::fs::remove_dir_all(#path) },
+        "unlink" | "rm" => quote! { tokio::fs::remove_file(#path) },
+        "rmdir" => quote! { tokio::fs::remove_dir(#path) },
+        "rename" => {
+            let to = path_arg(args, 1)?;
+            quote! { tokio::fs::rename(#path, #to) }
+        }
+        "copyFile" => {
+            let to = path_arg(args, 1)?;
+            quote! { async { tokio::fs::copy(#path, #to).await.map(|_| ()) } }
+        }
+        _ => unsupported(&format!("promises.{}", method)),
+    })
+}
@@ -2,27 +2,120 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use swc_ecma_ast::*;
 
-use super::super::convert::func::convert_expr_or_spread;
+use super::super::convert::func::{convert_expr_or_spread, convert_str_arg};
+use super::super::convert::scope;
+use super::super::convert::type_mapper::map_inner_type;
+use crate::diagnostics;
 
 /// Handle JSON.* calls
 pub fn handle(method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
     match method {
-        "stringify" => {
-            if args.len() == 1 {
-                let obj = convert_expr_or_spread(&args[0]);
-                Some(quote! { serde_json::to_string(&#obj).unwrap() })
-            } else {
-                None
+        "stringify" => stringify(args),
+        "parse" => parse(args, None),
+        _ => None,
+    }
+}
+
+/// `JSON.parse(s)`, into `target` when the expected type is known and into
+/// whatever the context infers otherwise. Errors propagate with `?` in
+/// functions that return `Result`.
+pub fn parse(args: &[ExprOrSpread], target: Option<&TsType>) -> Option<TokenStream> {
+    let text = convert_str_arg(&args.first()?.expr);
+    if args.len() > 1 {
+        diagnostics::warn("the `JSON.parse` reviver argument is not supported and was ignored");
+    }
+
+    let parse = match target {
+        Some(target) => {
+            let target = map_inner_type(target);
+            quote! { serde_json::from_str::<#target>(#text) }
+        }
+        None => quote! { serde_json::from_str(#text) },
+    };
+    if scope::is_fallible() {
+        Some(quote! { #parse? })
+    } else {
+        Some(quote! { #parse.unwrap() })
+    }
+}
+
+/// `JSON.stringify(value, replacer, indent)`
+fn stringify(args: &[ExprOrSpread]) -> Option<TokenStream> {
+    let value = convert_expr_or_spread(args.first()?);
+
+    // A key whitelist filters object keys at every depth, like JS
+    let value = match args.get(1).map(|arg| &*arg.expr) {
+        None | Some(Expr::Lit(Lit::Null(_))) => quote! { &#value },
+        Some(Expr::Ident(ident)) if ident.sym == "undefined" => quote! { &#value },
+        Some(Expr::Array(keys)) => {
+            let keys: Vec<_> = keys
+                .elems
+                .iter()
+                .flatten()
+                .map(|key| convert_str_arg(&key.expr))
+                .collect();
+            quote! {
+                &{
+                    fn retain_keys(value: &mut serde_json::Value, keys: &[&str]) {
+                        match value {
+                            serde_json::Value::Object(map) => {
+                                map.retain(|key, _| keys.contains(&key.as_str()));
+                                map.values_mut().for_each(|v| retain_keys(v, keys));
+                            }
+                            serde_json::Value::Array(items) => {
+                                items.iter_mut().for_each(|v| retain_keys(v, keys));
+                            }
+                            _ => {}
+                        }
+                    }
+                    let mut value = serde_json::to_value(&#value).unwrap();
+                    retain_keys(&mut value, &[#(#keys),*]);
+                    value
+                }
             }
         }
-        "parse" => {
-            if args.len() == 1 {
-                let json_str = convert_expr_or_spread(&args[0]);
-                Some(quote! { serde_json::from_str(#json_str).unwrap() })
-            } else {
-                None
+        Some(_) => {
+            diagnostics::warn(
+                "`JSON.stringify` replacer functions are not supported; the replacer was ignored",
+            );
+            quote! { &#value }
+        }
+    };
+
+    // Integral numbers are written without a fraction, as JS does
+    let json_value = crate::runtime::path("json_value");
+    let value = quote! { &#json_value(#value) };
+
+    let indent = match args.get(2).map(|arg| &*arg.expr) {
+        None => None,
+        Some(Expr::Lit(Lit::Num(n))) if n.value <= 0.0 => None,
+        Some(Expr::Lit(Lit::Num(n))) => Some(" ".repeat(n.value.min(10.0) as usize)),
+        Some(Expr::Lit(Lit::Str(s))) if s.value.is_empty() => None,
+        Some(Expr::Lit(Lit::Str(s))) => {
+            Some(s.value.as_str().unwrap_or("").chars().take(10).collect())
+        }
+        Some(_) => {
+            diagnostics::warn(
+                "`JSON.stringify` with a computed indent is emitted with two-space indentation",
+            );
+            Some("  ".to_string())
+        }
+    };
+
+    Some(match indent {
+        None => quote! { serde_json::to_string(#value).unwrap() },
+        Some(indent) if indent == "  " => quote! { serde_json::to_string_pretty(#value).unwrap() },
+        Some(indent) => {
+            let indent = proc_macro2::Literal::byte_string(indent.as_bytes());
+            quote! {
+                {
+                    let mut buf = Vec::new();
+                    let formatter = serde_json::ser::PrettyFormatter::with_indent(#indent);
+                    let mut serializer = serde_json::Serializer::with_formatter(&mut buf, formatter);
+                    serde::Serialize::serialize(#value, &mut serializer).unwrap();
+                    String::from_utf8(buf).unwrap()
+                }
             }
         }
-        _ => None,
-    }
+    })
 }
@@ -0,0 +1,384 @@
+use std::collections::{BTreeSet, HashSet};
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+use super::super::convert::func::{convert_expr, convert_str_arg};
+use super::super::convert::module::import_bindings;
+use super::super::convert::scope;
+use super::registry::{operand, MappedCall, StdlibMapping};
+use super::timers;
+use crate::{dependencies, diagnostics, support};
+
+// The common lodash functions, on the typed collections the rest of the
+// translation produces: arrays are `Vec`s, iteratees are closures or property
+// names, and the objects of `pick`, `omit` and `get` with a computed path go
+// through `serde_json::Value`. Records come out as `HashMap<String, _>`.
+
+/// The lodash functions with a translation
+pub const FUNCTIONS: &[&str] = &[
+    "groupBy",
+    "keyBy",
+    "uniq",
+    "uniqBy",
+    "chunk",
+    "sortBy",
+    "pick",
+    "omit",
+    "debounce",
+    "cloneDeep",
+    "isEqual",
+    "get",
+];
+
+/// `lodash`, `lodash-es` and the per-function `lodash/groupBy` and
+/// `lodash.groupby` modules
+pub struct Lodash;
+
+/// The function a per-function module such as `lodash/groupBy` exports, or
+/// `None` for the whole library
+fn function_module(module: &str) -> Option<&str> {
+    module
+        .strip_prefix("lodash/")
+        .or_else(|| module.strip_prefix("lodash."))
+        .or_else(|| module.strip_prefix("lodash-es/"))
+}
+
+fn is_lodash(module: &str) -> bool {
+    matches!(module, "lodash" | "lodash-es") || function_module(module).is_some()
+}
+
+impl StdlibMapping for Lodash {
+    fn handles_module(&self, module: &str) -> bool {
+        is_lodash(module)
+    }
+
+    fn map_call(&self, call: &MappedCall<'_>) -> Option<TokenStream> {
+        let module = call.module.filter(|module| is_lodash(module))?;
+        let function = match function_module(module) {
+            // `lodash.groupby` is all lower case
+            Some(name) if call.function == "default" => *FUNCTIONS
+                .iter()
+                .find(|function| function.eq_ignore_ascii_case(name))?,
+            Some(_) => return None,
+            None => call.function,
+        };
+        translate(function, call.args)
+    }
+}
+
+fn translate(function: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    let arg = |index: usize| args.get(index).map(|arg| operand(convert_expr(&arg.expr)));
+    match function {
+        "chunk" => {
+            let items = arg(0)?;
+            let size = arg(1).unwrap_or_else(|| quote! { 1.0 });
+            Some(quote! {
+                #items.chunks((#size as usize).max(1)).map(|chunk| chunk.to_vec()).collect::<Vec<_>>()
+            })
+        }
+        "groupBy" => {
+            let items = arg(0)?;
+            let key = key_of(args.get(1)?, &quote! { item });
+            dependencies::require("itertools", "\"0.13\"");
+            Some(quote! {
+                itertools::Itertools::into_group_map_by(#items.iter().cloned(), |item| #key.to_string())
+            })
+        }
+        "keyBy" => {
+            let items = arg(0)?;
+            let key = key_of(args.get(1)?, &quote! { item });
+            Some(quote! {
+                #items.iter().map(|item| (#key.to_string(), item.clone())).collect::<std::collections::HashMap<_, _>>()
+            })
+        }
+        // Elements need only `PartialEq`, so numbers can be deduplicated too
+        "uniq" => {
+            let items = arg(0)?;
+            Some(quote! {
+                {
+                    let mut unique = Vec::new();
+                    for item in #items.iter() {
+                        if !unique.contains(item) {
+                            unique.push(item.clone());
+                        }
+                    }
+                    unique
+                }
+            })
+        }
+        "uniqBy" => {
+            let items = arg(0)?;
+            let key = key_of(args.get(1)?, &quote! { item });
+            Some(quote! {
+                {
+                    let mut keys = Vec::new();
+                    #items
+                        .iter()
+                        .filter(|&item| {
+                            let key = #key;
+                            if keys.contains(&key) {
+                                false
+                            } else {
+                                keys.push(key);
+                                true
+                            }
+                        })
+                        .cloned()
+                        .collect::<Vec<_>>()
+                }
+            })
+        }
+        // `sorted_by` is stable, like `_.sortBy`
+        "sortBy" => {
+            let items = arg(0)?;
+            let iteratees: Vec<&ExprOrSpread> = match args.get(1).map(|arg| &*arg.expr) {
+                Some(Expr::Array(array)) => array.elems.iter().flatten().collect(),
+                Some(_) => vec![&args[1]],
+                None => Vec::new(),
+            };
+            let ordering = iteratees
+                .iter()
+                .map(|iteratee| {
+                    let left = key_of(iteratee, &quote! { left });
+                    let right = key_of(iteratee, &quote! { right });
+                    quote! {
+                        #left.partial_cmp(&#right).unwrap_or(std::cmp::Ordering::Equal)
+                    }
+                })
+                .reduce(|ordering, next| quote! { #ordering.then_with(|| #next) })
+                .unwrap_or_else(|| {
+                    quote! { left.partial_cmp(right).unwrap_or(std::cmp::Ordering::Equal) }
+                });
+            dependencies::require("itertools", "\"0.13\"");
+            Some(quote! {
+                itertools::Itertools::sorted_by(#items.iter().cloned(), |left, right| #ordering).collect::<Vec<_>>()
+            })
+        }
+        "pick" | "omit" => {
+            let object = arg(0)?;
+            let keys = property_names(&args[1..])?;
+            let keep = if function == "pick" {
+                quote! { keys.contains(&key.as_str()) }
+            } else {
+                quote! { !keys.contains(&key.as_str()) }
+            };
+            Some(quote! {
+                {
+                    let keys = [#(#keys),*];
+                    let mut value = serde_json::to_value(&#object).unwrap_or_default();
+                    if let Some(fields) = value.as_object_mut() {
+                        fields.retain(|key, _| #keep);
+                    }
+                    value
+                }
+            })
+        }
+        "debounce" => debounce(args),
+        // Rust clones own their data; only `Arc` fields stay shared
+        "cloneDeep" => {
+            let value = arg(0)?;
+            Some(quote! { #value.clone() })
+        }
+        "isEqual" => {
+            let (left, right) = (arg(0)?, arg(1)?);
+            Some(quote! { #left == #right })
+        }
+        "get" => get(args),
+        _ => None,
+    }
+}
+
+/// The key an iteratee gives `item` (a `&T`): a property path such as
+/// `"address.city"`, or a function of the element
+fn key_of(iteratee: &ExprOrSpread, item: &TokenStream) -> TokenStream {
+    match &*iteratee.expr {
+        Expr::Lit(Lit::Str(path)) => {
+            let path = path.value.to_string_lossy();
+            let fields = path.split('.').map(|field| format_ident!("{}", field));
+            quote! { #item #(.#fields)*.clone() }
+        }
+        // `u => u.dept` binds the element directly, so the field access
+        // needs no closure type annotation
+        Expr::Arrow(arrow) if arrow.params.len() == 1 && arrow.body.is_expr() => {
+            let Pat::Ident(param) = &arrow.params[0] else {
+                return call_iteratee(iteratee, item);
+            };
+            let name = format_ident!("{}", param.id.sym.as_ref());
+            let body = convert_expr(arrow.body.as_expr().unwrap());
+            quote! { { let #name = #item.clone(); #body } }
+        }
+        _ => call_iteratee(iteratee, item),
+    }
+}
+
+fn call_iteratee(iteratee: &ExprOrSpread, item: &TokenStream) -> TokenStream {
+    let callback = convert_expr(&iteratee.expr);
+    quote! { (#callback)(#item.clone()) }
+}
+
+/// The `&str` property names of `pick(object, "a", ["b", "c"])`
+fn property_names(args: &[ExprOrSpread]) -> Option<Vec<TokenStream>> {
+    let mut names = Vec::new();
+    for arg in args {
+        match &*arg.expr {
+            Expr::Array(array) => names.extend(
+                array
+                    .elems
+                    .iter()
+                    .flatten()
+                    .map(|e| convert_str_arg(&e.expr)),
+            ),
+            Expr::Lit(Lit::Str(_)) | Expr::Tpl(_) => names.push(convert_str_arg(&arg.expr)),
+            _ => {
+                diagnostics::warn(
+                    "lodash `pick`/`omit` need the property names as literals or an array literal",
+                );
+                return None;
+            }
+        }
+    }
+    Some(names)
+}
+
+/// `_.get(object, "a.b")`: a field access for a literal path; through
+/// `serde_json::Value` for a computed path, indices or a default
+fn get(args: &[ExprOrSpread]) -> Option<TokenStream> {
+    let object = operand(convert_expr(&args.first()?.expr));
+    let path = args.get(1)?;
+    let default = args.get(2).map(|arg| convert_expr(&arg.expr));
+
+    if let (Expr::Lit(Lit::Str(literal)), None) = (&*path.expr, &default) {
+        let literal = literal.value.to_string_lossy();
+        if literal.split('.').all(|field| {
+            !field.is_empty() && field.chars().all(|c| c.is_alphanumeric() || c == '_')
+        }) {
+            let fields = literal.split('.').map(|field| format_ident!("{}", field));
+            return Some(quote! { #object #(.#fields)*.clone() });
+        }
+    }
+
+    let pointer = match &*path.expr {
+        Expr::Lit(Lit::Str(literal)) => {
+            let pointer = json_pointer(&literal.value.to_string_lossy());
+            quote! { #pointer }
+        }
+        _ => {
+            let path = convert_str_arg(&path.expr);
+            quote! {
+                &format!("/{}", #path.replace('[', ".").replace(']', "").replace('.', "/"))
+            }
+        }
+    };
+    let value = quote! { serde_json::to_value(&#object).ok().and_then(|value| value.pointer(#pointer).cloned()) };
+    Some(match default {
+        Some(default) => quote! { #value.unwrap_or_else(|| serde_json::json!(#default)) },
+        None => value,
+    })
+}
+
+/// `a.b[0].c` -> `/a/b/0/c`
+fn json_pointer(path: &str) -> String {
+    path.replace('[', ".")
+        .replace(']', "")
+        .split('.')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| format!("/{}", segment))
+        .collect()
+}
+
+/// `_.debounce(f, wait)` as a closure of the same arity, backed by
+/// `crate::lodash::debounce`
+fn debounce(args: &[ExprOrSpread]) -> Option<TokenStream> {
+    let function = args.first()?;
+    let arity = match &*function.expr {
+        Expr::Arrow(arrow) => arrow.params.len(),
+        Expr::Fn(f) => f.function.params.len(),
+        Expr::Ident(ident) => scope::lookup_function(&ident.sym).map_or(0, |params| params.len()),
+        _ => 0,
+    };
+    let callback = convert_expr(&function.expr);
+    let wait = timers::duration(args.get(1));
+    if args.len() > 2 {
+        diagnostics::warn("lodash `debounce` options are not supported; it is trailing-edge only");
+    }
+    support::require("lodash");
+    let params: Vec<_> = (0..arity).map(|i| format_ident!("arg{}", i)).collect();
+    Some(quote! {
+        {
+            let debounced = crate::lodash::debounce(move |(#(#params,)*)| (#callback)(#(#params),*), #wait);
+            move |#(#params),*| debounced((#(#params,)*))
+        }
+    })
+}
+
+/// Reports the lodash functions a file uses that have no translation, in one
+/// diagnostic, since their calls are emitted unchanged
+pub fn register(program: &Program) {
+    let Program::Module(module) = program else {
+        return;
+    };
+    let mut used = BTreeSet::new();
+    let mut namespaces = HashSet::new();
+    for item in &module.body {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+            continue;
+        };
+        let src = import.src.value.to_string_lossy();
+        if !is_lodash(&src) || crate::options().mappings.handles_module(&src) {
+            continue;
+        }
+        for (local, member) in import_bindings(import) {
+            match (function_module(&src), member) {
+                (Some(name), None) => {
+                    used.insert(name.to_string());
+                }
+                (Some(_), Some(_)) => {}
+                (None, Some(member)) => {
+                    used.insert(member);
+                }
+                (None, None) => {
+                    namespaces.insert(local.sym.to_string());
+                }
+            }
+        }
+    }
+    if !namespaces.is_empty() {
+        let mut collector = NamespaceCalls {
+            namespaces: &namespaces,
+            used: &mut used,
+        };
+        program.visit_with(&mut collector);
+    }
+
+    let unmapped: Vec<String> = used
+        .into_iter()
+        .filter(|name| !FUNCTIONS.iter().any(|f| f.eq_ignore_ascii_case(name)))
+        .map(|name| format!("`{}`", name))
+        .collect();
+    if !unmapped.is_empty() {
+        diagnostics::warn(format!(
+            "lodash functions without a Rust translation: {}; their calls are emitted unchanged",
+            unmapped.join(", ")
+        ));
+    }
+}
+
+/// Finds `_.name` on the lodash module object
+struct NamespaceCalls<'a> {
+    namespaces: &'a HashSet<String>,
+    used: &'a mut BTreeSet<String>,
+}
+
+impl Visit for NamespaceCalls<'_> {
+    fn visit_member_expr(&mut self, member: &MemberExpr) {
+        if let (Some(obj), Some(prop)) = (member.obj.as_ident(), member.prop.as_ident()) {
+            if self.namespaces.contains(obj.sym.as_ref()) {
+                self.used.insert(prop.sym.to_string());
+            }
+        }
+        member.visit_children_with(self);
+    }
+}
@@ -1,14 +1,153 @@
 use proc_macro2::TokenStream;
 use swc_ecma_ast::*;
 
+use super::convert::scope::{self, NodeModule};
+
 pub mod array;
+pub mod axios;
+pub mod buffer;
 pub mod console;
+pub mod crypto;
+pub mod date;
+pub mod fetch;
+pub mod fs;
 pub mod json;
+pub mod lodash;
 pub mod math;
+pub mod number;
+pub mod object;
+pub mod path;
+pub mod process;
+pub mod promise;
+pub mod receiver;
+pub mod regexp;
+pub mod registry;
 pub mod string;
+pub mod timers;
+pub mod url;
+
+/// The Node built-in module `expr` refers to: an imported `fs`, `path` or
+/// `process` (or `fs.promises`), or the global `process`
+fn node_module(expr: &Expr) -> Option<NodeModule> {
+    match expr {
+        Expr::Ident(ident) => match scope::lookup_node_import(&ident.sym) {
+            Some(import) => match (import.module, import.member.as_deref()) {
+                (module, None) => Some(module),
+                (NodeModule::Fs, Some("promises")) => Some(NodeModule::FsPromises),
+                _ => None,
+            },
+            None if ident.sym == "process" && scope::lookup("process").is_none() => {
+                Some(NodeModule::Process)
+            }
+            None if ident.sym == "crypto" && scope::lookup("crypto").is_none() => {
+                Some(NodeModule::Crypto)
+            }
+            None => None,
+        },
+        Expr::Member(member) => {
+            let prop = member.prop.as_ident()?;
+            (node_module(&member.obj)? == NodeModule::Fs && prop.sym == "promises")
+                .then_some(NodeModule::FsPromises)
+        }
+        _ => None,
+    }
+}
+
+fn handle_node_call(
+    module: NodeModule,
+    method: &str,
+    args: &[ExprOrSpread],
+) -> Option<TokenStream> {
+    match module {
+        NodeModule::Fs => fs::handle_sync(method, args),
+        NodeModule::FsPromises => fs::handle_async(method, args),
+        NodeModule::Path => path::handle(method, args),
+        NodeModule::Process => process::handle(method, args),
+        NodeModule::Crypto => crypto::handle(method, args),
+    }
+}
+
+/// Whether `expr` is `process.env`, or `env` imported from `process`
+pub fn is_process_env(expr: &Expr) -> bool {
+    match expr {
+        Expr::Ident(ident) => scope::lookup_node_import(&ident.sym).is_some_and(|import| {
+            import.module == NodeModule::Process && import.member.as_deref() == Some("env")
+        }),
+        Expr::Member(member) => {
+            member.prop.as_ident().is_some_and(|prop| prop.sym == "env")
+                && node_module(&member.obj) == Some(NodeModule::Process)
+        }
+        _ => false,
+    }
+}
+
+/// Property reads on Node built-ins (`process.env.HOME`, `process.argv`,
+/// `path.sep`), fetch responses (`res.ok`, `res.status`) and URLs (`url.pathname`)
+pub fn try_handle_member(member: &MemberExpr) -> Option<TokenStream> {
+    if fetch::is_response_expr(&member.obj) {
+        return fetch::property(&member.obj, &member.prop.as_ident()?.sym);
+    }
+    if is_process_env(&member.obj) {
+        return process::env_var(&member.prop);
+    }
+    if let Some(value) = url::property(member) {
+        return Some(value);
+    }
+    if let Some(value) = axios::try_handle_member(member) {
+        return Some(value);
+    }
+    let prop = member.prop.as_ident()?;
+    match node_module(&member.obj)? {
+        NodeModule::Process => process::property(&prop.sym),
+        NodeModule::Path => path::constant(&prop.sym),
+        NodeModule::Fs | NodeModule::FsPromises | NodeModule::Crypto => None,
+    }
+}
 
 /// Main dispatcher for stdlib method calls
 pub fn try_handle_stdlib_call(callee: &Callee, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    // Mappings loaded from the project come first, so they can override ours
+    if let Some(mapped) = registry::try_handle_call(callee, args) {
+        return Some(mapped);
+    }
+
+    if let Some(expr) = callee.as_expr() {
+        // `import { readFileSync } from "fs"`
+        if let Some(import) = expr
+            .as_ident()
+            .and_then(|ident| scope::lookup_node_import(&ident.sym))
+        {
+            if let Some(member) = &import.member {
+                return handle_node_call(import.module, member, args);
+            }
+        }
+        // `fs.readFileSync(...)`, `fs.promises.readFile(...)`, `process.exit(...)`
+        if let Some(member) = expr.as_member() {
+            if let (Some(module), Some(method)) = (node_module(&member.obj), member.prop.as_ident())
+            {
+                return handle_node_call(module, &method.sym, args);
+            }
+        }
+    }
+
+    // Global functions
+    if let Callee::Expr(expr) = callee {
+        if let Expr::Ident(ident) = &**expr {
+            match ident.sym.as_ref() {
+                "structuredClone" => return object::structured_clone(args),
+                "Number" | "parseInt" | "parseFloat" | "isNaN" | "isFinite" => {
+                    return number::handle_global(&ident.sym, args)
+                }
+                "setTimeout" | "setInterval" | "clearTimeout" | "clearInterval" => {
+                    return timers::handle_global(&ident.sym, args)
+                }
+                "btoa" | "atob" => return buffer::handle_global(&ident.sym, args),
+                "String" => return string::handle_global(args),
+                _ => {}
+            }
+        }
+    }
+
     // Try to handle as stdlib call
     if let Callee::Expr(expr) = callee {
         if let Expr::Member(member) = &**expr {
@@ -23,10 +162,34 @@ pub fn try_handle_stdlib_call(callee: &Callee, args: &[ExprOrSpread]) -> Option<
                     if let Some(method_ident) = member.prop.as_ident() {
                         return json::handle(&method_ident.sym, args);
                     }
+                } else if obj_name == "Date" {
+                    if let Some(method_ident) = member.prop.as_ident() {
+                        return date::handle_static(&method_ident.sym, args);
+                    }
+                } else if obj_name == "Number" {
+                    if let Some(method_ident) = member.prop.as_ident() {
+                        return number::handle(&method_ident.sym, args);
+                    }
+                } else if obj_name == "Object" {
+                    if let Some(method_ident) = member.prop.as_ident() {
+                        return object::handle(&method_ident.sym, args);
+                    }
+                } else if obj_name == "Array" {
+                    if let Some(method_ident) = member.prop.as_ident() {
+                        return array::handle_static(&method_ident.sym, args);
+                    }
+                } else if obj_name == "Promise" {
+                    if let Some(method_ident) = member.prop.as_ident() {
+                        return promise::handle(&method_ident.sym, args);
+                    }
                 } else if obj_name == "console" {
                     if let Some(method_ident) = member.prop.as_ident() {
                         return console::handle(&method_ident.sym, args);
                     }
+                } else if obj_name == "Buffer" && scope::lookup("Buffer").is_none() {
+                    if let Some(method_ident) = member.prop.as_ident() {
+                        return buffer::handle_static(&method_ident.sym, args);
+                    }
                 }
             }
         }
@@ -41,16 +204,66 @@ pub fn try_handle_method_call(
     method: &str,
     args: &[ExprOrSpread],
 ) -> Option<TokenStream> {
-    // For now, we'll handle this based on method name
-    // In the future, we could use type inference
+    if date::is_date_expr(obj) {
+        if let Some(code) = date::handle_method(obj, method, args) {
+            return Some(code);
+        }
+    }
+
+    if regexp::is_regex_expr(obj) {
+        return regexp::handle_method(obj, method, args);
+    }
+
+    // `createHash(...).update(...).digest(...)`
+    if let Some(code) = crypto::handle_method(obj, method, args) {
+        return Some(code);
+    }
+    if let Some(code) = buffer::handle_method(obj, method, args) {
+        return Some(code);
+    }
+    if let Some(code) = url::handle_method(obj, method, args) {
+        return Some(code);
+    }
+    if matches!(
+        method,
+        "match" | "matchAll" | "replace" | "replaceAll" | "split" | "search"
+    ) && args
+        .first()
+        .is_some_and(|arg| regexp::is_regex_expr(&arg.expr))
+    {
+        return regexp::handle_string_method(obj, method, args);
+    }
+
+    match receiver::classify(obj) {
+        Some(receiver::Receiver::String) => string::handle_method(obj, method, args),
+        Some(receiver::Receiver::Array) => array::handle_method(obj, method, args),
+        Some(receiver::Receiver::Number) => number::handle_method(obj, method, args),
+        Some(receiver::Receiver::Declared) => receiver::handle_declared_method(obj, method, args),
+        None => guess_method_call(obj, method, args),
+    }
+}
 
-    match method {
-        // String methods
-        "includes" | "replace" | "split" | "toUpperCase" | "toLowerCase" | "trim" | "toString" => {
-            string::handle_method(obj, method, args)
+/// Routes a call on a receiver of unknown type by the method name alone
+fn guess_method_call(obj: &Expr, method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    let (kind, code) = match method {
+        "toFixed" => ("number", number::handle_method(obj, method, args)),
+        "toString" if args.len() == 1 => ("number", number::handle_method(obj, method, args)),
+        "includes" | "replace" | "split" | "toUpperCase" | "toLowerCase" | "trim" | "toString"
+        | "startsWith" | "endsWith" | "indexOf" | "lastIndexOf" | "slice" | "substring"
+        | "charAt" | "charCodeAt" | "at" | "padStart" | "padEnd" | "repeat" | "replaceAll"
+        | "trimStart" | "trimEnd" | "localeCompare" | "concat" => {
+            ("string", string::handle_method(obj, method, args))
         }
-        // Array methods
-        "push" | "map" | "filter" | "join" => array::handle_method(obj, method, args),
-        _ => None,
+        _ if array::ARRAY_METHODS.contains(&method) => {
+            ("array", array::handle_method(obj, method, args))
+        }
+        _ => return None,
+    };
+    if code.is_some() {
+        crate::diagnostics::warn(format!(
+            "the receiver of `.{}()` has no known type; translated it as a {} method",
+            method, kind
+        ));
     }
+    code
 }
@@ -0,0 +1,269 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use swc_ecma_ast::*;
+
+use super::super::convert::func::{convert_expr, convert_expr_or_spread};
+
+/// Handle the global `Number`, `parseInt`, `parseFloat`, `isNaN` and `isFinite` functions
+pub fn handle_global(name: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    match name {
+        "Number" => Some(match args.first() {
+            Some(arg) => to_number(&arg.expr),
+            None => quote! { 0.0 },
+        }),
+        "parseInt" => Some(parse_int(args.first()?, args.get(1))),
+        "parseFloat" => Some(parse_float(args.first()?)),
+        // The globals coerce their argument with `Number(x)` first
+        "isNaN" => {
+            let value = to_number(&args.first()?.expr);
+            Some(quote! { (#value).is_nan() })
+        }
+        "isFinite" => {
+            let value = to_number(&args.first()?.expr);
+            Some(quote! { (#value).is_finite() })
+        }
+        _ => None,
+    }
+}
+
+/// Handle Number.* calls
+pub fn handle(method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    match method {
+        "parseInt" => Some(parse_int(args.first()?, args.get(1))),
+        "parseFloat" => Some(parse_float(args.first()?)),
+        // Unlike the globals, `Number.isNaN` and friends do not coerce
+        "isNaN" => {
+            let value = convert_expr_or_spread(args.first()?);
+            Some(quote! { { let x: f64 = #value; x.is_nan() } })
+        }
+        "isFinite" => {
+            let value = convert_expr_or_spread(args.first()?);
+            Some(quote! { { let x: f64 = #value; x.is_finite() } })
+        }
+        "isInteger" | "isSafeInteger" => {
+            let value = convert_expr_or_spread(args.first()?);
+            let limit = if method == "isSafeInteger" {
+                quote! { && x.abs() <= 9007199254740991.0 }
+            } else {
+                quote! {}
+            };
+            Some(quote! {
+                {
+                    let x: f64 = #value;
+                    x.is_finite() && x.trunc() == x #limit
+                }
+            })
+        }
+        _ => None,
+    }
+}
+
+/// `Number.MAX_SAFE_INTEGER` and the other constants
+pub fn constant(name: &str) -> Option<TokenStream> {
+    Some(match name {
+        "MAX_SAFE_INTEGER" => quote! { 9007199254740991f64 },
+        "MIN_SAFE_INTEGER" => quote! { -9007199254740991f64 },
+        "EPSILON" => quote! { f64::EPSILON },
+        "MAX_VALUE" => quote! { f64::MAX },
+        "MIN_VALUE" => quote! { f64::from_bits(1) },
+        "POSITIVE_INFINITY" => quote! { f64::INFINITY },
+        "NEGATIVE_INFINITY" => quote! { f64::NEG_INFINITY },
+        "NaN" => quote! { f64::NAN },
+        _ => return None,
+    })
+}
+
+/// Handle number method calls (`x.toFixed(2)`, `x.toString(16)`)
+pub fn handle_method(obj: &Expr, method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    let value = convert_expr(obj);
+    match method {
+        "toFixed" => {
+            let digits = args
+                .first()
+                .map(convert_expr_or_spread)
+                .unwrap_or_else(|| quote! { 0.0 });
+            Some(to_fixed(&value, &digits))
+        }
+        "toString" => match args.first() {
+            Some(radix) => Some(to_string_radix(&value, &convert_expr_or_spread(radix))),
+            None => {
+                let js_to_string = crate::runtime::path("js_to_string");
+                Some(quote! { #js_to_string(#value) })
+            }
+        },
+        _ => None,
+    }
+}
+
+/// `expr` as a JS string when it is a computed number, whose Rust formatting
+/// differs from JS for `-0`, `Infinity` and large or small magnitudes
+pub fn js_string_of(expr: &Expr) -> Option<TokenStream> {
+    if matches!(expr, Expr::Lit(Lit::Num(_)))
+        || super::receiver::classify(expr) != Some(super::receiver::Receiver::Number)
+    {
+        return None;
+    }
+    let value = convert_expr(expr);
+    let js_to_string = crate::runtime::path("js_to_string");
+    Some(quote! { #js_to_string(#value) })
+}
+
+/// `Number(s)`: the whole trimmed string must be a numeric literal, `""` is 0
+pub fn to_number(arg: &Expr) -> TokenStream {
+    if let Expr::Lit(Lit::Num(_)) = arg {
+        return convert_expr(arg);
+    }
+    if let Expr::Lit(Lit::Bool(b)) = arg {
+        return if b.value {
+            quote! { 1.0 }
+        } else {
+            quote! { 0.0 }
+        };
+    }
+    // `true` is 1 and `false` 0; their text would parse as NaN
+    if super::receiver::is_boolean(arg) {
+        let value = convert_expr(arg);
+        return quote! { ((#value) as u8 as f64) };
+    }
+    let value = convert_expr(arg);
+    let to_number = crate::runtime::path("to_number");
+    match super::receiver::classify(arg) {
+        Some(super::receiver::Receiver::Number) => quote! { ((#value) as f64) },
+        Some(super::receiver::Receiver::String) => quote! { #to_number(&#value) },
+        // `Number(value)` reads the value's string form, e.g. of a JSON string
+        _ => {
+            let text = super::string::js_string(arg);
+            quote! { #to_number(&#text) }
+        }
+    }
+}
+
+/// `parseInt(s, radix)`: leading whitespace and sign, then as many digits as
+/// are valid in the radix; `NaN` if there are none
+fn parse_int(arg: &ExprOrSpread, radix: Option<&ExprOrSpread>) -> TokenStream {
+    let value = convert_expr_or_spread(arg);
+    let radix = radix
+        .map(convert_expr_or_spread)
+        .unwrap_or_else(|| quote! { 0.0 });
+    quote! {
+        {
+            let s = #value.to_string();
+            let s = s.trim_start();
+            let (negative, s) = match s.strip_prefix('-') {
+                Some(rest) => (true, rest),
+                None => (false, s.strip_prefix('+').unwrap_or(s)),
+            };
+            let mut radix = (#radix) as u32;
+            let mut s = s;
+            if (radix == 0 || radix == 16) && (s.starts_with("0x") || s.starts_with("0X")) {
+                radix = 16;
+                s = &s[2..];
+            }
+            if radix == 0 {
+                radix = 10;
+            }
+            let digits: Vec<u32> = if (2..=36).contains(&radix) {
+                s.chars().map_while(|c| c.to_digit(radix)).collect()
+            } else {
+                Vec::new()
+            };
+            if digits.is_empty() {
+                f64::NAN
+            } else {
+                let value = digits.iter().fold(0.0, |acc, d| acc * radix as f64 + *d as f64);
+                if negative { -value } else { value }
+            }
+        }
+    }
+}
+
+/// `parseFloat(s)`: the longest numeric prefix after leading whitespace
+fn parse_float(arg: &ExprOrSpread) -> TokenStream {
+    let value = convert_expr_or_spread(arg);
+    quote! {
+        {
+            let s = #value.to_string();
+            let s = s.trim_start();
+            let unsigned = s.strip_prefix(['+', '-']).unwrap_or(s);
+            if unsigned.starts_with("Infinity") {
+                if s.starts_with('-') { f64::NEG_INFINITY } else { f64::INFINITY }
+            } else {
+                (1..=s.len())
+                    .rev()
+                    .filter(|&end| s.is_char_boundary(end))
+                    .map(|end| &s[..end])
+                    .filter(|prefix| !prefix.contains(|c: char| c.is_alphabetic() && c != 'e' && c != 'E'))
+                    .find_map(|prefix| prefix.parse::<f64>().ok())
+                    .unwrap_or(f64::NAN)
+            }
+        }
+    }
+}
+
+/// `x.toFixed(digits)`. Rust rounds exact ties to even, JS away from zero, so
+/// exact ties are nudged to the next representable value first.
+fn to_fixed(value: &TokenStream, digits: &TokenStream) -> TokenStream {
+    quote! {
+        {
+            let x: f64 = #value;
+            let digits = (#digits) as usize;
+            if x.is_nan() {
+                "NaN".to_string()
+            } else if x.is_infinite() {
+                (if x > 0.0 { "Infinity" } else { "-Infinity" }).to_string()
+            } else if x.abs() >= 1e21 {
+                format!("{:e}", x).replace('e', "e+")
+            } else {
+                let exact = format!("{:.1100}", x.abs());
+                let cut = exact.find('.').map_or(exact.len(), |dot| dot + 1 + digits);
+                let is_tie = exact.get(cut..).is_some_and(|rest| rest.trim_end_matches('0') == "5");
+                let magnitude = if is_tie { f64::from_bits(x.abs().to_bits() + 1) } else { x.abs() };
+                let sign = if x < 0.0 { "-" } else { "" };
+                format!("{}{:.*}", sign, digits, magnitude)
+            }
+        }
+    }
+}
+
+/// `x.toString(radix)`; fractions are expanded digit by digit, which is exact
+/// for power-of-two radixes
+fn to_string_radix(value: &TokenStream, radix: &TokenStream) -> TokenStream {
+    quote! {
+        {
+            let x: f64 = #value;
+            let radix = (#radix) as u32;
+            if radix == 10 || !x.is_finite() || !(2..=36).contains(&radix) {
+                x.to_string()
+            } else {
+                let base = radix as f64;
+                let mut int = x.abs().trunc();
+                let mut frac = x.abs().fract();
+                let mut digits = Vec::new();
+                loop {
+                    digits.push(std::char::from_digit((int % base) as u32, radix).unwrap());
+                    int = (int / base).trunc();
+                    if int == 0.0 {
+                        break;
+                    }
+                }
+                let mut s: String = digits.iter().rev().collect();
+                if frac > 0.0 {
+                    s.push('.');
+                    for _ in 0..52 {
+                        if frac == 0.0 {
+                            break;
+                        }
+                        frac *= base;
+                        let digit = frac.trunc();
+                        s.push(std::char::from_digit(digit as u32, radix).unwrap());
+                        frac -= digit;
+                    }
+                }
+                if x < 0.0 {
+                    s.insert(0, '-');
+                }
+                s
+            }
+        }
+    }
+}
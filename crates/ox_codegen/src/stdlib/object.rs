@@ -0,0 +1,293 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use swc_ecma_ast::*;
+
+use super::super::convert::func::{convert_expr, convert_expr_or_spread, convert_place_expr};
+use super::super::convert::scope::{self, StructField};
+use super::super::convert::type_mapper::map_inner_type;
+use crate::diagnostics;
+
+/// What the generated code knows about an object-like value
+enum Shape {
+    /// `Record<K, V>` / `Map<K, V>`, emitted as a `HashMap`
+    Map,
+    /// An interface declared in this file
+    Struct(String, Vec<StructField>),
+    /// Anything else; handled through its `serde_json` representation
+    Unknown,
+}
+
+fn shape_of(expr: &Expr) -> Shape {
+    let ts_type = match expr {
+        Expr::Paren(paren) => return shape_of(&paren.expr),
+        Expr::Ident(ident) => scope::lookup(&ident.sym),
+        _ => None,
+    };
+    let Some(TsType::TsTypeRef(type_ref)) = ts_type else {
+        return Shape::Unknown;
+    };
+    let Some(name) = type_ref.type_name.as_ident() else {
+        return Shape::Unknown;
+    };
+    match name.sym.as_ref() {
+        "Record" | "Map" => Shape::Map,
+        name => match scope::lookup_struct(name) {
+            Some(fields) => Shape::Struct(name.to_string(), fields),
+            None => Shape::Unknown,
+        },
+    }
+}
+
+/// `Object.freeze(x)` only needs an immutable binding in Rust
+pub fn unwrap_freeze(expr: &Expr) -> Option<&Expr> {
+    let Expr::Call(call) = expr else {
+        return None;
+    };
+    let Callee::Expr(callee) = &call.callee else {
+        return None;
+    };
+    let Expr::Member(member) = &**callee else {
+        return None;
+    };
+    let is_freeze = member.obj.as_ident().is_some_and(|obj| obj.sym == "Object")
+        && member
+            .prop
+            .as_ident()
+            .is_some_and(|prop| prop.sym == "freeze");
+    if is_freeze {
+        call.args.first().map(|arg| &*arg.expr)
+    } else {
+        None
+    }
+}
+
+fn warn_map_order() {
+    diagnostics::warn(
+        "`Object.keys/values/entries` over a `HashMap` iterate in unspecified order, \
+         while JS preserves insertion order",
+    );
+}
+
+/// Struct field values, typed when every field has the same Rust type
+fn struct_values(obj: &TokenStream, fields: &[StructField]) -> Vec<TokenStream> {
+    let field_types: Vec<String> = fields
+        .iter()
+        .map(|f| {
+            let ty = map_inner_type(&f.ts_type);
+            if f.optional {
+                quote! { Option<#ty> }.to_string()
+            } else {
+                ty.to_string()
+            }
+        })
+        .collect();
+    let uniform = field_types.windows(2).all(|w| w[0] == w[1]);
+
+    fields
+        .iter()
+        .map(|f| {
+            let field = format_ident!("{}", f.name);
+            if uniform {
+                quote! { #obj.#field.clone() }
+            } else {
+                quote! { serde_json::to_value(&#obj.#field).unwrap() }
+            }
+        })
+        .collect()
+}
+
+/// Handle Object.* calls
+pub fn handle(method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    match method {
+        "keys" | "values" | "entries" => {
+            let target = &*args.first()?.expr;
+            let obj = convert_expr(target);
+            Some(match (shape_of(target), method) {
+                (Shape::Map, "keys") => {
+                    warn_map_order();
+                    quote! { #obj.keys().cloned().collect::<Vec<_>>() }
+                }
+                (Shape::Map, "values") => {
+                    warn_map_order();
+                    quote! { #obj.values().cloned().collect::<Vec<_>>() }
+                }
+                (Shape::Map, _) => {
+                    warn_map_order();
+                    quote! { #obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect::<Vec<_>>() }
+                }
+                (Shape::Struct(_, fields), "keys") => {
+                    let names = fields.iter().map(|f| &f.name);
+                    quote! { vec![#(String::from(#names)),*] }
+                }
+                (Shape::Struct(_, fields), "values") => {
+                    let values = struct_values(&obj, &fields);
+                    quote! { vec![#(#values),*] }
+                }
+                (Shape::Struct(_, fields), _) => {
+                    let values = struct_values(&obj, &fields);
+                    let names = fields.iter().map(|f| &f.name);
+                    quote! { vec![#((String::from(#names), #values)),*] }
+                }
+                (Shape::Unknown, _) => {
+                    let collect = match method {
+                        "keys" => quote! { o.keys().cloned().collect::<Vec<_>>() },
+                        "values" => quote! { o.values().cloned().collect::<Vec<_>>() },
+                        _ => {
+                            quote! { o.iter().map(|(k, v)| (k.clone(), v.clone())).collect::<Vec<_>>() }
+                        }
+                    };
+                    quote! {
+                        serde_json::to_value(&#obj)
+                            .ok()
+                            .and_then(|v| v.as_object().map(|o| #collect))
+                            .unwrap_or_default()
+                    }
+                }
+            })
+        }
+        "assign" => {
+            let (target, sources) = args.split_first()?;
+            handle_assign(&target.expr, sources)
+        }
+        "freeze" => {
+            // A named value stays usable after being frozen, so it is copied
+            // rather than moved; literals and calls are already owned
+            let frozen = args.first()?;
+            let value = convert_expr_or_spread(frozen);
+            match &*frozen.expr {
+                Expr::Ident(_) | Expr::Member(_) => Some(quote! { #value.clone() }),
+                _ => Some(value),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// `{ key: value, ... }` as `(key, value)` pairs, if every property is a plain key
+fn literal_fields(obj: &ObjectLit) -> Option<Vec<(String, &Expr)>> {
+    obj.props
+        .iter()
+        .map(|prop| match prop {
+            PropOrSpread::Prop(prop) => match &**prop {
+                Prop::KeyValue(kv) => {
+                    let key = match &kv.key {
+                        PropName::Ident(ident) => ident.sym.to_string(),
+                        PropName::Str(s) => s.value.as_str()?.to_string(),
+                        _ => return None,
+                    };
+                    Some((key, &*kv.value))
+                }
+                _ => None,
+            },
+            PropOrSpread::Spread(_) => None,
+        })
+        .collect()
+}
+
+fn handle_assign(target: &Expr, sources: &[ExprOrSpread]) -> Option<TokenStream> {
+    let literal_sources: Option<Vec<_>> = sources
+        .iter()
+        .map(|source| match &*source.expr {
+            Expr::Object(obj) => literal_fields(obj),
+            _ => None,
+        })
+        .collect();
+
+    match (target, shape_of(target)) {
+        // `Object.assign({}, defaults, { port: 8080 })` -> struct update syntax
+        (Expr::Object(empty), _) if empty.props.is_empty() && !sources.is_empty() => {
+            if let Shape::Struct(name, _) = shape_of(&sources[0].expr) {
+                let overrides: Option<Vec<_>> = sources[1..]
+                    .iter()
+                    .map(|source| match &*source.expr {
+                        Expr::Object(obj) => literal_fields(obj),
+                        _ => None,
+                    })
+                    .collect();
+                if let Some(overrides) = overrides {
+                    let base = convert_expr_or_spread(&sources[0]);
+                    let struct_name = format_ident!("{}", name);
+                    let fields = overrides.into_iter().flatten().map(|(key, value)| {
+                        let field = format_ident!("{}", key);
+                        let value = convert_expr(value);
+                        quote! { #field: #value }
+                    });
+                    return Some(quote! { #struct_name { #(#fields,)* ..#base.clone() } });
+                }
+            }
+            Some(merge_as_value(target, sources))
+        }
+        (_, Shape::Map) => {
+            let place = convert_place_expr(target);
+            let extends =
+                sources.iter().map(|source| match &*source.expr {
+                    Expr::Object(obj) => {
+                        let pairs = literal_fields(obj).unwrap_or_default().into_iter().map(
+                            |(key, value)| {
+                                let value = convert_expr(value);
+                                quote! { (String::from(#key), #value) }
+                            },
+                        );
+                        quote! { #place.extend([#(#pairs),*]); }
+                    }
+                    _ => {
+                        let source = convert_expr_or_spread(source);
+                        quote! { #place.extend(#source.clone()); }
+                    }
+                });
+            Some(quote! {
+                {
+                    #(#extends)*
+                    #place.clone()
+                }
+            })
+        }
+        (_, Shape::Struct(..)) if literal_sources.is_some() => {
+            let place = convert_place_expr(target);
+            let assignments =
+                literal_sources
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .map(|(key, value)| {
+                        let field = format_ident!("{}", key);
+                        let value = convert_expr(value);
+                        quote! { #place.#field = #value; }
+                    });
+            Some(quote! {
+                {
+                    #(#assignments)*
+                    #place.clone()
+                }
+            })
+        }
+        _ => Some(merge_as_value(target, sources)),
+    }
+}
+
+/// Fallback: merge the JSON representations of all arguments
+fn merge_as_value(target: &Expr, sources: &[ExprOrSpread]) -> TokenStream {
+    diagnostics::warn(
+        "`Object.assign` on values of unknown shape is translated to a `serde_json::Value` merge; \
+         the target is not modified in place",
+    );
+    let target = convert_expr(target);
+    let sources = sources.iter().map(convert_expr_or_spread);
+    quote! {
+        {
+            let mut merged = serde_json::to_value(&#target).unwrap();
+            for source in [#(serde_json::to_value(&#sources).unwrap()),*] {
+                if let (Some(merged), serde_json::Value::Object(source)) = (merged.as_object_mut(), source) {
+                    merged.extend(source);
+                }
+            }
+            merged
+        }
+    }
+}
+
+/// `structuredClone(x)` -> `x.clone()`
+pub fn structured_clone(args: &[ExprOrSpread]) -> Option<TokenStream> {
+    let value = convert_expr_or_spread(args.first()?);
+    Some(quote! { #value.clone() })
+}
@@ -0,0 +1,90 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use swc_ecma_ast::*;
+
+use super::super::convert::func::convert_str_arg;
+use crate::diagnostics;
+
+// Paths stay `String`s, as in JS. `join`, `resolve` and `normalize` follow
+// node's POSIX rules through `typerust_runtime`, since `PathBuf::join` lets an
+// absolute segment replace the path and leaves `..` in place.
+
+/// `a`, `b`, ... -> `typerust_runtime::<function>(&[a, b, ...])`
+fn segments(function: &str, args: &[ExprOrSpread]) -> TokenStream {
+    let function = crate::runtime::path(function);
+    let segments = args.iter().map(|arg| convert_str_arg(&arg.expr));
+    quote! { #function(&[#(#segments),*]) }
+}
+
+/// Handle `path.*` calls
+pub fn handle(method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    match method {
+        "join" => Some(segments("path_join", args)),
+        "resolve" => Some(segments("path_resolve", args)),
+        "normalize" => {
+            let path = convert_str_arg(&args.first()?.expr);
+            let normalize = crate::runtime::path("path_normalize");
+            Some(quote! { #normalize(#path) })
+        }
+        "dirname" => {
+            let path = convert_str_arg(&args.first()?.expr);
+            Some(quote! {
+                std::path::Path::new(#path)
+                    .parent()
+                    .filter(|parent| !parent.as_os_str().is_empty())
+                    .map_or_else(|| String::from("."), |parent| parent.to_string_lossy().into_owned())
+            })
+        }
+        "basename" => {
+            let path = convert_str_arg(&args.first()?.expr);
+            let name = quote! {
+                std::path::Path::new(#path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            };
+            match args.get(1) {
+                Some(ext) => {
+                    let ext = convert_str_arg(&ext.expr);
+                    Some(quote! {
+                        {
+                            let name = #name;
+                            name.strip_suffix(#ext).map(str::to_string).unwrap_or(name)
+                        }
+                    })
+                }
+                None => Some(name),
+            }
+        }
+        "extname" => {
+            let path = convert_str_arg(&args.first()?.expr);
+            Some(quote! {
+                std::path::Path::new(#path)
+                    .extension()
+                    .map(|ext| format!(".{}", ext.to_string_lossy()))
+                    .unwrap_or_default()
+            })
+        }
+        "isAbsolute" => {
+            let path = convert_str_arg(&args.first()?.expr);
+            Some(quote! { std::path::Path::new(#path).is_absolute() })
+        }
+        _ => {
+            let name = format!("path.{}", method);
+            diagnostics::warn(format!(
+                "`{}` has no Rust shim; use `std::path` directly",
+                name
+            ));
+            Some(quote! { todo!(#name) })
+        }
+    }
+}
+
+/// `path.sep` and `path.delimiter`, as `&'static str` like string constants
+pub fn constant(name: &str) -> Option<TokenStream> {
+    match name {
+        "sep" => Some(quote! { std::path::MAIN_SEPARATOR_STR }),
+        "delimiter" => Some(quote! { (if cfg!(windows) { ";" } else { ":" }) }),
+        _ => None,
+    }
+}
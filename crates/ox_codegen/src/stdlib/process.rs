@@ -0,0 +1,73 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use swc_ecma_ast::*;
+
+use super::super::convert::func::{convert_expr_or_spread, convert_str_arg};
+use crate::diagnostics;
+
+/// Handle `process.*` calls
+pub fn handle(method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    match method {
+        "exit" => {
+            let code = args
+                .first()
+                .map(convert_expr_or_spread)
+                .unwrap_or_else(|| quote! { 0 });
+            Some(quote! { std::process::exit((#code) as i32) })
+        }
+        "cwd" => Some(quote! {
+            std::env::current_dir()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned()
+        }),
+        _ => {
+            let name = format!("process.{}", method);
+            diagnostics::warn(format!(
+                "`{}` has no Rust shim; use `std::process` or `std::env` directly",
+                name
+            ));
+            Some(quote! { todo!(#name) })
+        }
+    }
+}
+
+/// `process.argv`, `process.platform`, `process.pid` and `process.env`
+pub fn property(name: &str) -> Option<TokenStream> {
+    match name {
+        // JS puts the `node` binary first and the script second; the Rust
+        // binary stands in for the script so user arguments keep their indices
+        "argv" => Some(quote! {
+            std::iter::once(String::from("node"))
+                .chain(std::env::args())
+                .collect::<Vec<String>>()
+        }),
+        "platform" => Some(quote! {
+            String::from(match std::env::consts::OS {
+                "macos" => "darwin",
+                "windows" => "win32",
+                os => os,
+            })
+        }),
+        "pid" => Some(quote! { (std::process::id() as f64) }),
+        "env" => Some(quote! {
+            std::env::vars().collect::<std::collections::HashMap<String, String>>()
+        }),
+        _ => None,
+    }
+}
+
+/// `process.env.NAME` or `process.env[name]`: `Some(value)` if the variable is set
+pub fn env_var(prop: &MemberProp) -> Option<TokenStream> {
+    match prop {
+        MemberProp::Ident(ident) => {
+            let name = ident.sym.to_string();
+            Some(quote! { std::env::var(#name).ok() })
+        }
+        MemberProp::Computed(computed) => {
+            let name = convert_str_arg(&computed.expr);
+            Some(quote! { std::env::var(#name).ok() })
+        }
+        MemberProp::PrivateName(_) => None,
+    }
+}
@@ -0,0 +1,295 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use swc_ecma_ast::*;
+
+use super::super::convert::func::{convert_expr, convert_expr_or_spread};
+use super::super::convert::scope;
+use super::timers;
+use crate::{dependencies, diagnostics};
+
+// Every promise is emitted as a future yielding `Result<T, crate::AppError>`,
+// so `await` is uniformly `.await?`. The exceptions are `Promise.allSettled`,
+// whose settled results are never an error, and `Promise.resolve/reject`,
+// which need no future at all when awaited.
+
+fn require_futures() {
+    dependencies::require("futures", "\"0.3\"");
+}
+
+/// `Promise.method(args)`
+fn static_call(expr: &Expr) -> Option<(&str, &[ExprOrSpread])> {
+    let Expr::Call(call) = expr else {
+        return None;
+    };
+    let Callee::Expr(callee) = &call.callee else {
+        return None;
+    };
+    let Expr::Member(member) = &**callee else {
+        return None;
+    };
+    if member.obj.as_ident()?.sym != "Promise" {
+        return None;
+    }
+    Some((member.prop.as_ident()?.sym.as_ref(), &call.args))
+}
+
+/// Whether `expr` evaluates to a promise rather than a value
+pub fn is_promise_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(paren) => is_promise_expr(&paren.expr),
+        Expr::New(new_expr) => new_expr
+            .callee
+            .as_ident()
+            .is_some_and(|callee| callee.sym == "Promise"),
+        Expr::Call(call) => {
+            static_call(expr).is_some()
+                || call
+                    .callee
+                    .as_expr()
+                    .and_then(|callee| callee.as_ident())
+                    .is_some_and(|ident| scope::is_async_function(&ident.sym))
+        }
+        _ => false,
+    }
+}
+
+/// `await expr`
+pub fn await_expr(expr: &Expr) -> TokenStream {
+    match static_call(expr) {
+        Some(("resolve", args)) => args
+            .first()
+            .map(convert_expr_or_spread)
+            .unwrap_or_else(|| quote! { () }),
+        Some(("reject", args)) => {
+            let err = rejection(args.first().map(|arg| &*arg.expr));
+            quote! { return Err(#err) }
+        }
+        Some(("allSettled", _)) => {
+            let future = convert_expr(expr);
+            quote! { #future.await }
+        }
+        _ => {
+            let future = convert_expr(expr);
+            quote! { #future.await? }
+        }
+    }
+}
+
+/// `return promise` inside an async function resolves to the promise's
+/// outcome, like JS does. `None` if `expr` is not a promise.
+pub fn return_value(expr: &Expr) -> Option<TokenStream> {
+    if super::fetch::is_fetch_promise(expr) {
+        let future = convert_expr(expr);
+        return Some(quote! { return Ok(#future.await?); });
+    }
+    if !is_promise_expr(expr) {
+        return None;
+    }
+    Some(match static_call(expr) {
+        Some(("resolve" | "allSettled", _)) => {
+            let value = await_expr(expr);
+            quote! { return Ok(#value); }
+        }
+        Some(("reject", _)) => {
+            let err = await_expr(expr);
+            quote! { #err; }
+        }
+        _ => {
+            let future = convert_expr(expr);
+            quote! { return #future.await; }
+        }
+    })
+}
+
+/// The `crate::AppError` a promise rejects with
+pub fn rejection(reason: Option<&Expr>) -> TokenStream {
+    crate::convert::error::app_error(reason)
+}
+
+fn warn_settled() {
+    diagnostics::warn(
+        "`Promise.allSettled` yields `Result`s: check `is_ok()` instead of \
+         `status === \"fulfilled\"`, and read `value`/`reason` from `Ok`/`Err`",
+    );
+}
+
+/// Elements of an array literal, if it has no holes or spreads
+fn literal_elements(arr: &ArrayLit) -> Option<Vec<&Expr>> {
+    arr.elems
+        .iter()
+        .map(|elem| match elem {
+            Some(ExprOrSpread { spread: None, expr }) => Some(&**expr),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Wraps an element so futures with different error types can be joined
+fn as_app_result(expr: &Expr) -> TokenStream {
+    let value = await_expr(expr);
+    quote! { async { Ok::<_, crate::AppError>(#value) } }
+}
+
+/// `futures::future::join`/`join3`/... (or the `try_` variants) for a literal
+/// of two to five promises, so `const [a, b] = await ...` can destructure a tuple
+fn join_tuple(prefix: &str, futures: &[TokenStream]) -> Option<TokenStream> {
+    let name = match futures.len() {
+        2 => format_ident!("{}", prefix),
+        3..=5 => format_ident!("{}{}", prefix, futures.len()),
+        _ => return None,
+    };
+    Some(quote! { futures::future::#name(#(#futures),*) })
+}
+
+/// `await Promise.all([a, b])` (or `allSettled`) being destructured into
+/// `const [x, y]`: the promises may have different types, so they are joined
+/// into a tuple rather than a `Vec`
+pub fn joined_tuple(expr: &Expr) -> Option<TokenStream> {
+    let Expr::Await(await_expr) = expr else {
+        return None;
+    };
+    let (method, args) = static_call(&await_expr.arg)?;
+    let elems = args
+        .first()
+        .and_then(|arg| arg.expr.as_array())
+        .and_then(literal_elements)?;
+    match method {
+        "all" => {
+            require_futures();
+            let futures: Vec<_> = elems.iter().map(|e| as_app_result(e)).collect();
+            let joined = join_tuple("try_join", &futures)?;
+            Some(quote! { #joined.await? })
+        }
+        "allSettled" => {
+            require_futures();
+            warn_settled();
+            let futures: Vec<_> = elems.iter().map(|e| convert_expr(e)).collect();
+            let joined = join_tuple("join", &futures)?;
+            Some(quote! { #joined.await })
+        }
+        _ => None,
+    }
+}
+
+/// Handle Promise.* calls
+pub fn handle(method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    match method {
+        "all" => {
+            require_futures();
+            let promises = convert_expr(&args.first()?.expr);
+            Some(quote! { futures::future::try_join_all(#promises) })
+        }
+        "allSettled" => {
+            require_futures();
+            warn_settled();
+            let promises = convert_expr(&args.first()?.expr);
+            Some(quote! { futures::future::join_all(#promises) })
+        }
+        "race" => {
+            let arg = &*args.first()?.expr;
+            if let Some(elems) = arg.as_array().and_then(literal_elements) {
+                let futures = elems.iter().map(|e| as_app_result(e));
+                return Some(quote! {
+                    async {
+                        tokio::select! {
+                            #(result = #futures => result,)*
+                        }
+                    }
+                });
+            }
+            require_futures();
+            let promises = convert_expr(arg);
+            Some(quote! {
+                async {
+                    futures::future::select_all(#promises.into_iter().map(Box::pin)).await.0
+                }
+            })
+        }
+        "resolve" => {
+            require_futures();
+            let value = args
+                .first()
+                .map(convert_expr_or_spread)
+                .unwrap_or_else(|| quote! { () });
+            Some(quote! { futures::future::ok::<_, crate::AppError>(#value) })
+        }
+        "reject" => {
+            require_futures();
+            let err = rejection(args.first().map(|arg| &*arg.expr));
+            Some(quote! { futures::future::err::<_, crate::AppError>(#err) })
+        }
+        _ => None,
+    }
+}
+
+/// The single expression an arrow function evaluates, if it has one
+fn arrow_body_expr(arrow: &ArrowExpr) -> Option<&Expr> {
+    match &*arrow.body {
+        BlockStmtOrExpr::Expr(expr) => Some(expr),
+        BlockStmtOrExpr::BlockStmt(block) => match block.stmts.as_slice() {
+            [Stmt::Expr(stmt)] => Some(&stmt.expr),
+            _ => None,
+        },
+    }
+}
+
+fn param_name(pat: &Pat) -> Option<&str> {
+    pat.as_ident().map(|ident| ident.sym.as_ref())
+}
+
+/// `new Promise(executor)`. Only timer executors are translated:
+/// `new Promise(resolve => setTimeout(resolve, ms))` and the same with a
+/// callback calling `resolve(value)` or `reject(error)`.
+pub fn handle_new(args: &[ExprOrSpread]) -> Option<TokenStream> {
+    let timer = args
+        .first()
+        .and_then(|arg| arg.expr.as_arrow())
+        .and_then(|executor| {
+            let resolve = executor.params.first().and_then(param_name);
+            let reject = executor.params.get(1).and_then(param_name);
+            let Expr::Call(call) = arrow_body_expr(executor)? else {
+                return None;
+            };
+            let callee = call.callee.as_expr()?.as_ident()?;
+            if callee.sym != "setTimeout" {
+                return None;
+            }
+            let duration = timers::duration(call.args.get(1));
+            let outcome = match &*call.args.first()?.expr {
+                Expr::Ident(ident) if Some(ident.sym.as_ref()) == resolve => {
+                    quote! { Ok::<_, crate::AppError>(()) }
+                }
+                Expr::Arrow(callback) if callback.params.is_empty() => {
+                    let Expr::Call(settle) = arrow_body_expr(callback)? else {
+                        return None;
+                    };
+                    let settle_name = settle.callee.as_expr()?.as_ident()?.sym.as_ref();
+                    let value = settle.args.first().map(|arg| &*arg.expr);
+                    if Some(settle_name) == resolve {
+                        let value = value.map(convert_expr).unwrap_or_else(|| quote! { () });
+                        quote! { Ok::<_, crate::AppError>(#value) }
+                    } else if Some(settle_name) == reject {
+                        let err = rejection(value);
+                        quote! { Err::<_, crate::AppError>(#err) }
+                    } else {
+                        return None;
+                    }
+                }
+                _ => return None,
+            };
+            Some(quote! {
+                async {
+                    tokio::time::sleep(#duration).await;
+                    #outcome
+                }
+            })
+        });
+
+    if timer.is_none() {
+        diagnostics::warn(
+            "`new Promise(executor)` is only translated for `setTimeout` executors; \
+             rewrite other executors with async/await",
+        );
+    }
+    timer
+}
@@ -0,0 +1,356 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use swc_ecma_ast::*;
+
+use super::super::convert::func::{
+    convert_call_args, convert_expr, convert_expr_or_spread, to_snake_case,
+};
+use super::super::convert::scope;
+use super::{array, string};
+
+// `includes`, `slice`, `indexOf`, `concat` and `at` exist on strings and
+// arrays alike, and `toString` on everything, so a method call is routed by
+// the TS type of its receiver: the annotation of a local or parameter, the
+// type of a field, or the declared return type of the function called.
+
+/// What a method call's receiver is known to be
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Receiver {
+    String,
+    Array,
+    Number,
+    /// A class or interface of this file, whose methods are its own
+    Declared,
+}
+
+/// The kind of receiver `expr` is, or `None` when its type is unknown
+pub fn classify(expr: &Expr) -> Option<Receiver> {
+    match expr {
+        Expr::Lit(Lit::Str(_)) | Expr::Tpl(_) => return Some(Receiver::String),
+        Expr::Lit(Lit::Num(_)) => return Some(Receiver::Number),
+        Expr::Array(_) => return Some(Receiver::Array),
+        Expr::Paren(paren) => return classify(&paren.expr),
+        Expr::Bin(bin) => match bin.op {
+            BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod | BinaryOp::Exp => {
+                return Some(Receiver::Number)
+            }
+            BinaryOp::Add
+                if classify(&bin.left) == Some(Receiver::Number)
+                    && classify(&bin.right) == Some(Receiver::Number) =>
+            {
+                return Some(Receiver::Number)
+            }
+            _ => {}
+        },
+        _ => {}
+    }
+    if let Some(receiver) = static_type(expr).and_then(|ts_type| classify_type(&ts_type)) {
+        return Some(receiver);
+    }
+    // Chains such as `name.trim().slice(1)` or `xs.map(f).includes(x)`
+    if string::is_string_expr(expr) {
+        Some(Receiver::String)
+    } else if array::is_array_expr(expr) {
+        Some(Receiver::Array)
+    } else {
+        None
+    }
+}
+
+/// Whether `expr` is a `bool`: a literal, a negation or comparison, or a
+/// value annotated `boolean`
+pub fn is_boolean(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(Lit::Bool(_)) => true,
+        Expr::Paren(paren) => is_boolean(&paren.expr),
+        Expr::Unary(unary) => unary.op == UnaryOp::Bang,
+        Expr::Bin(bin) => matches!(
+            bin.op,
+            BinaryOp::EqEq
+                | BinaryOp::EqEqEq
+                | BinaryOp::NotEq
+                | BinaryOp::NotEqEq
+                | BinaryOp::Lt
+                | BinaryOp::LtEq
+                | BinaryOp::Gt
+                | BinaryOp::GtEq
+        ),
+        _ => matches!(
+            static_type(expr),
+            Some(TsType::TsKeywordType(TsKeywordType {
+                kind: TsKeywordTypeKind::TsBooleanKeyword,
+                ..
+            }))
+        ),
+    }
+}
+
+/// The kind of the elements of the array `expr`, as far as its annotation or
+/// literal tells
+pub fn classify_element(expr: &Expr) -> Option<Receiver> {
+    match expr {
+        Expr::Paren(paren) => classify_element(&paren.expr),
+        Expr::Array(array) => {
+            let mut kinds = array.elems.iter().map(|elem| {
+                elem.as_ref()
+                    .filter(|e| e.spread.is_none())
+                    .and_then(|e| classify(&e.expr))
+            });
+            let first = kinds.next()??;
+            kinds.all(|kind| kind == Some(first)).then_some(first)
+        }
+        _ => match static_type(expr)? {
+            TsType::TsArrayType(array) => classify_type(&array.elem_type),
+            TsType::TsTypeOperator(op) => match &*op.type_ann {
+                TsType::TsArrayType(array) => classify_type(&array.elem_type),
+                _ => None,
+            },
+            TsType::TsTypeRef(type_ref) => {
+                classify_type(type_ref.type_params.as_ref()?.params.first()?)
+            }
+            _ => None,
+        },
+    }
+}
+
+/// The type an unannotated binding gets from a literal initializer: `string`,
+/// `number`, `boolean`, or an array of the element kind (`unknown[]` when the
+/// elements differ)
+pub fn literal_type(expr: &Expr) -> Option<TsType> {
+    let keyword = |kind| {
+        TsType::TsKeywordType(TsKeywordType {
+            span: swc_common::DUMMY_SP,
+            kind,
+        })
+    };
+    match expr {
+        Expr::Paren(paren) => literal_type(&paren.expr),
+        Expr::Lit(Lit::Str(_)) | Expr::Tpl(_) => Some(keyword(TsKeywordTypeKind::TsStringKeyword)),
+        Expr::Lit(Lit::Num(_)) => Some(keyword(TsKeywordTypeKind::TsNumberKeyword)),
+        Expr::Lit(Lit::Bool(_)) => Some(keyword(TsKeywordTypeKind::TsBooleanKeyword)),
+        Expr::Array(_) => {
+            let elem_type = match classify_element(expr) {
+                Some(Receiver::String) => keyword(TsKeywordTypeKind::TsStringKeyword),
+                Some(Receiver::Number) => keyword(TsKeywordTypeKind::TsNumberKeyword),
+                _ => keyword(TsKeywordTypeKind::TsUnknownKeyword),
+            };
+            Some(TsType::TsArrayType(TsArrayType {
+                span: swc_common::DUMMY_SP,
+                elem_type: Box::new(elem_type),
+            }))
+        }
+        _ => None,
+    }
+}
+
+/// Lookups that are `Option`s in Rust because they may miss
+const MAYBE_MISSING: &[&str] = &["find", "findLast", "pop", "shift", "at", "get", "exec"];
+
+/// Whether `expr` is an `Option` in the generated code: a value typed as a
+/// union with `null` or `undefined` (optional parameters and fields are), an
+/// environment variable, an optional chain, or a lookup that may miss
+pub fn is_nullable(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(paren) => is_nullable(&paren.expr),
+        Expr::OptChain(_) | Expr::Lit(Lit::Null(_)) => true,
+        Expr::Ident(ident) if ident.sym == "undefined" => true,
+        Expr::Member(member) if super::is_process_env(&member.obj) => true,
+        Expr::Member(member) if is_optional_field(member) => true,
+        Expr::Member(_) if super::axios::is_error_response(expr) => true,
+        Expr::Call(call)
+            if call
+                .callee
+                .as_expr()
+                .and_then(|callee| callee.as_member())
+                .and_then(|member| member.prop.as_ident())
+                .is_some_and(|prop| MAYBE_MISSING.contains(&prop.sym.as_ref())) =>
+        {
+            true
+        }
+        _ => static_type(expr).is_some_and(|ts_type| is_nullable_type(&ts_type)),
+    }
+}
+
+fn is_nullable_type(ts_type: &TsType) -> bool {
+    match ts_type {
+        TsType::TsParenthesizedType(paren) => is_nullable_type(&paren.type_ann),
+        TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(union)) => {
+            union.types.iter().any(|t| is_nullish(t))
+        }
+        _ => false,
+    }
+}
+
+/// `user.email` where `email?: string` is declared on an interface
+fn is_optional_field(member: &MemberExpr) -> bool {
+    let (Some(type_name), Some(prop)) = (
+        static_type(&member.obj).and_then(|t| type_name(&t)),
+        member.prop.as_ident(),
+    ) else {
+        return false;
+    };
+    scope::lookup_struct(&type_name).is_some_and(|fields| {
+        fields
+            .iter()
+            .any(|f| f.name == prop.sym.as_ref() && f.optional)
+    })
+}
+
+/// How a value is tested by `if (x)` or `!x`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Truthiness {
+    /// Already a `bool`, or of unknown type
+    Bool,
+    /// A string, number or array, tested with `typerust_runtime::truthy`
+    Value,
+    /// A nullable object, truthy when present
+    Present,
+}
+
+/// How `expr` is tested in a condition
+pub fn truthiness(expr: &Expr) -> Truthiness {
+    if super::axios::is_error_response(expr) {
+        return Truthiness::Present;
+    }
+    if let Some(ts_type) = static_type(expr) {
+        if is_nullable_type(&ts_type) && classify_type(&ts_type) == Some(Receiver::Declared) {
+            return Truthiness::Present;
+        }
+    }
+    match classify(expr) {
+        Some(Receiver::String | Receiver::Number | Receiver::Array) => Truthiness::Value,
+        _ => Truthiness::Bool,
+    }
+}
+
+/// `user.toString()` on a class of this file calls the class's own method,
+/// emitted under its snake_case name, with omitted optional arguments as `None`
+pub fn handle_declared_method(
+    obj: &Expr,
+    method: &str,
+    args: &[ExprOrSpread],
+) -> Option<TokenStream> {
+    let class = scope::lookup_class(&type_name(&static_type(obj)?)?)?;
+    if !class.methods.contains_key(method) {
+        return None;
+    }
+    let obj_tokens = convert_expr(obj);
+    let method_ident = format_ident!("{}", to_snake_case(method));
+    let args: Vec<_> = match class.method_params.get(method) {
+        Some(params) => convert_call_args(params, args),
+        None => args.iter().map(convert_expr_or_spread).collect(),
+    };
+    Some(quote! { #obj_tokens.#method_ident(#(#args),*) })
+}
+
+/// The kind of receiver a value of `ts_type` is
+fn classify_type(ts_type: &TsType) -> Option<Receiver> {
+    if array::is_array_type(ts_type) {
+        return Some(Receiver::Array);
+    }
+    match ts_type {
+        TsType::TsKeywordType(keyword) => match keyword.kind {
+            TsKeywordTypeKind::TsStringKeyword => Some(Receiver::String),
+            TsKeywordTypeKind::TsNumberKeyword => Some(Receiver::Number),
+            _ => None,
+        },
+        TsType::TsLitType(lit) => match lit.lit {
+            TsLit::Str(_) | TsLit::Tpl(_) => Some(Receiver::String),
+            TsLit::Number(_) => Some(Receiver::Number),
+            _ => None,
+        },
+        TsType::TsParenthesizedType(paren) => classify_type(&paren.type_ann),
+        // `string | undefined` after a guard
+        TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(union)) => {
+            let mut present = union.types.iter().filter(|t| !is_nullish(t));
+            match (present.next(), present.next()) {
+                (Some(only), None) => classify_type(only),
+                _ => None,
+            }
+        }
+        TsType::TsTypeRef(_) => {
+            let name = type_name(ts_type)?;
+            (scope::lookup_class(&name).is_some() || scope::lookup_struct(&name).is_some())
+                .then_some(Receiver::Declared)
+        }
+        _ => None,
+    }
+}
+
+fn is_nullish(ts_type: &TsType) -> bool {
+    matches!(
+        ts_type,
+        TsType::TsKeywordType(TsKeywordType {
+            kind: TsKeywordTypeKind::TsUndefinedKeyword | TsKeywordTypeKind::TsNullKeyword,
+            ..
+        })
+    )
+}
+
+fn type_name(ts_type: &TsType) -> Option<String> {
+    match ts_type {
+        TsType::TsTypeRef(type_ref) => Some(type_ref.type_name.as_ident()?.sym.to_string()),
+        _ => None,
+    }
+}
+
+/// The declared TS type of `expr`, as far as the annotations in scope tell
+fn static_type(expr: &Expr) -> Option<TsType> {
+    match expr {
+        Expr::Ident(ident) => scope::lookup_type(&ident.sym),
+        Expr::This(_) => Some(scope::type_ref(&scope::current_class()?)),
+        Expr::Paren(paren) => static_type(&paren.expr),
+        Expr::TsNonNull(non_null) => static_type(&non_null.expr),
+        Expr::TsAs(as_expr) => Some((*as_expr.type_ann).clone()),
+        Expr::Member(member) => {
+            let prop = member.prop.as_ident()?;
+            if prop.sym == "length" {
+                return Some(TsType::TsKeywordType(TsKeywordType {
+                    span: swc_common::DUMMY_SP,
+                    kind: TsKeywordTypeKind::TsNumberKeyword,
+                }));
+            }
+            field_type(&type_name(&static_type(&member.obj)?)?, &prop.sym)
+        }
+        Expr::Call(call) => match &**call.callee.as_expr()? {
+            Expr::Ident(ident) => scope::lookup_function_return(&ident.sym),
+            Expr::Member(member) => {
+                let class = scope::lookup_class(&type_name(&static_type(&member.obj)?)?)?;
+                class
+                    .methods
+                    .get(member.prop.as_ident()?.sym.as_ref())
+                    .cloned()
+                    .flatten()
+            }
+            _ => None,
+        },
+        // `(await load()).slice(1)` where `load(): Promise<string>`
+        Expr::Await(await_expr) => match static_type(&await_expr.arg)? {
+            TsType::TsTypeRef(type_ref)
+                if type_ref
+                    .type_name
+                    .as_ident()
+                    .is_some_and(|ident| ident.sym == "Promise") =>
+            {
+                type_ref
+                    .type_params
+                    .as_ref()
+                    .and_then(|params| params.params.first())
+                    .map(|param| (**param).clone())
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The type of field `name` of a class or interface of this file
+fn field_type(type_name: &str, name: &str) -> Option<TsType> {
+    if let Some(class) = scope::lookup_class(type_name) {
+        return class.fields.get(name).cloned();
+    }
+    scope::lookup_struct(type_name)?
+        .into_iter()
+        .find(|field| field.name == name)
+        .map(|field| field.ts_type)
+}
@@ -0,0 +1,405 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use swc_ecma_ast::*;
+
+use super::super::convert::func::{
+    convert_expr, convert_expr_or_spread, convert_stmt_recursive, convert_str_arg, to_snake_case,
+};
+use super::super::convert::scope;
+use crate::{dependencies, diagnostics};
+
+/// A regex whose source and flags are known at compile time
+pub struct Pattern {
+    source: String,
+    flags: String,
+}
+
+/// `/source/flags`, or `new RegExp("source", "flags")` with literal arguments
+pub fn pattern_of(expr: &Expr) -> Option<Pattern> {
+    match expr {
+        Expr::Paren(paren) => pattern_of(&paren.expr),
+        Expr::Lit(Lit::Regex(regex)) => Some(Pattern {
+            source: regex.exp.to_string(),
+            flags: regex.flags.to_string(),
+        }),
+        Expr::New(new_expr) if is_regexp_ctor(new_expr) => Some(Pattern {
+            source: literal_arg(new_expr, 0)?,
+            flags: literal_arg(new_expr, 1)?,
+        }),
+        _ => None,
+    }
+}
+
+/// A string literal argument of `new RegExp(...)`; an omitted one is empty
+fn literal_arg(new_expr: &NewExpr, index: usize) -> Option<String> {
+    let args = new_expr.args.as_deref().unwrap_or_default();
+    match args.get(index).map(|arg| &*arg.expr) {
+        Some(Expr::Lit(Lit::Str(s))) => Some(s.value.to_string_lossy().into_owned()),
+        None => Some(String::new()),
+        Some(_) => None,
+    }
+}
+
+fn is_regexp_ctor(new_expr: &NewExpr) -> bool {
+    new_expr
+        .callee
+        .as_ident()
+        .is_some_and(|callee| callee.sym == "RegExp")
+}
+
+/// Whether `expr` evaluates to a `regex::Regex`
+pub fn is_regex_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(paren) => is_regex_expr(&paren.expr),
+        Expr::Lit(Lit::Regex(_)) => true,
+        Expr::New(new_expr) => is_regexp_ctor(new_expr),
+        Expr::Ident(ident) => scope::regex_flags(&ident.sym).is_some(),
+        _ => false,
+    }
+}
+
+/// The flags of a regex expression, as far as they are known
+fn flags_of(expr: &Expr) -> String {
+    match expr {
+        Expr::Ident(ident) => scope::regex_flags(&ident.sym).unwrap_or_default(),
+        _ => pattern_of(expr).map(|p| p.flags).unwrap_or_default(),
+    }
+}
+
+/// Flags to remember for a binding initialised with `init`, if it is a regex
+pub fn declared_flags(init: &Expr) -> Option<String> {
+    if let Some(pattern) = pattern_of(init) {
+        return Some(pattern.flags);
+    }
+    is_regex_expr(init).then(|| flags_of(init))
+}
+
+/// Rewrites a JS pattern for the `regex` crate, reporting what it cannot express
+fn translate(pattern: &Pattern) -> String {
+    let display = format!("/{}/{}", pattern.source, pattern.flags);
+    let unsupported = |feature: &str| {
+        diagnostics::warn(format!(
+            "regex {}: {} are not supported by the `regex` crate; \
+             rewrite the pattern or use `fancy-regex`",
+            display, feature
+        ));
+    };
+
+    let mut inline_flags = String::new();
+    for flag in pattern.flags.chars() {
+        match flag {
+            'i' | 'm' | 's' => inline_flags.push(flag),
+            // Global/unicode only change how the regex is applied
+            'g' | 'u' => {}
+            other => {
+                diagnostics::warn(format!("unsupported regex flag `{}` in {}", other, display))
+            }
+        }
+    }
+
+    let mut source = String::new();
+    let mut chars = pattern.source.chars().peekable();
+    let mut in_class = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let Some(next) = chars.next() else {
+                    source.push(c);
+                    break;
+                };
+                match next {
+                    '1'..='9' if !in_class => unsupported("backreferences"),
+                    'k' if chars.peek() == Some(&'<') => unsupported("backreferences"),
+                    _ => {}
+                }
+                source.push(c);
+                source.push(next);
+            }
+            '[' if !in_class => {
+                // `[^]` matches any character, newlines included
+                let rest: String = chars.clone().take(2).collect();
+                if rest == "^]" {
+                    chars.nth(1);
+                    source.push_str("(?s:.)");
+                    continue;
+                }
+                in_class = true;
+                source.push(c);
+            }
+            ']' if in_class => {
+                in_class = false;
+                source.push(c);
+            }
+            '(' if !in_class && chars.peek() == Some(&'?') => {
+                let rest: String = chars.clone().take(3).collect();
+                if rest.starts_with("?<=") || rest.starts_with("?<!") {
+                    unsupported("lookbehind assertions");
+                } else if rest.starts_with("?=") || rest.starts_with("?!") {
+                    unsupported("lookahead assertions");
+                }
+                source.push(c);
+            }
+            _ => source.push(c),
+        }
+    }
+
+    if inline_flags.is_empty() {
+        source
+    } else {
+        format!("(?{}){}", inline_flags, source)
+    }
+}
+
+/// The initializer of a module-level regex static, which is already lazy
+pub fn compile_static(init: &Expr) -> Option<TokenStream> {
+    pattern_of(init).map(|pattern| compile(&pattern))
+}
+
+fn compile(pattern: &Pattern) -> TokenStream {
+    dependencies::require("regex", "\"1\"");
+    let source = translate(pattern);
+    quote! { regex::Regex::new(#source).unwrap() }
+}
+
+/// `/ab+c/i` -> a `regex::Regex` compiled once on first use
+fn compile_once(pattern: &Pattern) -> TokenStream {
+    let regex = compile(pattern);
+    quote! {
+        {
+            static RE: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(|| #regex);
+            &*RE
+        }
+    }
+}
+
+pub fn handle_literal(regex: &swc_ecma_ast::Regex) -> TokenStream {
+    compile_once(&Pattern {
+        source: regex.exp.to_string(),
+        flags: regex.flags.to_string(),
+    })
+}
+
+/// `new RegExp(source, flags)`; a pattern built at runtime is compiled on every call
+pub fn handle_new(new_expr: &NewExpr) -> TokenStream {
+    let flags = literal_arg(new_expr, 1);
+    let source = match (literal_arg(new_expr, 0), flags.clone()) {
+        (Some(source), Some(flags)) => return compile_once(&Pattern { source, flags }),
+        _ => new_expr.args.as_deref().unwrap_or_default().first(),
+    };
+    dependencies::require("regex", "\"1\"");
+    let Some(source) = source else {
+        return quote! { regex::Regex::new("(?:)").unwrap() };
+    };
+    let source = convert_expr(&source.expr);
+    let inline_flags: String = match flags {
+        Some(flags) => flags
+            .chars()
+            .filter(|c| matches!(c, 'i' | 'm' | 's'))
+            .collect(),
+        None => {
+            diagnostics::warn(
+                "`new RegExp` flags must be a string literal; the flags were ignored",
+            );
+            String::new()
+        }
+    };
+    if inline_flags.is_empty() {
+        quote! { regex::Regex::new(&#source).unwrap() }
+    } else {
+        let prefix = format!("(?{})", inline_flags);
+        quote! { regex::Regex::new(&format!("{}{}", #prefix, #source)).unwrap() }
+    }
+}
+
+/// Capture groups as JS match arrays; groups that did not participate are `""`
+fn captures_to_vec(captures: TokenStream) -> TokenStream {
+    quote! {
+        #captures
+            .iter()
+            .map(|m| m.map_or(String::new(), |m| m.as_str().to_string()))
+            .collect::<Vec<String>>()
+    }
+}
+
+/// Handle method calls on a regex (`re.test(s)`, `re.exec(s)`)
+pub fn handle_method(obj: &Expr, method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    let re = convert_expr(obj);
+    let text = convert_str_arg(&args.first()?.expr);
+    match method {
+        "test" => Some(quote! { #re.is_match(#text) }),
+        "exec" => {
+            if flags_of(obj).contains('g') {
+                diagnostics::warn(
+                    "`exec` on a global regex always searches from the start; \
+                     iterate with `matchAll` instead of `lastIndex`",
+                );
+            }
+            let groups = captures_to_vec(quote! { caps });
+            Some(quote! { #re.captures(#text).map(|caps| #groups) })
+        }
+        _ => None,
+    }
+}
+
+/// Handle string methods whose first argument is a regex
+pub fn handle_string_method(
+    obj: &Expr,
+    method: &str,
+    args: &[ExprOrSpread],
+) -> Option<TokenStream> {
+    let regex_arg = &*args.first()?.expr;
+    let re = convert_expr(regex_arg);
+    let text = convert_str_arg(obj);
+    let global = flags_of(regex_arg).contains('g');
+
+    match method {
+        "match" if global => Some(quote! {
+            {
+                let matches: Vec<String> =
+                    #re.find_iter(#text).map(|m| m.as_str().to_string()).collect();
+                if matches.is_empty() { None } else { Some(matches) }
+            }
+        }),
+        "match" => {
+            let groups = captures_to_vec(quote! { caps });
+            Some(quote! { #re.captures(#text).map(|caps| #groups) })
+        }
+        "matchAll" => {
+            let groups = captures_to_vec(quote! { caps });
+            Some(
+                quote! { #re.captures_iter(#text).map(|caps| #groups).collect::<Vec<Vec<String>>>() },
+            )
+        }
+        "replace" | "replaceAll" => {
+            let replacer = replacer(&args.get(1)?.expr);
+            if global || method == "replaceAll" {
+                Some(quote! { #re.replace_all(#text, #replacer).into_owned() })
+            } else {
+                Some(quote! { #re.replace(#text, #replacer).into_owned() })
+            }
+        }
+        "split" => {
+            let limit = args.get(1).map(|limit| {
+                let limit = convert_expr_or_spread(limit);
+                quote! { .take((#limit) as usize) }
+            });
+            Some(quote! {
+                #re.split(#text)#limit.map(|part| part.to_string()).collect::<Vec<String>>()
+            })
+        }
+        "search" => {
+            // JS string indices count UTF-16 code units
+            let haystack = convert_expr(obj);
+            Some(quote! {
+                #re.find(#text).map_or(-1.0, |m| #haystack[..m.start()].encode_utf16().count() as f64)
+            })
+        }
+        _ => None,
+    }
+}
+
+/// A replacement string with JS `$` patterns rewritten for the `regex` crate:
+/// `$1` -> `${1}`, `$<name>` -> `${name}`, `$&` -> `${0}`
+fn translate_replacement(replacement: &str) -> String {
+    let mut out = String::new();
+    let mut chars = replacement.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            out.push(c);
+            continue;
+        }
+        match chars.peek().copied() {
+            Some('$') => {
+                chars.next();
+                out.push_str("$$");
+            }
+            Some('&') => {
+                chars.next();
+                out.push_str("${0}");
+            }
+            Some(d) if d.is_ascii_digit() => {
+                let mut group = String::new();
+                while let Some(d) = chars.peek().copied().filter(char::is_ascii_digit) {
+                    group.push(d);
+                    chars.next();
+                    if group.len() == 2 {
+                        break;
+                    }
+                }
+                out.push_str(&format!("${{{}}}", group));
+            }
+            Some('<') => {
+                chars.next();
+                let name: String = chars.by_ref().take_while(|&c| c != '>').collect();
+                out.push_str(&format!("${{{}}}", name));
+            }
+            Some('`' | '\'') => {
+                diagnostics::warn(
+                    "the `$`` and `$'` replacement patterns are not supported and were kept literally",
+                );
+                out.push_str("$$");
+            }
+            // A lone `$` is literal
+            _ => out.push_str("$$"),
+        }
+    }
+    out
+}
+
+/// The replacement argument of `replace`: a `$`-pattern string, or a
+/// function receiving the match and its groups
+fn replacer(expr: &Expr) -> TokenStream {
+    match expr {
+        Expr::Lit(Lit::Str(s)) => {
+            let replacement = translate_replacement(&s.value.to_string_lossy());
+            quote! { #replacement }
+        }
+        Expr::Arrow(arrow) => {
+            // Parameters are the match, then one per group; a group that did not
+            // participate is `""`. The trailing offset/string arguments are not supported.
+            let string_type = TsType::TsKeywordType(TsKeywordType {
+                span: swc_common::DUMMY_SP,
+                kind: TsKeywordTypeKind::TsStringKeyword,
+            });
+            let bindings: Vec<_> = arrow
+                .params
+                .iter()
+                .enumerate()
+                .filter_map(|(index, param)| {
+                    let ident = param.as_ident()?;
+                    scope::declare(&ident.sym, &string_type);
+                    let name = format_ident!("{}", to_snake_case(&ident.sym));
+                    let index = proc_macro2::Literal::usize_unsuffixed(index);
+                    Some(quote! {
+                        let #name = caps.get(#index).map_or(String::new(), |m| m.as_str().to_string());
+                    })
+                })
+                .collect();
+            let body = match &*arrow.body {
+                BlockStmtOrExpr::Expr(expr) => {
+                    let value = convert_expr(expr);
+                    quote! { (#value).to_string() }
+                }
+                BlockStmtOrExpr::BlockStmt(block) => {
+                    let stmts = block.stmts.iter().map(|stmt| {
+                        convert_stmt_recursive(stmt, &|ret| match &ret.arg {
+                            Some(arg) => {
+                                let value = convert_expr(arg);
+                                quote! { return (#value).to_string(); }
+                            }
+                            None => quote! { return String::new(); },
+                        })
+                    });
+                    quote! { #(#stmts)* }
+                }
+            };
+            quote! {
+                |caps: &regex::Captures| -> String {
+                    #(#bindings)*
+                    #body
+                }
+            }
+        }
+        _ => convert_str_arg(expr),
+    }
+}
@@ -0,0 +1,306 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use serde::Deserialize;
+use swc_ecma_ast::{Callee, Expr, ExprOrSpread};
+
+use super::super::convert::func::convert_expr;
+use super::super::convert::scope;
+use crate::{dependencies, diagnostics};
+
+// Mappings of JS library calls that are not built in, such as a company's own
+// `@acme/utils`. They are consulted before the built-in handlers, so they can
+// also override them. Imports of a module a mapping handles emit no `use`.
+// Mappings shipped with the translator, such as lodash's, come after the
+// user's.
+
+/// Mappings shipped with the translator
+static BUILTIN: &[&dyn StdlibMapping] = &[&super::lodash::Lodash];
+
+/// A call a [`StdlibMapping`] may translate
+pub struct MappedCall<'a> {
+    /// The module the callee was imported from, or `None` for a global
+    pub module: Option<&'a str>,
+    /// The exported name for `chunk(...)` and `_.chunk(...)`, `default` for
+    /// a default import called directly, or the dotted path of a global such
+    /// as `Acme.format`
+    pub function: &'a str,
+    pub args: &'a [ExprOrSpread],
+}
+
+impl MappedCall<'_> {
+    /// The argument at `index`, converted to Rust
+    pub fn arg(&self, index: usize) -> Option<TokenStream> {
+        self.args.get(index).map(|arg| convert_expr(&arg.expr))
+    }
+}
+
+/// Translates calls into a JS library
+pub trait StdlibMapping: Send + Sync {
+    /// Whether this mapping replaces imports of `module`
+    fn handles_module(&self, module: &str) -> bool;
+
+    /// The Rust code for `call`, or `None` to leave it to the other handlers
+    fn map_call(&self, call: &MappedCall<'_>) -> Option<TokenStream>;
+}
+
+/// The mappings in use, in registration order; the first one to translate a
+/// call wins
+#[derive(Clone, Default)]
+pub struct Registry {
+    mappings: Vec<Arc<dyn StdlibMapping>>,
+}
+
+impl std::fmt::Debug for Registry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Registry")
+            .field("mappings", &self.mappings.len())
+            .finish()
+    }
+}
+
+impl Registry {
+    pub fn register(&mut self, mapping: impl StdlibMapping + 'static) {
+        self.mappings.push(Arc::new(mapping));
+    }
+
+    /// Add the mappings of `other` after these
+    pub fn extend(&mut self, other: Registry) {
+        self.mappings.extend(other.mappings);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty()
+    }
+
+    pub fn handles_module(&self, module: &str) -> bool {
+        self.mappings
+            .iter()
+            .any(|mapping| mapping.handles_module(module))
+    }
+
+    pub fn map_call(&self, call: &MappedCall<'_>) -> Option<TokenStream> {
+        self.mappings
+            .iter()
+            .find_map(|mapping| mapping.map_call(call))
+    }
+}
+
+/// A file of declarative mappings, in TOML:
+///
+/// ```toml
+/// module = "lodash"
+///
+/// [dependencies]
+/// itertools = "0.13"
+///
+/// [[mapping]]
+/// function = "chunk"
+/// rust = "$0.chunks($1 as usize).map(|c| c.to_vec()).collect::<Vec<_>>()"
+/// ```
+///
+/// or the same structure in JSON. In `rust`, `$0`, `$1`, ... are the
+/// arguments and `$args` all of them.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MappingFile {
+    /// The module of every mapping that names none
+    #[serde(default)]
+    pub module: Option<String>,
+    /// Crates the generated code needs once a mapping of the file is used
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, Dependency>,
+    #[serde(default, rename = "mapping")]
+    pub mappings: Vec<Mapping>,
+}
+
+/// A `Cargo.toml` dependency: a version, or a version with features
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Dependency {
+    Version(String),
+    Detailed {
+        version: String,
+        #[serde(default)]
+        features: Vec<String>,
+    },
+}
+
+impl Dependency {
+    /// The right-hand side of the `Cargo.toml` entry
+    fn spec(&self) -> String {
+        match self {
+            Dependency::Version(version) => format!("{:?}", version),
+            Dependency::Detailed { version, features } if features.is_empty() => {
+                format!("{:?}", version)
+            }
+            Dependency::Detailed { version, features } => format!(
+                "{{ version = {:?}, features = [{}] }}",
+                version,
+                features
+                    .iter()
+                    .map(|feature| format!("{:?}", feature))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+/// One call translated by a template
+#[derive(Debug, Clone, Deserialize)]
+pub struct Mapping {
+    /// Overrides the module of the file; a global when neither is set
+    #[serde(default)]
+    pub module: Option<String>,
+    pub function: String,
+    pub rust: String,
+}
+
+impl MappingFile {
+    pub fn from_toml(source: &str) -> Result<Self, String> {
+        toml::from_str(source).map_err(|err| err.to_string())
+    }
+
+    pub fn from_json(source: &str) -> Result<Self, String> {
+        serde_json::from_str(source).map_err(|err| err.to_string())
+    }
+
+    fn module_of<'a>(&'a self, mapping: &'a Mapping) -> Option<&'a str> {
+        mapping.module.as_deref().or(self.module.as_deref())
+    }
+}
+
+impl StdlibMapping for MappingFile {
+    fn handles_module(&self, module: &str) -> bool {
+        self.mappings
+            .iter()
+            .any(|mapping| self.module_of(mapping) == Some(module))
+    }
+
+    fn map_call(&self, call: &MappedCall<'_>) -> Option<TokenStream> {
+        let mapping = self.mappings.iter().find(|mapping| {
+            self.module_of(mapping) == call.module && mapping.function == call.function
+        })?;
+        let code = expand(&mapping.rust, call)?;
+        for (name, dependency) in &self.dependencies {
+            dependencies::require(name, &dependency.spec());
+        }
+        Some(code)
+    }
+}
+
+/// `expr`, parenthesized unless it is a single term, so `$0.len()` does not
+/// bind to the end of `a + b`
+pub fn operand(expr: TokenStream) -> TokenStream {
+    match syn::parse2::<syn::Expr>(expr.clone()) {
+        Ok(
+            syn::Expr::Path(_)
+            | syn::Expr::Lit(_)
+            | syn::Expr::Call(_)
+            | syn::Expr::MethodCall(_)
+            | syn::Expr::Field(_)
+            | syn::Expr::Index(_)
+            | syn::Expr::Macro(_)
+            | syn::Expr::Paren(_),
+        ) => expr,
+        _ => quote! { (#expr) },
+    }
+}
+
+/// Substitutes the arguments into a template
+fn expand(template: &str, call: &MappedCall<'_>) -> Option<TokenStream> {
+    let args: Vec<String> = call
+        .args
+        .iter()
+        .map(|arg| operand(convert_expr(&arg.expr)).to_string())
+        .collect();
+
+    let mut code = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('$') {
+        code.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+        if let Some(after) = rest.strip_prefix("args") {
+            code.push_str(&args.join(", "));
+            rest = after;
+            continue;
+        }
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits == 0 {
+            code.push('$');
+            continue;
+        }
+        let index: usize = rest[..digits].parse().ok()?;
+        match args.get(index) {
+            Some(arg) => code.push_str(arg),
+            None => {
+                diagnostics::warn(format!(
+                    "the mapping of `{}` uses argument ${}, but the call passes {}",
+                    call.function,
+                    index,
+                    args.len()
+                ));
+                return None;
+            }
+        }
+        rest = &rest[digits..];
+    }
+    code.push_str(rest);
+
+    match code.parse() {
+        Ok(tokens) => Some(tokens),
+        Err(err) => {
+            diagnostics::warn(format!(
+                "the mapping of `{}` is not valid Rust: {}",
+                call.function, err
+            ));
+            None
+        }
+    }
+}
+
+/// Hand a call to the mappings of the current options: `chunk(...)` and
+/// `_.chunk(...)` imported from a mapped module, or a global such as
+/// `Acme.format(...)`
+pub fn try_handle_call(callee: &Callee, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    let registry = crate::options().mappings;
+    let (module, function) = match &**callee.as_expr()? {
+        Expr::Ident(ident) => match scope::lookup_mapped_import(&ident.sym) {
+            Some(import) => (
+                Some(import.module),
+                import.member.unwrap_or_else(|| "default".to_string()),
+            ),
+            None if scope::lookup(&ident.sym).is_none() => (None, ident.sym.to_string()),
+            None => return None,
+        },
+        Expr::Member(member) => {
+            let prop = member.prop.as_ident()?.sym.to_string();
+            let obj = member.obj.as_ident()?;
+            match scope::lookup_mapped_import(&obj.sym) {
+                Some(import) if import.member.is_none() => (Some(import.module), prop),
+                Some(_) => return None,
+                None if scope::lookup(&obj.sym).is_none() => {
+                    (None, format!("{}.{}", obj.sym, prop))
+                }
+                None => return None,
+            }
+        }
+        _ => return None,
+    };
+    let call = MappedCall {
+        module: module.as_deref(),
+        function: &function,
+        args,
+    };
+    registry
+        .map_call(&call)
+        .or_else(|| BUILTIN.iter().find_map(|mapping| mapping.map_call(&call)))
+}
+
+/// Whether imports of `module` are translated by a mapping
+pub fn is_mapped_module(module: &str) -> bool {
+    crate::options().mappings.handles_module(module)
+        || BUILTIN.iter().any(|mapping| mapping.handles_module(module))
+}
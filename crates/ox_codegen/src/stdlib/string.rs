@@ -2,7 +2,107 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use swc_ecma_ast::*;
 
-use super::super::convert::func::{convert_expr, convert_expr_or_spread};
+use super::super::convert::func::{convert_expr, convert_expr_or_spread, convert_str_arg};
+use super::super::convert::scope;
+use crate::diagnostics;
+
+/// Whether `expr` is known to evaluate to a `String` in the generated code
+pub fn is_string_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(Lit::Str(_)) | Expr::Tpl(_) => true,
+        Expr::Paren(paren) => is_string_expr(&paren.expr),
+        Expr::Ident(ident) => scope::lookup(&ident.sym).is_some_and(|t| {
+            matches!(
+                t,
+                TsType::TsKeywordType(TsKeywordType {
+                    kind: TsKeywordTypeKind::TsStringKeyword,
+                    ..
+                })
+            )
+        }),
+        Expr::Bin(bin) if bin.op == BinaryOp::Add => {
+            is_string_expr(&bin.left) || is_string_expr(&bin.right)
+        }
+        // `String(value)`
+        Expr::Call(call)
+            if call
+                .callee
+                .as_expr()
+                .and_then(|callee| callee.as_ident())
+                .is_some_and(|callee| callee.sym == "String") =>
+        {
+            true
+        }
+        Expr::Call(call) => {
+            if let Callee::Expr(callee) = &call.callee {
+                if let Expr::Member(member) = &**callee {
+                    if let Some(prop) = member.prop.as_ident() {
+                        return matches!(
+                            prop.sym.as_ref(),
+                            "toUpperCase"
+                                | "toLowerCase"
+                                | "replace"
+                                | "replaceAll"
+                                | "substring"
+                                | "charAt"
+                                | "padStart"
+                                | "padEnd"
+                                | "repeat"
+                                | "toString"
+                                | "toFixed"
+                        ) || (prop.sym == "slice" && is_string_expr(&member.obj));
+                    }
+                }
+            }
+            false
+        }
+        _ => false,
+    }
+}
+
+/// The JS string form of `expr`, as `String(value)` or `"text" + value` give
+pub fn js_string(expr: &Expr) -> TokenStream {
+    let value = convert_expr(expr);
+    let to_js_string = crate::runtime::path("ToJsString");
+    quote! { #to_js_string::to_js_string(&(#value)) }
+}
+
+/// The global `String(value)`
+pub fn handle_global(args: &[ExprOrSpread]) -> Option<TokenStream> {
+    Some(match args.first() {
+        Some(arg) => js_string(&arg.expr),
+        None => quote! { String::new() },
+    })
+}
+
+/// `.length`: UTF-16 code units for strings, element count otherwise
+pub fn convert_length(obj: &Expr) -> TokenStream {
+    let obj_tokens = convert_expr(obj);
+    if is_string_expr(obj) {
+        return quote! { (#obj_tokens.encode_utf16().count() as f64) };
+    }
+    if !super::array::is_array_expr(obj) && !super::buffer::is_buffer_expr(obj) {
+        diagnostics::warn(
+            "`.length` on a value of unknown type is translated to `.len()`, which counts \
+             bytes rather than UTF-16 code units if the value is a string; annotate it as \
+             `string` to get JS semantics",
+        );
+    }
+    quote! { (#obj_tokens.len() as f64) }
+}
+
+/// Binds `units` to the receiver's UTF-16 code units, as JS indexes strings
+fn utf16_units(obj_tokens: &TokenStream) -> TokenStream {
+    quote! {
+        let units: Vec<u16> = #obj_tokens.encode_utf16().collect();
+    }
+}
+
+fn arg_or(args: &[ExprOrSpread], index: usize, default: TokenStream) -> TokenStream {
+    args.get(index)
+        .map(convert_expr_or_spread)
+        .unwrap_or(default)
+}
 
 /// Handle string method calls
 pub fn handle_method(obj: &Expr, method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
@@ -11,27 +111,48 @@ pub fn handle_method(obj: &Expr, method: &str, args: &[ExprOrSpread]) -> Option<
     match method {
         "includes" => {
             if args.len() == 1 {
-                let arg = convert_expr_or_spread(&args[0]);
-                Some(quote! { #obj_tokens.contains(&#arg) })
+                let arg = convert_str_arg(&args[0].expr);
+                Some(quote! { #obj_tokens.contains(#arg) })
             } else {
                 None
             }
         }
         "replace" => {
             if args.len() == 2 {
-                let pattern = convert_expr_or_spread(&args[0]);
-                let replacement = convert_expr_or_spread(&args[1]);
-                Some(quote! { #obj_tokens.replace(&#pattern, &#replacement) })
+                let pattern = convert_str_arg(&args[0].expr);
+                let replacement = convert_str_arg(&args[1].expr);
+                // JS replaces only the first occurrence of a string pattern
+                Some(quote! { #obj_tokens.replacen(#pattern, #replacement, 1) })
             } else {
                 None
             }
         }
         "split" => {
-            if args.len() == 1 {
-                let delimiter = convert_expr_or_spread(&args[0]);
-                Some(quote! { #obj_tokens.split(&#delimiter).collect::<Vec<_>>() })
-            } else {
-                None
+            let delimiter = match args.first().map(|arg| &*arg.expr) {
+                // `"abc".split("")` yields the individual characters
+                Some(Expr::Lit(Lit::Str(s))) if s.value.is_empty() => {
+                    let split = quote! { #obj_tokens.split("").filter(|s| !s.is_empty()).map(String::from) };
+                    return Some(match args.get(1) {
+                        Some(limit) => {
+                            let limit = convert_expr_or_spread(limit);
+                            quote! { #split.take((#limit) as usize).collect::<Vec<_>>() }
+                        }
+                        None => quote! { #split.collect::<Vec<_>>() },
+                    });
+                }
+                Some(delimiter) => convert_str_arg(delimiter),
+                None => return Some(quote! { vec![#obj_tokens.to_string()] }),
+            };
+            match args.get(1) {
+                Some(limit) => {
+                    let limit = convert_expr_or_spread(limit);
+                    Some(
+                        quote! { #obj_tokens.split(#delimiter).take((#limit) as usize).map(String::from).collect::<Vec<_>>() },
+                    )
+                }
+                None => Some(
+                    quote! { #obj_tokens.split(#delimiter).map(String::from).collect::<Vec<_>>() },
+                ),
             }
         }
         "toUpperCase" => {
@@ -48,9 +169,10 @@ pub fn handle_method(obj: &Expr, method: &str, args: &[ExprOrSpread]) -> Option<
                 None
             }
         }
+        // `trim` and friends return a `&str` borrowing the receiver; copy it out
         "trim" => {
             if args.is_empty() {
-                Some(quote! { #obj_tokens.trim() })
+                Some(quote! { #obj_tokens.trim().to_string() })
             } else {
                 None
             }
@@ -62,6 +184,186 @@ pub fn handle_method(obj: &Expr, method: &str, args: &[ExprOrSpread]) -> Option<
                 None
             }
         }
+        "trimStart" => Some(quote! { #obj_tokens.trim_start().to_string() }),
+        "trimEnd" => Some(quote! { #obj_tokens.trim_end().to_string() }),
+        "replaceAll" => {
+            if args.len() == 2 {
+                let pattern = convert_str_arg(&args[0].expr);
+                let replacement = convert_str_arg(&args[1].expr);
+                Some(quote! { #obj_tokens.replace(#pattern, #replacement) })
+            } else {
+                None
+            }
+        }
+        "repeat" => {
+            let count = convert_expr_or_spread(args.first()?);
+            Some(quote! { #obj_tokens.repeat((#count) as usize) })
+        }
+        "concat" => {
+            let parts: Vec<_> = args.iter().map(convert_expr_or_spread).collect();
+            let format = "{}".repeat(parts.len() + 1);
+            Some(quote! { format!(#format, #obj_tokens, #(#parts),*) })
+        }
+        "localeCompare" => {
+            diagnostics::warn(
+                "`localeCompare` is translated to a code point comparison, not locale-aware collation",
+            );
+            let other = convert_str_arg(&args.first()?.expr);
+            Some(quote! { (#obj_tokens.as_str().cmp(#other) as i32 as f64) })
+        }
+        "startsWith" | "endsWith" => {
+            let search = convert_str_arg(&args.first()?.expr);
+            match (method, args.get(1)) {
+                ("startsWith", None) => Some(quote! { #obj_tokens.starts_with(#search) }),
+                (_, None) => Some(quote! { #obj_tokens.ends_with(#search) }),
+                (_, Some(position)) => {
+                    let units = utf16_units(&obj_tokens);
+                    let position = convert_expr_or_spread(position);
+                    let check = if method == "startsWith" {
+                        quote! { units[position.min(units.len())..].starts_with(&search) }
+                    } else {
+                        quote! { units[..position.min(units.len())].ends_with(&search) }
+                    };
+                    Some(quote! {
+                        {
+                            #units
+                            let search: &str = #search;
+                            let search: Vec<u16> = search.encode_utf16().collect();
+                            let position = (#position) as usize;
+                            #check
+                        }
+                    })
+                }
+            }
+        }
+        "indexOf" | "lastIndexOf" => {
+            let units = utf16_units(&obj_tokens);
+            let search = convert_str_arg(&args.first()?.expr);
+            let found = if method == "indexOf" {
+                let from = arg_or(args, 1, quote! { 0.0 });
+                quote! {
+                    let from = ((#from) as usize).min(units.len());
+                    if search.is_empty() {
+                        from as f64
+                    } else {
+                        units[from..]
+                            .windows(search.len())
+                            .position(|w| w == search.as_slice())
+                            .map(|i| (i + from) as f64)
+                            .unwrap_or(-1.0)
+                    }
+                }
+            } else {
+                let from = arg_or(args, 1, quote! { f64::INFINITY });
+                quote! {
+                    let end = (((#from) as usize).saturating_add(search.len())).min(units.len());
+                    if search.is_empty() {
+                        end as f64
+                    } else {
+                        units[..end]
+                            .windows(search.len())
+                            .rposition(|w| w == search.as_slice())
+                            .map(|i| i as f64)
+                            .unwrap_or(-1.0)
+                    }
+                }
+            };
+            Some(quote! {
+                {
+                    #units
+                    let search: &str = #search;
+                    let search: Vec<u16> = search.encode_utf16().collect();
+                    #found
+                }
+            })
+        }
+        "slice" | "substring" => {
+            let units = utf16_units(&obj_tokens);
+            let start = arg_or(args, 0, quote! { 0.0 });
+            let end = arg_or(args, 1, quote! { len });
+            let bounds = if method == "slice" {
+                // Negative indices count from the end
+                quote! {
+                    let relative = |i: f64| (if i < 0.0 { (len + i).max(0.0) } else { i.min(len) }) as usize;
+                    let start = relative(#start);
+                    let end = relative(#end).max(start);
+                }
+            } else {
+                // Negative indices clamp to 0 and the bounds are swapped if reversed
+                quote! {
+                    let clamp = |i: f64| (if i.is_nan() { 0.0 } else { i.clamp(0.0, len) }) as usize;
+                    let (start, end) = (clamp(#start), clamp(#end));
+                    let (start, end) = (start.min(end), start.max(end));
+                }
+            };
+            Some(quote! {
+                {
+                    #units
+                    let len = units.len() as f64;
+                    #bounds
+                    String::from_utf16_lossy(&units[start..end])
+                }
+            })
+        }
+        "charAt" | "at" => {
+            let index = arg_or(args, 0, quote! { 0.0 });
+            let lookup = if method == "at" {
+                quote! {
+                    let index: f64 = #index;
+                    let index = if index < 0.0 { units.len() as f64 + index } else { index };
+                    (index >= 0.0)
+                        .then(|| units.get(index as usize))
+                        .flatten()
+                        .map(|unit| String::from_utf16_lossy(&[*unit]))
+                }
+            } else {
+                quote! {
+                    units
+                        .get((#index) as usize)
+                        .map(|unit| String::from_utf16_lossy(&[*unit]))
+                        .unwrap_or_default()
+                }
+            };
+            let units = utf16_units(&obj_tokens);
+            Some(quote! {
+                {
+                    #units
+                    #lookup
+                }
+            })
+        }
+        "charCodeAt" => {
+            let index = arg_or(args, 0, quote! { 0.0 });
+            Some(quote! {
+                #obj_tokens.encode_utf16().nth((#index) as usize).map(|unit| unit as f64).unwrap_or(f64::NAN)
+            })
+        }
+        "padStart" | "padEnd" => {
+            let target = convert_expr_or_spread(args.first()?);
+            let fill = args
+                .get(1)
+                .map(|arg| convert_str_arg(&arg.expr))
+                .unwrap_or_else(|| quote! { " " });
+            let padded = if method == "padStart" {
+                quote! { String::from_utf16_lossy(&pad) + s }
+            } else {
+                quote! { s.to_string() + &String::from_utf16_lossy(&pad) }
+            };
+            Some(quote! {
+                {
+                    let s: &str = &#obj_tokens;
+                    let fill: &str = #fill;
+                    let len = s.encode_utf16().count();
+                    let target = (#target) as usize;
+                    if target <= len || fill.is_empty() {
+                        s.to_string()
+                    } else {
+                        let pad: Vec<u16> = fill.encode_utf16().cycle().take(target - len).collect();
+                        #padded
+                    }
+                }
+            })
+        }
         _ => None,
     }
 }
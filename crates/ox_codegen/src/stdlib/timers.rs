@@ -0,0 +1,82 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use swc_ecma_ast::*;
+
+use super::super::convert::func::{convert_expr, convert_expr_or_spread};
+use super::super::convert::scope;
+use crate::diagnostics;
+
+/// A millisecond delay argument as a `std::time::Duration`
+pub fn duration(ms: Option<&ExprOrSpread>) -> TokenStream {
+    match ms {
+        Some(ms) => {
+            let ms = convert_expr_or_spread(ms);
+            quote! { std::time::Duration::from_millis((#ms) as u64) }
+        }
+        None => quote! { std::time::Duration::ZERO },
+    }
+}
+
+/// Runs a timer callback inside the spawned task
+fn invoke(callback: &Expr) -> TokenStream {
+    let is_async = match callback {
+        Expr::Arrow(arrow) => arrow.is_async,
+        Expr::Ident(ident) => scope::is_async_function(&ident.sym),
+        _ => false,
+    };
+    let callback = convert_expr(callback);
+    if is_async {
+        // Like an unhandled rejection, a failing callback does not stop the timer
+        quote! { let _ = (#callback)().await; }
+    } else {
+        quote! { (#callback)(); }
+    }
+}
+
+/// Handle the global `setTimeout`, `setInterval`, `clearTimeout` and
+/// `clearInterval` functions. Timers are tokio tasks, and their ids are the
+/// tasks' `JoinHandle`s.
+pub fn handle_global(name: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    if matches!(name, "setTimeout" | "setInterval") && args.len() > 2 {
+        diagnostics::warn(format!(
+            "extra `{}` arguments are not passed to the callback; capture them in a closure",
+            name
+        ));
+    }
+    match name {
+        "setTimeout" => {
+            let callback = invoke(&args.first()?.expr);
+            let duration = duration(args.get(1));
+            Some(quote! {
+                tokio::spawn(async move {
+                    tokio::time::sleep(#duration).await;
+                    #callback
+                })
+            })
+        }
+        "setInterval" => {
+            let callback = invoke(&args.first()?.expr);
+            let duration = duration(args.get(1));
+            // The first tick of a tokio interval completes immediately, while
+            // JS waits one period; missed ticks are delayed rather than burst
+            Some(quote! {
+                tokio::spawn(async move {
+                    let mut interval = tokio::time::interval(
+                        (#duration).max(std::time::Duration::from_millis(1)),
+                    );
+                    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                    interval.tick().await;
+                    loop {
+                        interval.tick().await;
+                        #callback
+                    }
+                })
+            })
+        }
+        "clearTimeout" | "clearInterval" => {
+            let handle = convert_expr_or_spread(args.first()?);
+            Some(quote! { #handle.abort() })
+        }
+        _ => None,
+    }
+}
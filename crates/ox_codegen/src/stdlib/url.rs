@@ -0,0 +1,410 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use swc_ecma_ast::*;
+
+use super::super::convert::func::{convert_expr, convert_place_expr, convert_str_arg};
+use super::super::convert::scope;
+use crate::{dependencies, diagnostics};
+
+// `URL` is `url::Url`, whose query string backs `url.searchParams`. A
+// standalone `URLSearchParams` is a `Vec<(String, String)>` of decoded pairs,
+// serialized with `url::form_urlencoded`.
+
+fn require_url() {
+    dependencies::require("url", "\"2\"");
+}
+
+/// The Rust type of the TS `URL` (or else `URLSearchParams`) type
+pub fn map_type(name: &str) -> TokenStream {
+    if name == "URL" {
+        require_url();
+        quote! { url::Url }
+    } else {
+        quote! { Vec<(String, String)> }
+    }
+}
+
+fn is_type_named(expr: &Expr, name: &str) -> bool {
+    match expr {
+        Expr::Paren(paren) => is_type_named(&paren.expr, name),
+        Expr::New(new_expr) => new_expr
+            .callee
+            .as_ident()
+            .is_some_and(|callee| callee.sym == name && scope::lookup_type(name).is_none()),
+        Expr::Ident(ident) => scope::lookup_type(&ident.sym).is_some_and(|ts_type| {
+            matches!(
+                &ts_type,
+                TsType::TsTypeRef(type_ref)
+                    if type_ref.type_name.as_ident().is_some_and(|type_name| type_name.sym == name)
+            )
+        }),
+        _ => false,
+    }
+}
+
+/// Whether `expr` is known to evaluate to a `url::Url`
+pub fn is_url_expr(expr: &Expr) -> bool {
+    is_type_named(expr, "URL")
+}
+
+/// Whether `expr` is a standalone `URLSearchParams`
+pub fn is_search_params_expr(expr: &Expr) -> bool {
+    is_type_named(expr, "URLSearchParams")
+}
+
+/// The `URL` whose `searchParams` `expr` reads
+fn search_params_of(expr: &Expr) -> Option<&Expr> {
+    let member = expr.as_member()?;
+    let is_search_params = member
+        .prop
+        .as_ident()
+        .is_some_and(|prop| prop.sym == "searchParams");
+    (is_search_params && is_url_expr(&member.obj)).then_some(&*member.obj)
+}
+
+/// `url::Url::parse`, which throws a `TypeError` in JS
+fn parse(result: TokenStream) -> TokenStream {
+    if scope::is_fallible() {
+        quote! { #result.map_err(|err| crate::AppError::Error(err.to_string()))? }
+    } else {
+        quote! { #result.expect("Invalid URL") }
+    }
+}
+
+/// Handle `new URL(input, base)` and `new URLSearchParams(init)`
+pub fn handle_new(new_expr: &NewExpr) -> Option<TokenStream> {
+    let callee = new_expr.callee.as_ident()?;
+    if scope::lookup_type(&callee.sym).is_some() {
+        return None;
+    }
+    let args = new_expr.args.as_deref().unwrap_or_default();
+    match callee.sym.as_ref() {
+        "URL" => {
+            require_url();
+            let input = convert_str_arg(&args.first()?.expr);
+            Some(match args.get(1) {
+                Some(base) => {
+                    let base = convert_str_arg(&base.expr);
+                    parse(quote! { url::Url::parse(#base).and_then(|base| base.join(#input)) })
+                }
+                None => parse(quote! { url::Url::parse(#input) }),
+            })
+        }
+        "URLSearchParams" => Some(search_params(args.first().map(|init| &*init.expr))),
+        _ => None,
+    }
+}
+
+/// The pairs of a `URLSearchParams` initialiser: a query string, an object
+/// literal, or any other serializable object
+fn search_params(init: Option<&Expr>) -> TokenStream {
+    match init {
+        None => quote! { Vec::<(String, String)>::new() },
+        Some(init) if super::string::is_string_expr(init) => {
+            require_url();
+            let query = convert_str_arg(init);
+            quote! {
+                url::form_urlencoded::parse(#query.trim_start_matches('?').as_bytes())
+                    .into_owned()
+                    .collect::<Vec<(String, String)>>()
+            }
+        }
+        Some(Expr::Object(object)) => {
+            let pairs = object.props.iter().filter_map(|prop| {
+                let Prop::KeyValue(kv) = prop.as_prop()?.as_ref() else {
+                    diagnostics::warn(
+                        "only `key: value` properties are supported in URLSearchParams",
+                    );
+                    return None;
+                };
+                let key = match &kv.key {
+                    PropName::Ident(ident) => ident.sym.to_string(),
+                    PropName::Str(s) => s.value.to_string_lossy().into_owned(),
+                    _ => return None,
+                };
+                let value = text(&kv.value);
+                Some(quote! { (String::from(#key), String::from(#value)) })
+            });
+            quote! { vec![#(#pairs),*] }
+        }
+        Some(init) if is_search_params_expr(init) => {
+            let init = convert_expr(init);
+            quote! { #init.clone() }
+        }
+        Some(init) => {
+            let init = convert_expr(init);
+            quote! {
+                serde_json::to_value(&#init)
+                    .ok()
+                    .and_then(|value| value.as_object().cloned())
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(key, value)| match value {
+                        serde_json::Value::String(value) => (key, value),
+                        value => (key, value.to_string()),
+                    })
+                    .collect::<Vec<(String, String)>>()
+            }
+        }
+    }
+}
+
+/// A search param key argument as a `&str`
+fn key_arg(args: &[ExprOrSpread], index: usize) -> Option<TokenStream> {
+    match &*args.get(index)?.expr {
+        Expr::Lit(Lit::Str(s)) => {
+            let s = s.value.as_str().unwrap_or("");
+            Some(quote! { #s })
+        }
+        expr @ (Expr::Ident(_) | Expr::Member(_) | Expr::Call(_)) => {
+            let expr = convert_expr(expr);
+            Some(quote! { #expr.as_str() })
+        }
+        expr => {
+            let expr = convert_expr(expr);
+            Some(quote! { (#expr).as_str() })
+        }
+    }
+}
+
+/// A value argument converted to a string, as JS does: a literal `&str` or a `String`
+fn text(expr: &Expr) -> TokenStream {
+    match expr {
+        Expr::Lit(Lit::Str(s)) => {
+            let s = s.value.as_str().unwrap_or("");
+            quote! { #s }
+        }
+        Expr::Ident(_) | Expr::Member(_) | Expr::Call(_) => {
+            let expr = convert_expr(expr);
+            quote! { #expr.to_string() }
+        }
+        _ => {
+            let expr = convert_expr(expr);
+            quote! { (#expr).to_string() }
+        }
+    }
+}
+
+fn text_arg(args: &[ExprOrSpread], index: usize) -> Option<TokenStream> {
+    Some(text(&args.get(index)?.expr))
+}
+
+/// Methods of `url.searchParams`, reading and rewriting the query of `url`
+fn url_search_params_method(
+    url: &Expr,
+    method: &str,
+    args: &[ExprOrSpread],
+) -> Option<TokenStream> {
+    let read = convert_expr(url);
+    match method {
+        "get" => {
+            let key = key_arg(args, 0)?;
+            Some(quote! {
+                #read.query_pairs().find(|(name, _)| name == #key).map(|(_, value)| value.into_owned())
+            })
+        }
+        "getAll" => {
+            let key = key_arg(args, 0)?;
+            Some(quote! {
+                #read
+                    .query_pairs()
+                    .filter(|(name, _)| name == #key)
+                    .map(|(_, value)| value.into_owned())
+                    .collect::<Vec<String>>()
+            })
+        }
+        "has" => {
+            let key = key_arg(args, 0)?;
+            Some(quote! { #read.query_pairs().any(|(name, _)| name == #key) })
+        }
+        "toString" => Some(quote! { #read.query().unwrap_or_default().to_string() }),
+        "append" => {
+            let (key, value) = (key_arg(args, 0)?, text_arg(args, 1)?);
+            let url = convert_place_expr(url);
+            Some(quote! { #url.query_pairs_mut().append_pair(#key, &#value) })
+        }
+        "set" => {
+            let (key, value) = (key_arg(args, 0)?, text_arg(args, 1)?);
+            let url = convert_place_expr(url);
+            let set = crate::runtime::path("search_params_set");
+            Some(quote! {
+                {
+                    let mut pairs: Vec<(String, String)> = #url
+                        .query_pairs()
+                        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                        .collect();
+                    #set(&mut pairs, #key, String::from(#value));
+                    #url.query_pairs_mut().clear().extend_pairs(pairs);
+                }
+            })
+        }
+        "delete" => {
+            let key = key_arg(args, 0)?;
+            let url = convert_place_expr(url);
+            Some(quote! {
+                {
+                    let kept: Vec<(String, String)> = #url
+                        .query_pairs()
+                        .filter(|(name, _)| name != #key)
+                        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                        .collect();
+                    #url.query_pairs_mut().clear().extend_pairs(kept);
+                    if #url.query() == Some("") {
+                        #url.set_query(None);
+                    }
+                }
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Methods of a standalone `URLSearchParams`
+fn search_params_method(obj: &Expr, method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    let params = convert_expr(obj);
+    match method {
+        "get" => {
+            let key = key_arg(args, 0)?;
+            Some(quote! {
+                #params.iter().find(|(name, _)| name == #key).map(|(_, value)| value.clone())
+            })
+        }
+        "getAll" => {
+            let key = key_arg(args, 0)?;
+            Some(quote! {
+                #params
+                    .iter()
+                    .filter(|(name, _)| name == #key)
+                    .map(|(_, value)| value.clone())
+                    .collect::<Vec<String>>()
+            })
+        }
+        "has" => {
+            let key = key_arg(args, 0)?;
+            Some(quote! { #params.iter().any(|(name, _)| name == #key) })
+        }
+        "toString" => {
+            require_url();
+            Some(quote! {
+                url::form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(&#params)
+                    .finish()
+            })
+        }
+        "append" => {
+            let (key, value) = (key_arg(args, 0)?, text_arg(args, 1)?);
+            let params = convert_place_expr(obj);
+            Some(quote! { #params.push((#key.to_string(), String::from(#value))) })
+        }
+        "set" => {
+            let (key, value) = (key_arg(args, 0)?, text_arg(args, 1)?);
+            let params = convert_place_expr(obj);
+            let set = crate::runtime::path("search_params_set");
+            Some(quote! {
+                #set(&mut #params, #key, String::from(#value))
+            })
+        }
+        "delete" => {
+            let key = key_arg(args, 0)?;
+            let params = convert_place_expr(obj);
+            Some(quote! { #params.retain(|(name, _)| name != #key) })
+        }
+        "sort" => {
+            let params = convert_place_expr(obj);
+            Some(quote! { #params.sort_by(|(a, _), (b, _)| a.cmp(b)) })
+        }
+        _ => None,
+    }
+}
+
+/// Methods of URLs and search params
+pub fn handle_method(obj: &Expr, method: &str, args: &[ExprOrSpread]) -> Option<TokenStream> {
+    if let Some(url) = search_params_of(obj) {
+        return url_search_params_method(url, method, args);
+    }
+    if is_search_params_expr(obj) {
+        return search_params_method(obj, method, args);
+    }
+    if is_url_expr(obj) && matches!(method, "toString" | "toJSON") {
+        let url = convert_expr(obj);
+        return Some(quote! { #url.to_string() });
+    }
+    None
+}
+
+/// `url.pathname`, `url.searchParams` and the other `URL` getters
+pub fn property(member: &MemberExpr) -> Option<TokenStream> {
+    if !is_url_expr(&member.obj) {
+        return None;
+    }
+    let url = convert_expr(&member.obj);
+    let value = match member.prop.as_ident()?.sym.as_ref() {
+        "href" => quote! { #url.to_string() },
+        "origin" => quote! { #url.origin().ascii_serialization() },
+        "protocol" => quote! { format!("{}:", #url.scheme()) },
+        "host" => quote! { #url[url::Position::BeforeHost..url::Position::AfterPort].to_string() },
+        "hostname" => quote! { #url.host_str().unwrap_or_default().to_string() },
+        "port" => quote! { #url.port().map(|port| port.to_string()).unwrap_or_default() },
+        "pathname" => quote! { #url.path().to_string() },
+        "search" => quote! { #url.query().map(|query| format!("?{}", query)).unwrap_or_default() },
+        "hash" => {
+            quote! { #url.fragment().map(|fragment| format!("#{}", fragment)).unwrap_or_default() }
+        }
+        "username" => quote! { #url.username().to_string() },
+        "password" => quote! { #url.password().unwrap_or_default().to_string() },
+        // A copy: changes go through `url.searchParams.append(...)` and friends
+        "searchParams" => {
+            quote! { #url.query_pairs().into_owned().collect::<Vec<(String, String)>>() }
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Whether `expr` reads a string property of a URL, such as `url.pathname`
+pub fn is_url_property(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(paren) => is_url_property(&paren.expr),
+        Expr::Member(member) => {
+            is_url_expr(&member.obj)
+                && member
+                    .prop
+                    .as_ident()
+                    .is_some_and(|prop| prop.sym != "searchParams")
+        }
+        _ => false,
+    }
+}
+
+/// `url.pathname = "/users"` and the other `URL` setters
+pub fn try_handle_assign(assign: &AssignExpr) -> Option<TokenStream> {
+    let member = assign.left.as_simple()?.as_member()?;
+    if assign.op != AssignOp::Assign || !is_url_expr(&member.obj) {
+        return None;
+    }
+    let url = convert_place_expr(&member.obj);
+    let value = text(&assign.right);
+    let assigned = match member.prop.as_ident()?.sym.as_ref() {
+        "pathname" => quote! { #url.set_path(&#value) },
+        "search" => quote! {
+            #url.set_query(Some(#value.trim_start_matches('?')).filter(|query| !query.is_empty()))
+        },
+        "hash" => quote! {
+            #url.set_fragment(Some(#value.trim_start_matches('#')).filter(|fragment| !fragment.is_empty()))
+        },
+        "hostname" | "host" => quote! { let _ = #url.set_host(Some(&#value)) },
+        "port" => quote! { let _ = #url.set_port(#value.parse().ok()) },
+        "protocol" => quote! { let _ = #url.set_scheme(#value.trim_end_matches(':')) },
+        "username" => quote! { let _ = #url.set_username(&#value) },
+        "password" => quote! { let _ = #url.set_password(Some(&#value)) },
+        "href" => {
+            let parsed = parse(quote! { url::Url::parse(&#value) });
+            quote! { #url = #parsed }
+        }
+        prop => {
+            diagnostics::warn(format!("setting `URL.{}` is not supported", prop));
+            return None;
+        }
+    };
+    Some(assigned)
+}
@@ -0,0 +1,31 @@
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+
+// Modules emitted into the generated crate, next to `error.rs`, when a
+// translation needs more than an expression (e.g. `crate::axios::send`)
+
+thread_local! {
+    static REQUIRED: RefCell<BTreeSet<&'static str>> = const { RefCell::new(BTreeSet::new()) };
+}
+
+/// Record that the generated code uses `crate::<name>`.
+pub fn require(name: &'static str) {
+    REQUIRED.with(|r| {
+        r.borrow_mut().insert(name);
+    });
+}
+
+/// Take all support modules required since the last call.
+pub fn take() -> BTreeSet<&'static str> {
+    REQUIRED.with(|r| std::mem::take(&mut *r.borrow_mut()))
+}
+
+/// The source of a support module.
+pub fn source(name: &str) -> Option<&'static str> {
+    match name {
+        "axios" => Some(include_str!("../support/axios.rs")),
+        "events" => Some(include_str!("../support/events.rs")),
+        "lodash" => Some(include_str!("../support/lodash.rs")),
+        _ => None,
+    }
+}
@@ -0,0 +1,167 @@
+//! Runtime support for code translated from axios
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// An axios instance: a shared client with its config defaults
+#[derive(Debug, Clone)]
+pub struct Instance {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl Instance {
+    /// `axios.create({ baseURL, timeout, headers })`
+    pub fn new(
+        base_url: impl Into<String>,
+        timeout: Option<Duration>,
+        headers: reqwest::header::HeaderMap,
+    ) -> Self {
+        let mut builder = reqwest::Client::builder().default_headers(headers);
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+        Self {
+            client: builder.build().unwrap_or_default(),
+            base_url: base_url.into(),
+        }
+    }
+
+    /// Starts a request; relative URLs are resolved against `baseURL`
+    pub fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+        self.request_at(&self.base_url, method, url)
+    }
+
+    /// Starts a request whose config sets its own `baseURL`
+    pub fn request_at(
+        &self,
+        base_url: &str,
+        method: reqwest::Method,
+        url: &str,
+    ) -> reqwest::RequestBuilder {
+        let url = if base_url.is_empty() || url.contains("://") {
+            url.to_string()
+        } else {
+            format!(
+                "{}/{}",
+                base_url.trim_end_matches('/'),
+                url.trim_start_matches('/')
+            )
+        };
+        self.client.request(method, url)
+    }
+}
+
+/// The default instance used by `axios.get(...)` and friends
+pub fn default() -> &'static Instance {
+    static DEFAULT: LazyLock<Instance> =
+        LazyLock::new(|| Instance::new("", None, reqwest::header::HeaderMap::new()));
+    &DEFAULT
+}
+
+/// `AxiosResponse<T>`
+#[derive(Debug, Clone)]
+pub struct Response<T> {
+    pub data: T,
+    pub status: f64,
+    pub headers: HashMap<String, String>,
+}
+
+/// `error.response`: what the server answered when a request failed
+#[derive(Debug, Clone)]
+pub struct ErrorResponse {
+    pub data: serde_json::Value,
+    pub status: f64,
+    pub headers: HashMap<String, String>,
+}
+
+/// `AxiosError`. `response` is `None` when no response was received.
+#[derive(Debug)]
+pub struct Error {
+    pub message: String,
+    pub response: Option<ErrorResponse>,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Self {
+            message: err.to_string(),
+            response: None,
+        }
+    }
+}
+
+impl From<Error> for crate::AppError {
+    fn from(err: Error) -> Self {
+        crate::AppError::Other(Box::new(err))
+    }
+}
+
+/// `axios.isAxiosError(err)` on a caught error
+pub fn is_error(err: &crate::AppError) -> bool {
+    matches!(err, crate::AppError::Other(other) if other.is::<Error>())
+}
+
+/// `err.response` of a caught error: `None` unless it is an axios error
+/// carrying the server's answer
+pub fn response(err: &crate::AppError) -> Option<ErrorResponse> {
+    match err {
+        crate::AppError::Other(other) => other.downcast_ref::<Error>()?.response.clone(),
+        _ => None,
+    }
+}
+
+fn headers_of(response: &reqwest::Response) -> HashMap<String, String> {
+    response
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+/// Sends a request and reads its JSON body into `T`. As in axios, a status
+/// outside 2xx is an error carrying the response.
+pub async fn send<T: serde::de::DeserializeOwned>(
+    request: reqwest::RequestBuilder,
+) -> Result<Response<T>, Error> {
+    let response = request.send().await?;
+    let status = response.status();
+    let headers = headers_of(&response);
+    let body = response.text().await?;
+
+    if !status.is_success() {
+        return Err(Error {
+            message: format!("Request failed with status code {}", status.as_u16()),
+            response: Some(ErrorResponse {
+                data: serde_json::from_str(&body).unwrap_or(serde_json::Value::String(body)),
+                status: f64::from(status.as_u16()),
+                headers,
+            }),
+        });
+    }
+
+    // An empty body (e.g. 204) reads as `null`, and a non-JSON one as a string
+    let value = if body.trim().is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::from_str(&body).unwrap_or(serde_json::Value::String(body))
+    };
+    let data = serde_json::from_value(value).map_err(|err| Error {
+        message: err.to_string(),
+        response: None,
+    })?;
+    Ok(Response {
+        data,
+        status: f64::from(status.as_u16()),
+        headers,
+    })
+}
@@ -0,0 +1,156 @@
+//! Runtime support for code translated from Node's `EventEmitter`
+
+use std::sync::{Arc, Mutex};
+
+/// The events of one emitter class, generated as an enum with a variant per
+/// event name
+pub trait Event: Clone + Send + Sync + 'static {
+    /// The JS event name, e.g. `"orderPlaced"`
+    fn name(&self) -> &'static str;
+}
+
+/// A registered listener
+pub type Listener<E> = Box<dyn Fn(&E) + Send + Sync>;
+
+struct Registration<E> {
+    event: &'static str,
+    /// The function name a listener was registered by, so `off` can find it
+    name: Option<&'static str>,
+    once: bool,
+    /// Shared with `emit`, which calls a snapshot of the listeners
+    listener: Arc<dyn Fn(&E) + Send + Sync>,
+}
+
+/// The listeners of an emitter. Clones share their listeners, as every
+/// reference to a JS emitter does.
+pub struct EventBus<E> {
+    listeners: Arc<Mutex<Vec<Registration<E>>>>,
+}
+
+impl<E> Default for EventBus<E> {
+    fn default() -> Self {
+        Self {
+            listeners: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl<E> Clone for EventBus<E> {
+    fn clone(&self) -> Self {
+        Self {
+            listeners: Arc::clone(&self.listeners),
+        }
+    }
+}
+
+impl<E> PartialEq for EventBus<E> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.listeners, &other.listeners)
+    }
+}
+
+impl<E> std::fmt::Debug for EventBus<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventBus")
+            .field("listeners", &self.lock().len())
+            .finish()
+    }
+}
+
+impl<E> EventBus<E> {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Registration<E>>> {
+        self.listeners.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<E: Event> EventBus<E> {
+    fn add(
+        &self,
+        event: &'static str,
+        name: Option<&'static str>,
+        once: bool,
+        listener: Listener<E>,
+    ) -> &Self {
+        self.lock().push(Registration {
+            event,
+            name,
+            once,
+            listener: Arc::from(listener),
+        });
+        self
+    }
+
+    /// `emitter.on(event, listener)`. `name` is the listener's function name,
+    /// if it has one.
+    pub fn on(
+        &self,
+        event: &'static str,
+        name: Option<&'static str>,
+        listener: Listener<E>,
+    ) -> &Self {
+        self.add(event, name, false, listener)
+    }
+
+    /// `emitter.once(event, listener)`: removed before its first call
+    pub fn once(
+        &self,
+        event: &'static str,
+        name: Option<&'static str>,
+        listener: Listener<E>,
+    ) -> &Self {
+        self.add(event, name, true, listener)
+    }
+
+    /// `emitter.off(event, listener)`: removes the last listener of `event`
+    /// registered by that function name
+    pub fn off(&self, event: &str, name: &str) -> &Self {
+        let mut listeners = self.lock();
+        if let Some(index) = listeners
+            .iter()
+            .rposition(|registration| registration.event == event && registration.name == Some(name))
+        {
+            listeners.remove(index);
+        }
+        drop(listeners);
+        self
+    }
+
+    /// `emitter.removeAllListeners(event)`, or of every event for `None`
+    pub fn remove_all_listeners(&self, event: Option<&str>) -> &Self {
+        self.lock()
+            .retain(|registration| event.is_some_and(|event| registration.event != event));
+        self
+    }
+
+    /// `emitter.listenerCount(event)`
+    pub fn listener_count(&self, event: &str) -> usize {
+        self.lock()
+            .iter()
+            .filter(|registration| registration.event == event)
+            .count()
+    }
+
+    /// `emitter.emit(event, ...args)`: calls the listeners of the event in
+    /// registration order, and tells whether there were any. Like Node, it
+    /// calls the listeners registered when it starts: listeners added or
+    /// removed meanwhile only matter to the next `emit`.
+    pub fn emit(&self, event: E) -> bool {
+        let name = event.name();
+        let called: Vec<_> = {
+            let mut listeners = self.lock();
+            let called = listeners
+                .iter()
+                .filter(|registration| registration.event == name)
+                .map(|registration| Arc::clone(&registration.listener))
+                .collect();
+            // `once` listeners are removed before they are called
+            listeners.retain(|registration| !(registration.once && registration.event == name));
+            called
+        };
+
+        for listener in &called {
+            listener(&event);
+        }
+        !called.is_empty()
+    }
+}
@@ -0,0 +1,30 @@
+//! Runtime support for code translated from lodash
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// `_.debounce(f, wait)`: calls `f` with the arguments of the last call once
+/// `wait` has passed without another call. The arguments of a call are one
+/// tuple. Needs a tokio runtime.
+pub fn debounce<A, F>(f: F, wait: Duration) -> impl Fn(A) + Clone + Send + Sync
+where
+    A: Send + 'static,
+    F: Fn(A) + Send + Sync + 'static,
+{
+    let f = Arc::new(f);
+    let pending: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>> = Arc::default();
+    move |args: A| {
+        let f = Arc::clone(&f);
+        let task = tokio::spawn(async move {
+            tokio::time::sleep(wait).await;
+            f(args);
+        });
+        let previous = pending
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .replace(task);
+        if let Some(previous) = previous {
+            previous.abort();
+        }
+    }
+}
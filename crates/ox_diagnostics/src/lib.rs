@@ -44,6 +44,13 @@ pub enum OxidizerError {
         span: SourceSpan,
     },
 
+    #[error("Mapping Error in {path}: {message}")]
+    #[diagnostic(
+        code(oxidizer::mapping_error),
+        help("see `ox_codegen::stdlib::registry::MappingFile` for the format")
+    )]
+    MappingError { path: String, message: String },
+
     #[error("Formatting Error: {0}")]
     #[diagnostic(code(oxidizer::fmt_error))]
     FormattingError(String),
@@ -35,20 +35,94 @@ pub fn pipeline() -> Result<(), OxidizerError> {
     Ok(())
 }
 
+pub use ox_codegen::stdlib::registry::{MappingFile, Registry};
+pub use ox_codegen::Options;
+
 pub fn build(path: FilePath) -> Result<String, OxidizerError> {
+    build_with_options(path, &Options::default())
+}
+
+/// Mapping files translate calls into libraries TypeRust does not know
+const MAPPING_SUFFIXES: &[&str] = &[".typerust.toml", ".typerust.json"];
+
+/// The mappings of the `*.typerust.toml` and `*.typerust.json` files in
+/// `dir`, in file name order
+pub fn load_mappings(dir: &Path) -> Result<Registry, OxidizerError> {
+    let mut registry = Registry::default();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(registry);
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| {
+                        MAPPING_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+                    })
+        })
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let source = fs::read_to_string(&path).map_err(OxidizerError::IoError)?;
+        let file = if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+            MappingFile::from_json(&source)
+        } else {
+            MappingFile::from_toml(&source)
+        };
+        let file = file.map_err(|message| OxidizerError::MappingError {
+            path: path.display().to_string(),
+            message,
+        })?;
+        registry.register(file);
+    }
+    Ok(registry)
+}
+
+/// `options` plus the mappings found in `dir`
+fn with_project_mappings(options: &Options, dir: &Path) -> Result<Options, OxidizerError> {
+    let mut options = options.clone();
+    options.mappings.extend(load_mappings(dir)?);
+    Ok(options)
+}
+
+pub fn build_with_options(path: FilePath, options: &Options) -> Result<String, OxidizerError> {
     let program = ox_parser::parse(path.as_ref())?;
+    let dir = path
+        .as_ref()
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let options = with_project_mappings(options, dir)?;
     // Default to false for single file build
-    let generated_code = ox_codegen::generate(&program, false);
+    let generated_code = ox_codegen::generate_with_options(&program, false, &options);
+    report_diagnostics(path.as_ref(), &generated_code.diagnostics);
     format_code(generated_code.code)
 }
 
 pub fn build_project(input_dir: PathBuf, output_dir: PathBuf) -> Result<(), OxidizerError> {
+    build_project_with_options(input_dir, output_dir, &Options::default())
+}
+
+pub fn build_project_with_options(
+    input_dir: PathBuf,
+    output_dir: PathBuf,
+    options: &Options,
+) -> Result<(), OxidizerError> {
+    let options = &with_project_mappings(options, &input_dir)?;
     let mut controllers: Vec<String> = Vec::new(); // Just names of controllers
     let mut class_module_map: std::collections::HashMap<String, String> =
         std::collections::HashMap::new();
     let mut generic_classes: std::collections::HashSet<String> = std::collections::HashSet::new();
     let mut programs = Vec::new();
     let mut file_paths = Vec::new();
+    let mut extra_dependencies: std::collections::BTreeMap<String, String> =
+        std::collections::BTreeMap::new();
+    let mut support_modules: std::collections::BTreeSet<&'static str> =
+        std::collections::BTreeSet::new();
 
     // 1. Walk, Parse, and Collect Info
     for entry in WalkDir::new(&input_dir) {
@@ -120,6 +194,18 @@ pub fn build_project(input_dir: PathBuf, output_dir: PathBuf) -> Result<(), Oxid
         .get_initialization_order()
         .map_err(OxidizerError::FormattingError)?; // Using FormattingError as generic error for now
 
+    // `Error` subclasses anywhere in the project become `AppError` variants
+    let error_classes = ox_codegen::convert::error::collect_error_classes(&programs);
+    for class in &error_classes {
+        class_module_map.remove(&class.name);
+    }
+    // Classes extending `EventEmitter` can be extended or held in other files
+    let emitter_classes = ox_codegen::convert::events::collect_emitter_classes(&programs);
+    // Calls to an imported function pad its optional and rest arguments
+    let functions = ox_codegen::convert::func::collect_exported_functions(&programs);
+    // An imported `let` is read and written through its lock
+    let statics = ox_codegen::convert::module::collect_exported_statics(&programs);
+
     // 3. Transpile
     for (i, program) in programs.iter().enumerate() {
         let path = &file_paths[i];
@@ -134,7 +220,18 @@ pub fn build_project(input_dir: PathBuf, output_dir: PathBuf) -> Result<(), Oxid
         // Check if it's index.ts
         let is_index = path.file_stem().and_then(|s| s.to_str()) == Some("index");
 
-        let generated = ox_codegen::generate(program, is_index);
+        let generated = ox_codegen::generate_in_project(
+            program,
+            is_index,
+            options,
+            &error_classes,
+            &emitter_classes,
+            &functions,
+            &statics,
+        );
+        report_diagnostics(path, &generated.diagnostics);
+        extra_dependencies.extend(generated.dependencies);
+        support_modules.extend(generated.support);
         let formatted_code = format_code(generated.code)?;
 
         let output_file = output_path.with_file_name(format!("{}.rs", sanitized_stem));
@@ -167,38 +264,23 @@ pub fn build_project(input_dir: PathBuf, output_dir: PathBuf) -> Result<(), Oxid
         fs::rename(src_mod, src_lib.clone()).map_err(OxidizerError::IoError)?;
     }
 
-    // Generate error.rs
+    // Generate error.rs: `AppError` with a variant per error class
     let error_rs = output_dir.join("src").join("error.rs");
-    let error_content = r#"
-use axum::{response::{IntoResponse, Response}, http::StatusCode};
-
-pub struct AppError(Box<dyn std::error::Error + Send + Sync>);
-
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            self.0.to_string(),
-        )
-            .into_response()
-    }
-}
-
-impl<E> From<E> for AppError
-where
-    E: std::error::Error + Send + Sync + 'static,
-{
-    fn from(err: E) -> Self {
-        Self(Box::new(err))
-    }
-}
-
-"#;
+    let error_content = format_code(ox_codegen::convert::error::error_module(&error_classes))?;
     fs::write(error_rs, error_content).map_err(OxidizerError::IoError)?;
 
     // Append mod error; pub use error::AppError; to lib.rs
     let mut lib_content = fs::read_to_string(&src_lib).map_err(OxidizerError::IoError)?;
     lib_content.push_str("\npub mod error;\npub use error::AppError;\n");
+
+    // Support modules used by the translated code, e.g. `crate::axios`
+    for name in &support_modules {
+        if let Some(source) = ox_codegen::support::source(name) {
+            let module_rs = output_dir.join("src").join(format!("{}.rs", name));
+            fs::write(module_rs, source).map_err(OxidizerError::IoError)?;
+            lib_content.push_str(&format!("pub mod {};\n", name));
+        }
+    }
     fs::write(&src_lib, lib_content).map_err(OxidizerError::IoError)?;
 
     // 5. Generate main.rs
@@ -208,6 +290,7 @@ where
         &controllers,
         &graph,
         &generic_classes,
+        options,
     )?;
 
     // Ensure src directory exists
@@ -220,7 +303,23 @@ where
     fs::write(main_rs, main_content).map_err(OxidizerError::IoError)?;
 
     // 6. Generate Cargo.toml
-    generate_cargo_toml(&output_dir)?;
+    if options.console_tracing {
+        extra_dependencies.insert("tracing".to_string(), "\"0.1\"".to_string());
+        extra_dependencies.insert("tracing-subscriber".to_string(), "\"0.3\"".to_string());
+    }
+    generate_cargo_toml(&output_dir, &extra_dependencies)?;
+
+    // The runtime crate is depended on by path, so it is copied alongside
+    if extra_dependencies.contains_key(ox_codegen::runtime::CRATE) {
+        let runtime_dir = output_dir.join(ox_codegen::runtime::CRATE);
+        for (file, content) in ox_codegen::runtime::FILES {
+            let path = runtime_dir.join(file);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(OxidizerError::IoError)?;
+            }
+            fs::write(path, content).map_err(OxidizerError::IoError)?;
+        }
+    }
 
     Ok(())
 }
@@ -231,6 +330,7 @@ fn generate_main_rs(
     controllers: &[String],
     graph: &ox_analyzer::graph::DependencyGraph,
     generic_classes: &std::collections::HashSet<String>,
+    options: &Options,
 ) -> Result<String, OxidizerError> {
     let mut main_content = String::new();
     main_content.push_str("use axum::Router;\n");
@@ -240,6 +340,9 @@ fn generate_main_rs(
 
     main_content.push_str("#[tokio::main]\n");
     main_content.push_str("async fn main() {\n");
+    if options.console_tracing {
+        main_content.push_str("    tracing_subscriber::fmt::init();\n\n");
+    }
 
     // Instantiate components in order
     let mut instantiated_vars = std::collections::HashMap::new();
@@ -304,8 +407,18 @@ fn generate_main_rs(
     Ok(main_content)
 }
 
-fn generate_cargo_toml(output_dir: &Path) -> Result<(), OxidizerError> {
-    let cargo_toml_content = r#"[package]
+fn generate_cargo_toml(
+    output_dir: &Path,
+    extra_dependencies: &std::collections::BTreeMap<String, String>,
+) -> Result<(), OxidizerError> {
+    // Crates only needed by some JS APIs (regex, chrono, ...) are added on demand
+    let extra: String = extra_dependencies
+        .iter()
+        .map(|(name, spec)| format!("{} = {}\n", name, spec))
+        .collect();
+
+    let cargo_toml_content = format!(
+        r#"[package]
 name = "typerust_app"
 version = "0.1.0"
 edition = "2021"
@@ -313,15 +426,16 @@ edition = "2021"
 [workspace]
 
 [dependencies]
-tokio = { version = "1.0", features = ["full"] }
+tokio = {{ version = "1.0", features = ["full"] }}
 axum = "0.7"
-serde = { version = "1.0", features = ["derive", "rc"] }
-serde_json = "1.0"
-reqwest = { version = "0.11", features = ["json"] }
-tower = { version = "0.4" }
-tower-http = { version = "0.5", features = ["trace"] }
+serde = {{ version = "1.0", features = ["derive", "rc"] }}
+serde_json = {{ version = "1.0", features = ["preserve_order"] }}
+reqwest = {{ version = "0.11", features = ["json"] }}
+tower = {{ version = "0.4" }}
+tower-http = {{ version = "0.5", features = ["trace"] }}
 rand = "0.8"
-
+thiserror = "1"
+{}
 [[bin]]
 name = "server"
 path = "src/main.rs"
@@ -329,7 +443,9 @@ path = "src/main.rs"
 [lib]
 name = "typerust_app"
 path = "src/lib.rs"
-"#;
+"#,
+        extra
+    );
 
     let cargo_toml_path = output_dir.join("Cargo.toml");
     fs::write(cargo_toml_path, cargo_toml_content).map_err(OxidizerError::IoError)?;
@@ -408,6 +524,12 @@ fn generate_mod_rs(dir: &Path) -> Result<(), OxidizerError> {
     Ok(())
 }
 
+fn report_diagnostics(path: &Path, diagnostics: &[String]) {
+    for diagnostic in diagnostics {
+        eprintln!("⚠️  {}: {}", path.display(), diagnostic);
+    }
+}
+
 fn format_code(code: String) -> Result<String, OxidizerError> {
     // Skip formatting for code containing async (edition compatibility)
     if code.contains("async fn") {
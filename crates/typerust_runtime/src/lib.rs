@@ -0,0 +1,653 @@
+//! JS semantics that translated code cannot express in a line or two:
+//! number formatting, truthiness, loose equality, the default sort order and
+//! node's path joining.
+//!
+//! The generated project depends on this crate by path. The default `json`
+//! feature adds the `serde_json::Value` implementations and [`json_value`].
+
+use std::cmp::Ordering;
+
+/// `String(n)` and `n.toString()`: the shortest digits that round-trip, in
+/// plain notation from `1e-6` up to `1e21` and in exponent notation outside
+pub fn js_to_string(value: f64) -> String {
+    if value.is_nan() {
+        return "NaN".to_string();
+    }
+    if value == 0.0 {
+        // `-0` prints as `0`
+        return "0".to_string();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+    if value < 0.0 {
+        return format!("-{}", js_to_string(-value));
+    }
+
+    // Rust's `{:e}` also prints the shortest round-tripping digits
+    let scientific = format!("{:e}", value);
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    let k = digits.len() as i32;
+    // The decimal point goes after `n` digits
+    let n = exponent + 1;
+
+    if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat((-n) as usize), digits)
+    } else {
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        let (first, rest) = digits.split_at(1);
+        if rest.is_empty() {
+            format!("{}e{}{}", first, sign, (n - 1).abs())
+        } else {
+            format!("{}.{}e{}{}", first, rest, sign, (n - 1).abs())
+        }
+    }
+}
+
+/// `Number(s)`: the whole trimmed string must be a numeric literal, `""` is 0
+pub fn to_number(text: &str) -> f64 {
+    let s = text.trim_matches(|c: char| c.is_whitespace() || c == '\u{feff}');
+    if s.is_empty() {
+        return 0.0;
+    }
+    let radix = match s.get(..2) {
+        Some("0x" | "0X") => 16,
+        Some("0o" | "0O") => 8,
+        Some("0b" | "0B") => 2,
+        _ => 10,
+    };
+    if radix != 10 {
+        let digits = &s[2..];
+        if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+            return f64::NAN;
+        }
+        return digits.chars().fold(0.0, |acc, c| {
+            acc * radix as f64 + c.to_digit(radix).unwrap() as f64
+        });
+    }
+    match s {
+        "Infinity" | "+Infinity" => f64::INFINITY,
+        "-Infinity" => f64::NEG_INFINITY,
+        // Rust also accepts "inf" and "NaN", JS does not
+        _ if !s
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-')) =>
+        {
+            f64::NAN
+        }
+        _ => s.parse().unwrap_or(f64::NAN),
+    }
+}
+
+/// The string a value converts to, as `String(value)` or `` `${value}` `` give
+pub trait ToJsString {
+    fn to_js_string(&self) -> String;
+
+    /// The string the value is as an array element, where `null` and
+    /// `undefined` are empty: `[undefined, 1].join()` is `",1"`
+    fn to_js_element(&self) -> String {
+        self.to_js_string()
+    }
+}
+
+impl ToJsString for f64 {
+    fn to_js_string(&self) -> String {
+        js_to_string(*self)
+    }
+}
+
+impl ToJsString for bool {
+    fn to_js_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl ToJsString for str {
+    fn to_js_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl ToJsString for String {
+    fn to_js_string(&self) -> String {
+        self.clone()
+    }
+}
+
+/// An absent optional value is `undefined`
+impl<T: ToJsString> ToJsString for Option<T> {
+    fn to_js_string(&self) -> String {
+        match self {
+            Some(value) => value.to_js_string(),
+            None => "undefined".to_string(),
+        }
+    }
+
+    fn to_js_element(&self) -> String {
+        match self {
+            Some(value) => value.to_js_element(),
+            None => String::new(),
+        }
+    }
+}
+
+/// Arrays join their elements with commas, absent ones as empty strings
+impl<T: ToJsString> ToJsString for Vec<T> {
+    fn to_js_string(&self) -> String {
+        self.iter()
+            .map(ToJsString::to_js_element)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl<T: ToJsString + ?Sized> ToJsString for &T {
+    fn to_js_string(&self) -> String {
+        (**self).to_js_string()
+    }
+
+    fn to_js_element(&self) -> String {
+        (**self).to_js_element()
+    }
+}
+
+/// Whether a value counts as `true` in a condition
+pub trait Truthy {
+    fn truthy(&self) -> bool;
+}
+
+/// `if (value)`: `false`, `0`, `NaN`, `""`, `null` and `undefined` are falsy
+pub fn truthy<T: Truthy + ?Sized>(value: &T) -> bool {
+    value.truthy()
+}
+
+impl Truthy for bool {
+    fn truthy(&self) -> bool {
+        *self
+    }
+}
+
+impl Truthy for f64 {
+    fn truthy(&self) -> bool {
+        *self != 0.0 && !self.is_nan()
+    }
+}
+
+impl Truthy for str {
+    fn truthy(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+impl Truthy for String {
+    fn truthy(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+impl<T: Truthy> Truthy for Option<T> {
+    fn truthy(&self) -> bool {
+        self.as_ref().is_some_and(Truthy::truthy)
+    }
+}
+
+/// Arrays are objects, and objects are truthy even when empty
+impl<T> Truthy for Vec<T> {
+    fn truthy(&self) -> bool {
+        true
+    }
+}
+
+impl<T: Truthy + ?Sized> Truthy for &T {
+    fn truthy(&self) -> bool {
+        (**self).truthy()
+    }
+}
+
+/// JS `==`, which converts strings and booleans to numbers before comparing
+/// them with numbers
+pub trait LooseEq<Rhs: ?Sized = Self> {
+    fn loose_eq(&self, other: &Rhs) -> bool;
+}
+
+/// `a == b`
+pub fn loose_eq<A: LooseEq<B> + ?Sized, B: ?Sized>(a: &A, b: &B) -> bool {
+    a.loose_eq(b)
+}
+
+/// The number a primitive converts to in a loose comparison
+trait ToJsNumber {
+    fn to_js_number(&self) -> f64;
+}
+
+impl ToJsNumber for f64 {
+    fn to_js_number(&self) -> f64 {
+        *self
+    }
+}
+
+impl ToJsNumber for bool {
+    fn to_js_number(&self) -> f64 {
+        if *self {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+impl ToJsNumber for str {
+    fn to_js_number(&self) -> f64 {
+        to_number(self)
+    }
+}
+
+impl ToJsNumber for String {
+    fn to_js_number(&self) -> f64 {
+        to_number(self)
+    }
+}
+
+/// Comparisons that go through numbers
+macro_rules! numeric_loose_eq {
+    ($($left:ty => $($right:ty),+;)+) => {
+        $($(
+            impl LooseEq<$right> for $left {
+                fn loose_eq(&self, other: &$right) -> bool {
+                    self.to_js_number() == other.to_js_number()
+                }
+            }
+        )+)+
+    };
+}
+
+numeric_loose_eq! {
+    f64 => f64, bool, str, String;
+    bool => f64, str, String;
+    str => f64, bool;
+    String => f64, bool;
+}
+
+impl LooseEq for bool {
+    fn loose_eq(&self, other: &bool) -> bool {
+        self == other
+    }
+}
+
+impl LooseEq<str> for str {
+    fn loose_eq(&self, other: &str) -> bool {
+        self == other
+    }
+}
+
+impl LooseEq<String> for str {
+    fn loose_eq(&self, other: &String) -> bool {
+        self == other
+    }
+}
+
+impl LooseEq<str> for String {
+    fn loose_eq(&self, other: &str) -> bool {
+        self == other
+    }
+}
+
+impl LooseEq for String {
+    fn loose_eq(&self, other: &String) -> bool {
+        self == other
+    }
+}
+
+/// `null == undefined`, but neither equals anything else
+impl<T: LooseEq<U>, U> LooseEq<Option<U>> for Option<T> {
+    fn loose_eq(&self, other: &Option<U>) -> bool {
+        match (self, other) {
+            (Some(left), Some(right)) => left.loose_eq(right),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+/// `array.sort()` without a comparator: by the elements' string forms, in
+/// UTF-16 code unit order
+pub fn compare_default<T: ToJsString + ?Sized>(a: &T, b: &T) -> Ordering {
+    a.to_js_string()
+        .encode_utf16()
+        .cmp(b.to_js_string().encode_utf16())
+}
+
+/// The `/`-separated segments of `path` with `.` and empty ones dropped and
+/// `..` applied; a leading `..` is kept only when `above_root` is allowed
+fn normalize_segments(path: &str, above_root: bool) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." if segments.last().is_some_and(|last| *last != "..") => {
+                segments.pop();
+            }
+            ".." if !above_root => {}
+            segment => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+/// `path.normalize(p)`: `..` and `.` resolved, repeated separators collapsed
+/// and a trailing separator kept, as node does on POSIX
+pub fn path_normalize(path: &str) -> String {
+    if path.is_empty() {
+        return ".".to_string();
+    }
+    let is_absolute = path.starts_with('/');
+    let mut normalized = normalize_segments(path, !is_absolute);
+    if normalized.is_empty() && !is_absolute {
+        normalized.push('.');
+    }
+    if !normalized.is_empty() && path.ends_with('/') {
+        normalized.push('/');
+    }
+    if is_absolute {
+        format!("/{}", normalized)
+    } else {
+        normalized
+    }
+}
+
+/// `path.join(a, b, ...)`: every segment is appended, absolute ones too, and
+/// the result normalized
+pub fn path_join(segments: &[&str]) -> String {
+    let joined = segments
+        .iter()
+        .filter(|segment| !segment.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("/");
+    path_normalize(&joined)
+}
+
+/// `path.resolve(a, b, ...)`: the segments from the last absolute one on, on
+/// top of the current directory when none is absolute, without a trailing
+/// separator
+pub fn path_resolve(segments: &[&str]) -> String {
+    let mut resolved = String::new();
+    for segment in segments.iter().rev().filter(|segment| !segment.is_empty()) {
+        resolved = format!("{}/{}", segment, resolved);
+        if segment.starts_with('/') {
+            break;
+        }
+    }
+    if !resolved.starts_with('/') {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        resolved = format!("{}/{}", cwd.to_string_lossy(), resolved);
+    }
+    format!("/{}", normalize_segments(&resolved, false))
+}
+
+/// `params.set(key, value)`: the first pair named `key` takes the value in
+/// place and later ones are dropped; the pair is appended when none exists
+pub fn search_params_set(pairs: &mut Vec<(String, String)>, key: &str, value: String) {
+    let mut value = Some(value);
+    pairs.retain_mut(|(name, current)| {
+        if name != key {
+            return true;
+        }
+        match value.take() {
+            Some(value) => {
+                *current = value;
+                true
+            }
+            None => false,
+        }
+    });
+    if let Some(value) = value {
+        pairs.push((key.to_string(), value));
+    }
+}
+
+#[cfg(feature = "json")]
+mod json {
+    use super::*;
+    use serde_json::Value;
+
+    /// `value` as `JSON.stringify` sees it: integral numbers have no
+    /// fraction, so `5.0` is written as `5`
+    pub fn json_value<T: serde::Serialize + ?Sized>(value: &T) -> Value {
+        let mut value = serde_json::to_value(value).unwrap_or(Value::Null);
+        normalize(&mut value);
+        value
+    }
+
+    fn normalize(value: &mut Value) {
+        match value {
+            Value::Number(number) => {
+                if let Some(float) = number.as_f64().filter(|_| number.is_f64()) {
+                    if float.fract() == 0.0 && float.abs() < 9007199254740992.0 {
+                        *number = serde_json::Number::from(float as i64);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(normalize),
+            Value::Object(fields) => fields.values_mut().for_each(normalize),
+            _ => {}
+        }
+    }
+
+    impl ToJsString for Value {
+        fn to_js_string(&self) -> String {
+            match self {
+                Value::Null => "null".to_string(),
+                Value::Bool(b) => b.to_string(),
+                Value::Number(n) => js_to_string(n.as_f64().unwrap_or(f64::NAN)),
+                Value::String(s) => s.clone(),
+                // `[1, null].join()` is `"1,"`
+                Value::Array(items) => items
+                    .iter()
+                    .map(ToJsString::to_js_element)
+                    .collect::<Vec<_>>()
+                    .join(","),
+                Value::Object(_) => "[object Object]".to_string(),
+            }
+        }
+
+        fn to_js_element(&self) -> String {
+            match self {
+                Value::Null => String::new(),
+                value => value.to_js_string(),
+            }
+        }
+    }
+
+    impl Truthy for Value {
+        fn truthy(&self) -> bool {
+            match self {
+                Value::Null => false,
+                Value::Bool(b) => *b,
+                Value::Number(n) => n.as_f64().is_some_and(|n| n.truthy()),
+                Value::String(s) => !s.is_empty(),
+                Value::Array(_) | Value::Object(_) => true,
+            }
+        }
+    }
+
+    /// Primitives compare by value, arrays and objects only to themselves,
+    /// which a copy never is
+    impl LooseEq for Value {
+        fn loose_eq(&self, other: &Value) -> bool {
+            match (self, other) {
+                (Value::Null, Value::Null) => true,
+                (Value::Null, _) | (_, Value::Null) => false,
+                (Value::String(a), Value::String(b)) => a == b,
+                (Value::Bool(a), Value::Bool(b)) => a == b,
+                (Value::Array(_) | Value::Object(_), _)
+                | (_, Value::Array(_) | Value::Object(_)) => false,
+                (a, b) => number_of(a) == number_of(b),
+            }
+        }
+    }
+
+    fn number_of(value: &Value) -> f64 {
+        match value {
+            Value::Number(n) => n.as_f64().unwrap_or(f64::NAN),
+            Value::String(s) => to_number(s),
+            Value::Bool(b) => b.to_js_number(),
+            _ => f64::NAN,
+        }
+    }
+
+    macro_rules! value_loose_eq {
+        ($($other:ty => $to_value:expr;)+) => {
+            $(
+                impl LooseEq<$other> for Value {
+                    fn loose_eq(&self, other: &$other) -> bool {
+                        self.loose_eq(&$to_value(other))
+                    }
+                }
+
+                impl LooseEq<Value> for $other {
+                    fn loose_eq(&self, other: &Value) -> bool {
+                        other.loose_eq(self)
+                    }
+                }
+            )+
+        };
+    }
+
+    value_loose_eq! {
+        f64 => |n: &f64| serde_json::Number::from_f64(*n).map_or(Value::Null, Value::Number);
+        bool => |b: &bool| Value::Bool(*b);
+        str => |s: &str| Value::String(s.to_string());
+        String => |s: &String| Value::String(s.clone());
+    }
+}
+
+#[cfg(feature = "json")]
+pub use json::json_value;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_js_to_string() {
+        assert_eq!(js_to_string(5.0), "5");
+        assert_eq!(js_to_string(-0.0), "0");
+        assert_eq!(js_to_string(0.1 + 0.2), "0.30000000000000004");
+        assert_eq!(js_to_string(123.456), "123.456");
+        assert_eq!(js_to_string(1e21), "1e+21");
+        assert_eq!(js_to_string(1.5e-7), "1.5e-7");
+        assert_eq!(js_to_string(0.000001), "0.000001");
+        assert_eq!(js_to_string(-2.5e25), "-2.5e+25");
+        assert_eq!(js_to_string(f64::NEG_INFINITY), "-Infinity");
+        assert_eq!(js_to_string(f64::NAN), "NaN");
+    }
+
+    #[test]
+    fn test_to_number() {
+        assert_eq!(to_number("  12.5 "), 12.5);
+        assert_eq!(to_number(""), 0.0);
+        assert_eq!(to_number("0x1F"), 31.0);
+        assert!(to_number("12px").is_nan());
+        assert!(to_number("inf").is_nan());
+        assert_eq!(to_number("-Infinity"), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_array_to_js_string() {
+        assert_eq!(vec![None, Some(1.0)].to_js_string(), ",1");
+        assert_eq!(vec![Some("a".to_string()), None].to_js_string(), "a,");
+        assert_eq!(vec![vec![1.0, 2.0], vec![]].to_js_string(), "1,2,");
+        assert_eq!(None::<f64>.to_js_string(), "undefined");
+    }
+
+    #[test]
+    fn test_truthy() {
+        assert!(!truthy(&0.0));
+        assert!(!truthy(&f64::NAN));
+        assert!(!truthy(""));
+        assert!(truthy(&"0".to_string()));
+        assert!(!truthy(&Some(0.0)));
+        assert!(!truthy(&None::<String>));
+        assert!(truthy(&Vec::<f64>::new()));
+    }
+
+    #[test]
+    fn test_loose_eq() {
+        assert!(loose_eq(&1.0, "1"));
+        assert!(loose_eq(&0.0, ""));
+        assert!(loose_eq(&true, &1.0));
+        assert!(loose_eq("1", &true));
+        assert!(!loose_eq(&f64::NAN, &f64::NAN));
+        assert!(loose_eq(&None::<f64>, &None::<String>));
+        assert!(!loose_eq(&Some(0.0), &None::<f64>));
+    }
+
+    #[test]
+    fn test_compare_default() {
+        let mut numbers = vec![10.0, 9.0, 1.0, 100.0];
+        numbers.sort_by(compare_default);
+        assert_eq!(numbers, vec![1.0, 10.0, 100.0, 9.0]);
+    }
+
+    #[test]
+    fn test_search_params_set() {
+        let pair = |name: &str, value: &str| (name.to_string(), value.to_string());
+        let mut pairs = vec![pair("a", "1"), pair("b", "2"), pair("a", "3")];
+        search_params_set(&mut pairs, "a", "x".to_string());
+        assert_eq!(pairs, vec![pair("a", "x"), pair("b", "2")]);
+        search_params_set(&mut pairs, "c", "4".to_string());
+        assert_eq!(pairs, vec![pair("a", "x"), pair("b", "2"), pair("c", "4")]);
+    }
+
+    #[test]
+    fn test_path_join() {
+        assert_eq!(path_join(&["/x/", "/y"]), "/x/y");
+        assert_eq!(path_join(&["a", "b/../c", "./d"]), "a/c/d");
+        assert_eq!(path_join(&["a", "../../b"]), "../b");
+        assert_eq!(path_join(&["/a", "../../b/"]), "/b/");
+        assert_eq!(path_join(&["", ""]), ".");
+    }
+
+    #[test]
+    fn test_path_resolve() {
+        assert_eq!(path_resolve(&["/x", "/y", "z"]), "/y/z");
+        assert_eq!(path_resolve(&["/x/y/", "../z/"]), "/x/z");
+        assert_eq!(path_resolve(&["/", ".."]), "/");
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(path_resolve(&["a"]), cwd.join("a").to_string_lossy());
+    }
+
+    #[test]
+    fn test_path_normalize() {
+        assert_eq!(path_normalize("/a//b/./c/../d/"), "/a/b/d/");
+        assert_eq!(path_normalize("a/../.."), "..");
+        assert_eq!(path_normalize("/.."), "/");
+        assert_eq!(path_normalize("./"), "./");
+        assert_eq!(path_normalize(""), ".");
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_value() {
+        let value = json_value(&vec![55.0, 0.5]);
+        assert_eq!(serde_json::to_string(&value).unwrap(), "[55,0.5]");
+        assert!(loose_eq(&serde_json::json!("2"), &2.0));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_value_keeps_key_order() {
+        let value = json_value(&serde_json::json!({ "name": "a", "id": 1.0, "tags": [] }));
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"name":"a","id":1,"tags":[]}"#
+        );
+    }
+}
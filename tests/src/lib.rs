@@ -7,7 +7,11 @@ use std::process::Command;
 mod test_build;
 mod test_compilation;
 mod test_execute;
+#[cfg(test)]
+mod test_expressions;
 mod test_generics;
+#[cfg(test)]
+mod test_module_items;
 mod test_nestjs;
 mod test_regression;
 #[cfg(test)]
@@ -0,0 +1,1194 @@
+#[cfg(test)]
+mod expression_tests {
+    use ox_common::fs::FilePath;
+
+    fn transpile(name: &str, ts_code: &str) -> String {
+        let ts_file = std::env::temp_dir().join(format!("expr_{}.ts", name));
+        std::fs::write(&ts_file, ts_code).unwrap();
+
+        let rust_code =
+            ox_orchestrator::build(FilePath::from(ts_file)).expect("Failed to generate Rust code");
+        println!("Generated Rust code:\n{}", rust_code);
+        rust_code
+    }
+
+    #[test]
+    fn test_type_assertions() {
+        let rust_code = transpile(
+            "type_assertions",
+            r#"
+            interface User { name: string; }
+            function load(raw: string, fallback: string | undefined): string {
+                const data = JSON.parse(raw);
+                const user = data as User;
+                const count = (user.name.length as number) satisfies number;
+                const tags = ["a", "b"] as const;
+                return fallback!;
+            }
+        "#,
+        );
+
+        assert!(rust_code.contains("serde_json::from_value::<User>(data.clone()).unwrap()"));
+        assert!(rust_code.contains("let mut tags = vec!["));
+        assert!(rust_code.contains("fallback.expect("));
+        assert!(!rust_code.contains("todo!"));
+    }
+
+    #[test]
+    fn test_literals() {
+        let rust_code = transpile(
+            "literals",
+            r#"
+            function check(s: string, parts: string[]): boolean {
+                const big = 9007199254740993n;
+                const million = 1_000_000;
+                const missing = null;
+                let owner: string | null = null;
+                const joined = parts.join(", ");
+                if (s === "ok") {
+                    return true;
+                }
+                return s.includes("x") && false;
+            }
+        "#,
+        );
+
+        assert!(rust_code.contains("9007199254740993i128"));
+        assert!(rust_code.contains("1000000f64"));
+        assert!(rust_code.contains("let mut missing: Option<serde_json::Value> = None;"));
+        assert!(rust_code.contains("let mut owner: Option<String> = None;"));
+        assert!(rust_code.contains("parts.join(\", \")"));
+        assert!(rust_code.contains("s == \"ok\""));
+        assert!(rust_code.contains("s.contains(\"x\") && false"));
+        assert!(!rust_code.contains("todo!"));
+    }
+
+    #[test]
+    fn test_regex_literal_adds_dependency() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let input_dir = temp_dir.path().join("input");
+        let output_dir = temp_dir.path().join("output");
+        std::fs::create_dir_all(&input_dir).unwrap();
+        std::fs::write(
+            input_dir.join("validate.ts"),
+            r#"
+            export function isSlug(s: string): boolean {
+                const re = /^[a-z-]+$/i;
+                return true;
+            }
+        "#,
+        )
+        .unwrap();
+
+        ox_orchestrator::build_project(input_dir, output_dir.clone()).expect("Build failed");
+
+        let module = std::fs::read_to_string(output_dir.join("src/validate.rs")).unwrap();
+        assert!(module.contains("LazyLock<regex::Regex>"));
+        assert!(module.contains("(?i)^[a-z-]+$"));
+
+        let cargo_toml = std::fs::read_to_string(output_dir.join("Cargo.toml")).unwrap();
+        assert!(cargo_toml.contains("regex = \"1\""));
+    }
+
+    #[test]
+    fn test_regexp_methods() {
+        let rust_code = transpile(
+            "regexp_methods",
+            r#"
+            const EMAIL = /(\w+)@(\w+)\.com/g;
+            function tidy(s: string): string {
+                const date = new RegExp("(\\d{4})-(\\d{2})", "i");
+                const ok = date.test(s);
+                const first = date.exec(s);
+                const all = s.match(EMAIL);
+                const each = s.matchAll(EMAIL);
+                const parts = s.split(/[,;]\s*/);
+                const swapped = s.replace(date, "$2/$1 ($&)");
+                const upper = s.replace(/(a)(b)?/g, (whole, a) => a.toUpperCase());
+                const back = /(a)\1(?<=b)/;
+                return s.replaceAll(/\s+/g, " ");
+            }
+        "#,
+        );
+
+        let compact: String = rust_code.split_whitespace().collect();
+        assert!(compact.contains(
+            "staticEMAIL:std::sync::LazyLock<regex::Regex>=std::sync::LazyLock::new(||regex::Regex::new(\"(\\\\w+)@(\\\\w+)\\\\.com\").unwrap());"
+        ));
+        assert!(compact.contains("regex::Regex::new(\"(?i)(\\\\d{4})-(\\\\d{2})\")"));
+        assert!(compact.contains("letmutok=date.is_match(&s);"));
+        assert!(compact.contains("letmutfirst=date.captures(&s).map(|caps|"));
+        assert!(compact.contains("EMAIL.find_iter(&s)"));
+        assert!(compact.contains("EMAIL.captures_iter(&s)"));
+        assert!(compact.contains(".split(&s).map(|part|part.to_string())"));
+        assert!(compact.contains("date.replace(&s,\"${2}/${1}(${0})\").into_owned()"));
+        assert!(compact.contains(".replace_all(&s,|caps:&regex::Captures|->String{"));
+        assert!(compact.contains("leta=caps.get(1).map_or(String::new(),"));
+        assert!(compact.contains(".replace_all(&s,\"\").into_owned()"));
+        assert!(!compact.contains("s.replace(\""));
+    }
+
+    #[test]
+    fn test_node_builtins() {
+        let rust_code = transpile(
+            "node_builtins",
+            r#"
+            import fs from "fs";
+            import * as path from "node:path";
+            import { readFile } from "fs/promises";
+            function configPath(name: string): string {
+                const dir = path.join(process.cwd(), "config", name);
+                const home = process.env.HOME ?? "/tmp";
+                const args = process.argv;
+                if (!fs.existsSync(dir)) {
+                    fs.mkdirSync(dir, { recursive: true });
+                }
+                const text = fs.readFileSync(path.resolve(dir, "app.json"), "utf8");
+                if (text.length === 0) {
+                    process.exit(1);
+                }
+                return dir + path.sep + path.basename(dir, ".json");
+            }
+            async function load(file: string): Promise<string> {
+                await fs.promises.writeFile(file, "{}");
+                return await readFile(file, "utf8");
+            }
+        "#,
+        );
+
+        let compact: String = rust_code.split_whitespace().collect();
+        assert!(!compact.contains("usefs"));
+        assert!(!compact.contains("usepath"));
+        assert!(compact.contains("typerust_runtime::path_join(&[&std::env::current_dir()"));
+        assert!(compact.contains(".to_string_lossy().into_owned(),\"config\",&name]);"));
+        assert!(compact.contains(
+            "letmuthome=std::env::var(\"HOME\").ok().unwrap_or_else(||String::from(\"/tmp\"));"
+        ));
+        assert!(compact.contains("std::env::args()"));
+        assert!(compact.contains("std::path::Path::new(&dir).exists()"));
+        assert!(compact.contains("std::fs::create_dir_all(&dir).unwrap();"));
+        assert!(compact.contains(
+            "std::fs::read_to_string(&typerust_runtime::path_resolve(&[&dir,\"app.json\"]))"
+        ));
+        assert!(compact.contains("std::process::exit((1f64)asi32)"));
+        assert!(compact.contains("std::path::MAIN_SEPARATOR_STR"));
+        assert!(compact.contains("tokio::fs::write(&file,\"{}\").await?;"));
+        assert!(compact.contains("tokio::fs::read_to_string(&file).await?"));
+    }
+
+    #[test]
+    fn test_fetch_request_options() {
+        let rust_code = transpile(
+            "fetch_request_options",
+            r#"
+            interface User { name: string; age: number; }
+            async function createUser(url: string, user: User, token: string): Promise<User> {
+                const res = await fetch(url, {
+                    method: "POST",
+                    headers: { "Content-Type": "application/json", Authorization: token },
+                    body: JSON.stringify(user),
+                    signal: AbortSignal.timeout(5000),
+                });
+                const status = res.status;
+                const kind = res.headers.get("content-type");
+                if (res.ok) {
+                    const created: User = await res.json();
+                    return created;
+                }
+                return res.json<User>();
+            }
+            async function readText(url: string): Promise<string> {
+                const res = await fetch(url);
+                return await res.text();
+            }
+        "#,
+        );
+
+        let compact: String = rust_code.split_whitespace().collect();
+        assert!(compact.contains("staticCLIENT:std::sync::LazyLock<reqwest::Client>"));
+        assert!(compact.contains(".request(reqwest::Method::POST,&url)"));
+        assert!(compact.contains(
+            ".header(\"Content-Type\",\"application/json\").header(\"Authorization\",&token)"
+        ));
+        assert!(compact.contains(
+            ".body(serde_json::to_string(&typerust_runtime::json_value(&user)).unwrap())"
+        ));
+        assert!(compact.contains(".timeout(std::time::Duration::from_millis((5000f64)asu64))"));
+        assert!(compact.contains(".send().await?;"));
+        assert!(compact.contains("letmutstatus=(res.status().as_u16()asf64);"));
+        assert!(compact
+            .contains("res.headers().get(\"content-type\").and_then(|value|value.to_str().ok())"));
+        assert!(compact.contains("ifres.status().is_success()"));
+        assert!(compact.contains("letmutcreated=res.json::<User>().await?;"));
+        assert!(compact.contains("returnOk(res.json::<User>().await?);"));
+        assert!(compact.contains(".request(reqwest::Method::GET,&url).send().await?;"));
+        assert!(compact.contains("returnOk(res.text().await?);"));
+    }
+
+    #[test]
+    fn test_axios_instances_and_config() {
+        let rust_code = transpile(
+            "axios_instances",
+            r#"
+            import axios, { AxiosResponse } from "axios";
+            interface User { id: number; name: string; }
+            const api = axios.create({ baseURL: "https://api.example.com", timeout: 5000 });
+            async function getUser(id: string): Promise<User> {
+                return await api.get<User>("/users/" + id);
+            }
+            async function search(q: string): Promise<User[]> {
+                const { data } = await api.get<User[]>("/users", { params: { q: q } });
+                return data;
+            }
+            async function create(user: User): Promise<AxiosResponse<User>> {
+                return axios.request<User>({ url: "https://example.com/users", method: "POST", data: user });
+            }
+            async function health(): Promise<string> {
+                return api.request<string>({ baseURL: "https://status.example.com", url: "/health" });
+            }
+            async function ping(): Promise<string> {
+                return axios.get<string>("/ping", { baseURL: "https://status.example.com" });
+            }
+        "#,
+        );
+
+        let compact: String = rust_code.split_whitespace().collect();
+        assert!(compact.contains("staticapi:std::sync::LazyLock<crate::axios::Instance>"));
+        assert!(compact.contains(
+            "crate::axios::Instance::new(String::from(\"https://api.example.com\"),Some(std::time::Duration::from_millis((5000f64)asu64)),reqwest::header::HeaderMap::new())"
+        ));
+        assert!(compact
+            .contains("returnOk(crate::axios::send::<User>(api.request(reqwest::Method::GET,"));
+        assert!(compact.contains(".await?.data);"));
+        assert!(compact.contains(
+            "letcrate::axios::Response{mutdata,..}=crate::axios::send::<Vec<User>>(api.request(reqwest::Method::GET,\"/users\").query(&serde_json::json!({\"q\":q}))).await?;"
+        ));
+        assert!(compact.contains("Result<crate::axios::Response<User>,crate::AppError>"));
+        assert!(compact.contains(
+            "returnOk(crate::axios::send::<User>(crate::axios::default().request(reqwest::Method::POST,\"https://example.com/users\").json(&user)).await?);"
+        ));
+        // A `baseURL` in the request config replaces the instance's
+        assert!(compact.contains(
+            "api.request_at(\"https://status.example.com\",reqwest::Method::GET,\"/health\")"
+        ));
+        assert!(compact.contains(
+            "crate::axios::default().request_at(\"https://status.example.com\",reqwest::Method::GET,\"/ping\")"
+        ));
+    }
+
+    #[test]
+    fn test_axios_error_response_in_catch() {
+        let rust_code = transpile(
+            "axios_errors",
+            r#"
+            import axios from "axios";
+            async function statusOf(url: string): Promise<number> {
+                try {
+                    await axios.get<string>(url);
+                    return 200;
+                } catch (err) {
+                    if (!axios.isAxiosError(err)) {
+                        throw err;
+                    }
+                    if (err.response) {
+                        return err.response.status;
+                    }
+                    return err.response?.status ?? 0;
+                }
+            }
+        "#,
+        );
+
+        let compact: String = rust_code.split_whitespace().collect();
+        assert!(compact.contains("if!crate::axios::is_error(&err){returnErr(err);}"));
+        assert!(compact.contains("ifcrate::axios::response(&err).is_some(){"));
+        assert!(compact.contains(
+            "returnOk(Some(crate::axios::response(&err).expect(\"error.responseisundefined\").status));"
+        ));
+        assert!(compact.contains(
+            "crate::axios::response(&err).map(|response|response.status).unwrap_or_else(||0f64)"
+        ));
+        assert!(!compact.contains("todo!"));
+    }
+
+    #[test]
+    fn test_optional_default_and_rest_params() {
+        let rust_code = transpile(
+            "params",
+            r#"
+            function greet(name: string, greeting: string = "Hello", suffix?: string): string {
+                return greeting + name;
+            }
+            function sum(...nums: number[]): number {
+                return 0;
+            }
+            function run(items: number[]): void {
+                const a = greet("Bob");
+                const b = greet("Bob", "Hi", undefined);
+                const c = sum(1, 2, 3);
+                const d = sum(...items);
+            }
+        "#,
+        );
+
+        assert!(rust_code.contains(
+            "fn greet(name: String, greeting: Option<String>, suffix: Option<String>) -> String"
+        ));
+        assert!(rust_code
+            .contains("let mut greeting = greeting.unwrap_or_else(|| String::from(\"Hello\"));"));
+        assert!(rust_code.contains("greet(String::from(\"Bob\"), None, None)"));
+        assert!(
+            rust_code.contains("greet(String::from(\"Bob\"), Some(String::from(\"Hi\")), None)")
+        );
+        assert!(rust_code.contains("fn sum(nums: Vec<f64>) -> f64"));
+        assert!(rust_code.contains("sum(vec![1f64, 2f64, 3f64])"));
+        assert!(rust_code.contains("sum(items.clone())"));
+    }
+
+    #[test]
+    fn test_imported_function_params() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let input_dir = temp_dir.path().join("input");
+        let output_dir = temp_dir.path().join("output");
+        std::fs::create_dir_all(&input_dir).unwrap();
+        std::fs::write(
+            input_dir.join("text.ts"),
+            r#"
+            export function pad(s: string, width: number = 2, fill?: string): string {
+                return s;
+            }
+            export function total(...nums: number[]): number {
+                return 0;
+            }
+        "#,
+        )
+        .unwrap();
+        std::fs::write(
+            input_dir.join("report.ts"),
+            r#"
+            import { pad, total as sum } from "./text";
+            export function line(name: string): string {
+                return pad(name) + pad("total", 8) + sum(1, 2);
+            }
+        "#,
+        )
+        .unwrap();
+
+        ox_orchestrator::build_project(input_dir, output_dir.clone()).expect("Build failed");
+
+        let module = std::fs::read_to_string(output_dir.join("src/report.rs")).unwrap();
+        println!("Generated Rust code:\n{}", module);
+        let compact: String = module.split_whitespace().collect();
+        assert!(compact.contains("pad(name,None,None)"));
+        assert!(compact.contains("pad(String::from(\"total\"),Some(8f64),None)"));
+        assert!(compact.contains("sum(vec![1f64,2f64])"));
+    }
+
+    #[test]
+    fn test_class_method_default_params() {
+        let rust_code = transpile(
+            "method_params",
+            r#"
+            class Svc {
+                constructor(private prefix: string, label: string = "svc") {}
+                hello(name: string, greeting: string = "hi"): string {
+                    return greeting + " " + name;
+                }
+                twice(name: string): string {
+                    return this.hello(name);
+                }
+            }
+            function run(): string {
+                const s: Svc = new Svc("p");
+                return `${s.hello("bob")}|${s.hello("al", "yo")}`;
+            }
+        "#,
+        );
+
+        assert!(rust_code
+            .contains("pub fn hello(&self, name: String, greeting: Option<String>) -> String"));
+        assert!(rust_code
+            .contains("let mut greeting = greeting.unwrap_or_else(|| String::from(\"hi\"));"));
+        assert!(rust_code.contains("pub fn new(prefix: String, label: Option<String>) -> Self"));
+        assert!(rust_code.contains("self.hello(name, None)"));
+        assert!(rust_code.contains("Svc::new(String::from(\"p\"), None)"));
+        assert!(rust_code.contains("s.hello(String::from(\"bob\"), None)"));
+        assert!(rust_code.contains("s.hello(String::from(\"al\"), Some(String::from(\"yo\")))"));
+    }
+
+    #[test]
+    fn test_overloads_are_merged() {
+        let rust_code = transpile(
+            "overloads",
+            r#"
+            function pick(id: string): string;
+            function pick(id: string, fallback: string): string;
+            function pick(id: string | number, fallback?: string): string {
+                return "picked";
+            }
+        "#,
+        );
+
+        assert_eq!(rust_code.matches("fn pick").count(), 1);
+        assert!(rust_code.contains("fn pick(id: String, fallback: Option<String>) -> String"));
+    }
+
+    #[test]
+    fn test_object_helpers() {
+        let rust_code = transpile(
+            "object_helpers",
+            r#"
+            interface Limits { min: number; max: number; }
+            interface Config { host: string; port: number; }
+            function describe(limits: Limits, config: Config, counts: Record<string, number>): number {
+                const names = Object.keys(limits);
+                const bounds = Object.values(limits);
+                const pairs = Object.entries(counts);
+                const local = Object.assign({}, config, { port: 8080 });
+                Object.assign(counts, { extra: 1 });
+                const frozen = Object.freeze([1, 2]);
+                const settings = Object.freeze(config);
+                Object.freeze(limits);
+                const copy = structuredClone(config);
+                return 0;
+            }
+        "#,
+        );
+
+        assert!(rust_code.contains("counts: std::collections::HashMap<String, f64>"));
+        assert!(rust_code.contains("vec![String::from(\"min\"), String::from(\"max\")]"));
+        assert!(rust_code.contains("vec![limits.min.clone(), limits.max.clone()]"));
+        assert!(rust_code.contains(".map(|(k, v)| (k.clone(), v.clone()))"));
+        assert!(
+            rust_code.contains("Config {\n        port: 8080f64,\n        ..config.clone()\n    }")
+        );
+        assert!(rust_code.contains("counts.extend([(String::from(\"extra\"), 1f64)]);"));
+        assert!(rust_code.contains("let frozen = vec![1f64, 2f64];"));
+        assert!(rust_code.contains("let settings = config.clone();"));
+        assert!(!rust_code.contains("limits;"));
+        assert!(rust_code.contains("let mut copy = config.clone();"));
+    }
+
+    #[test]
+    fn test_typed_json() {
+        let rust_code = transpile(
+            "typed_json",
+            r#"
+            interface User { name: string; age: number; }
+            function load(raw: string): string {
+                const user: User = JSON.parse(raw);
+                const other = JSON.parse<User>(raw);
+                const cast = JSON.parse(raw) as User;
+                const pretty = JSON.stringify(user, null, 2);
+                const tabbed = JSON.stringify(other, ["name"], "\t");
+                return pretty + tabbed;
+            }
+            async function fetchUser(raw: string): Promise<User> {
+                const user: User = JSON.parse(raw);
+                return user;
+            }
+        "#,
+        );
+
+        // Async code is emitted unformatted, so compare without whitespace
+        let compact: String = rust_code.split_whitespace().collect();
+        assert!(compact.contains("letmutuser=serde_json::from_str::<User>(&raw).unwrap();"));
+        assert!(compact.contains("letmutuser=serde_json::from_str::<User>(&raw)?;"));
+        assert_eq!(compact.matches("from_str::<User>").count(), 4);
+        assert!(compact.contains(
+            "serde_json::to_string_pretty(&typerust_runtime::json_value(&user)).unwrap()"
+        ));
+        assert!(compact.contains("retain_keys(&mutvalue,&[\"name\"])"));
+        assert!(compact.contains("PrettyFormatter::with_indent(b\"\\t\")"));
+    }
+
+    #[test]
+    fn test_promises_and_timers() {
+        let rust_code = transpile(
+            "promises_and_timers",
+            r#"
+            async function fetchCount(): Promise<number> {
+                return 3;
+            }
+            function sleep(ms: number): Promise<void> {
+                return new Promise(resolve => setTimeout(resolve, ms));
+            }
+            async function load(ids: number[]): Promise<number> {
+                const [a, b] = await Promise.all([fetchCount(), sleep(10)]);
+                const counts = await Promise.all(ids.map(id => fetchCount()));
+                const first = await Promise.race([fetchCount(), new Promise((_, reject) => setTimeout(() => reject(new Error("timeout")), 5000))]);
+                const fixed = await Promise.resolve(5);
+                const ticker = setInterval(async () => { await sleep(1); }, 500);
+                clearInterval(ticker);
+                if (a > 10) {
+                    return Promise.reject(new Error("too many"));
+                }
+                return fetchCount();
+            }
+        "#,
+        );
+
+        let compact: String = rust_code.split_whitespace().collect();
+        assert!(compact.contains("asyncfnsleep(ms:f64)->Result<(),crate::AppError>"));
+        assert!(compact
+            .contains("tokio::time::sleep(std::time::Duration::from_millis((ms)asu64)).await;"));
+        assert!(compact.contains("let(muta,mutb)=futures::future::try_join("));
+        assert!(compact.contains("futures::future::try_join_all(ids"));
+        assert!(compact.contains("tokio::select!{result="));
+        assert!(compact.contains("letmutfixed=5f64;"));
+        assert!(compact.contains("interval.tick().await;"));
+        assert!(compact.contains("ticker.abort();"));
+        assert!(compact.contains("move||asyncmove{sleep(1f64).await?;"));
+        assert!(compact.contains("returnErr(crate::AppError::Error(String::from(\"toomany\")));"));
+        assert!(compact.contains("returnfetch_count().await;"));
+    }
+
+    #[test]
+    fn test_error_classes_and_try_catch() {
+        let rust_code = transpile(
+            "error_classes",
+            r#"
+            class NotFoundError extends Error {
+                constructor(public resource: string) {
+                    super(`${resource} not found`);
+                    this.name = "NotFoundError";
+                }
+            }
+            async function find(id: number): Promise<string> {
+                if (id > 10) {
+                    throw new NotFoundError("user");
+                }
+                if (id < 0) {
+                    throw new Error("negative id");
+                }
+                return "alice";
+            }
+            async function describe(id: number): Promise<string> {
+                try {
+                    return await find(id);
+                } catch (err) {
+                    if (err instanceof NotFoundError) {
+                        return "missing " + err.resource;
+                    } else {
+                        throw err;
+                    }
+                }
+            }
+        "#,
+        );
+
+        let compact: String = rust_code.split_whitespace().collect();
+        assert!(compact.contains("//`NotFoundError`isavariantof`crate::AppError`"));
+        assert!(!compact.contains("structNotFoundError"));
+        assert!(compact
+            .contains("returnErr(crate::AppError::not_found_error(String::from(\"user\")));"));
+        assert!(
+            compact.contains("returnErr(crate::AppError::Error(String::from(\"negativeid\")));")
+        );
+        assert!(compact.contains("letoutcome=async{returnOk(Some(find(id).await?));"));
+        assert!(compact.contains("Err(err)=>async{match&err{crate::AppError::NotFoundError{..}=>"));
+        assert!(compact.contains(
+            "match&err{crate::AppError::NotFoundError{resource,..}=>resource.clone(),_=>unreachable!(),}"
+        ));
+        assert!(compact.contains("_=>{returnErr(err);}"));
+        assert!(compact
+            .contains("matchoutcome?{Some(returned)=>{returnOk(returned);}None=>unreachable!(),}"));
+    }
+
+    #[test]
+    fn test_error_subclass_instanceof() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let input_dir = temp_dir.path().join("input");
+        let output_dir = temp_dir.path().join("output");
+        std::fs::create_dir_all(&input_dir).unwrap();
+        std::fs::write(
+            input_dir.join("errors.ts"),
+            r#"
+            export class ValidationError extends Error {
+                constructor(public field: string, message: string) {
+                    super(message);
+                }
+            }
+        "#,
+        )
+        .unwrap();
+        std::fs::write(
+            input_dir.join("signup.ts"),
+            r#"
+            import { ValidationError } from "./errors";
+            export class BadEmailError extends ValidationError {
+                constructor() {
+                    super("email", "bad email");
+                }
+            }
+            async function check(email: string): Promise<void> {
+                if (!email.includes("@")) {
+                    throw new BadEmailError();
+                }
+            }
+            export async function handle(email: string): Promise<string> {
+                try {
+                    await check(email);
+                    return "200";
+                } catch (err) {
+                    if (err instanceof ValidationError) {
+                        return "400 " + err.field + " " + err.message;
+                    }
+                    return "500";
+                }
+            }
+        "#,
+        )
+        .unwrap();
+
+        ox_orchestrator::build_project(input_dir, output_dir.clone()).expect("Build failed");
+
+        let module = std::fs::read_to_string(output_dir.join("src/signup.rs")).unwrap();
+        println!("Generated Rust code:\n{}", module);
+        let compact: String = module.split_whitespace().collect();
+        assert!(compact.contains(
+            "match&err{crate::AppError::ValidationError{..}|crate::AppError::BadEmailError{..}=>"
+        ));
+        assert!(compact.contains(
+            "match&err{crate::AppError::ValidationError{field,..}|crate::AppError::BadEmailError{field,..}=>field.clone(),_=>unreachable!(),}"
+        ));
+    }
+
+    #[test]
+    fn test_crypto_and_buffers() {
+        let rust_code = transpile(
+            "crypto_buffers",
+            r#"
+            import crypto from "crypto";
+            import { createHmac } from "crypto";
+            function sign(payload: string, secret: string): string {
+                const id = crypto.randomUUID();
+                const hash = crypto.createHash("sha256").update(id).update(payload).digest("hex");
+                return hash + createHmac("sha256", secret).update(payload).digest("base64");
+            }
+            function decode(header: string): string {
+                const raw = Buffer.from(header, "base64");
+                const encoded = new TextEncoder().encode(raw.toString());
+                const token = btoa("user:pass");
+                return Buffer.from(encoded).toString("hex") + token;
+            }
+        "#,
+        );
+
+        let compact: String = rust_code.split_whitespace().collect();
+        assert!(compact.contains("letmutid=uuid::Uuid::new_v4().to_string();"));
+        assert!(compact.contains(
+            "usesha2::Digest;letdigest=sha2::Sha256::new().chain_update(&id).chain_update(&payload).finalize();hex::encode(&digest)"
+        ));
+        assert!(compact.contains(
+            "letdigest=<hmac::Hmac<sha2::Sha256>>::new_from_slice(secret.as_ref()).expect(\"HMACacceptskeysofanylength\").chain_update(&payload).finalize().into_bytes();"
+        ));
+        assert!(compact.contains(
+            "letmutraw=base64::Engine::decode(&base64::engine::general_purpose::STANDARD,&header).unwrap_or_default();"
+        ));
+        assert!(compact.contains(
+            "letmutencoded=String::from_utf8_lossy(&raw).into_owned().as_bytes().to_vec();"
+        ));
+        assert!(compact.contains("hex::encode(&encoded.clone())"));
+        assert!(compact.contains(
+            "letmuttoken=base64::Engine::encode(&base64::engine::general_purpose::STANDARD,&\"user:pass\".as_bytes()"
+        ));
+        assert!(compact.contains("returnhash+&{usehmac::Mac;"));
+    }
+
+    #[test]
+    fn test_url_and_search_params() {
+        let rust_code = transpile(
+            "url_search_params",
+            r#"
+            function buildUrl(path: string, base: string, page: number): string {
+                const url = new URL(path, base);
+                url.searchParams.append("page", page);
+                url.searchParams.set("limit", "10");
+                url.pathname = "/users";
+                return url.pathname + url.search + url.toString();
+            }
+            function query(): string {
+                const params = new URLSearchParams({ q: "rust", sort: "asc" });
+                params.append("page", "2");
+                const other = new URLSearchParams("a=1&b=2");
+                const first = other.get("a") ?? "";
+                other.set("a", "x");
+                return params.toString() + first + other.toString();
+            }
+        "#,
+        );
+
+        let compact: String = rust_code.split_whitespace().collect();
+        assert!(compact.contains(
+            "letmuturl=url::Url::parse(&base).and_then(|base|base.join(&path)).expect(\"InvalidURL\");"
+        ));
+        assert!(compact.contains("url.query_pairs_mut().append_pair(\"page\",&page.to_string());"));
+        assert!(compact.contains(
+            "typerust_runtime::search_params_set(&mutpairs,\"limit\",String::from(\"10\"));url.query_pairs_mut().clear().extend_pairs(pairs);"
+        ));
+        assert!(compact.contains("url.set_path(&\"/users\");"));
+        assert!(compact.contains("returnurl.path().to_string()+&url.query()"));
+        assert!(compact.contains(
+            "letmutparams=vec![(String::from(\"q\"),String::from(\"rust\")),(String::from(\"sort\"),String::from(\"asc\")),];"
+        ));
+        assert!(compact.contains(
+            "url::form_urlencoded::parse(\"a=1&b=2\".trim_start_matches('?').as_bytes())"
+        ));
+        assert!(compact.contains(
+            "url::form_urlencoded::Serializer::new(String::new()).extend_pairs(&params).finish()+&typerust_runtime::ToJsString::to_js_string(&(first))"
+        ));
+        assert!(compact
+            .contains("typerust_runtime::search_params_set(&mutother,\"a\",String::from(\"x\"));"));
+    }
+
+    #[test]
+    fn test_event_emitter_subclass() {
+        let rust_code = transpile(
+            "event_emitter",
+            r#"
+            import { EventEmitter } from "events";
+            interface Order { id: string; total: number; }
+            export class OrderService extends EventEmitter {
+                place(order: Order): void {
+                    this.emit("orderPlaced", order);
+                }
+                cancel(id: string, reason: string): boolean {
+                    return this.emit("orderCancelled", id, reason);
+                }
+                close(): void {
+                    this.emit("closed");
+                }
+            }
+            function audit(id: string, reason: string): void {
+                console.log(id, reason);
+            }
+            function watch(orders: OrderService): void {
+                orders.on("orderPlaced", (order) => {
+                    console.log(order.id);
+                });
+                orders.once("closed", () => console.log("closed"));
+                orders.on("orderCancelled", audit);
+                orders.off("orderCancelled", audit);
+            }
+        "#,
+        );
+
+        let compact: String = rust_code.split_whitespace().collect();
+        assert!(compact.contains(
+            "pubenumOrderServiceEvent{OrderPlaced(Order),OrderCancelled(String,String),Closed,}"
+        ));
+        assert!(compact.contains("Self::OrderPlaced(..)=>\"orderPlaced\""));
+        assert!(
+            compact.contains("#[serde(skip)]pubevents:crate::events::EventBus<OrderServiceEvent>")
+        );
+        assert!(
+            compact.contains("self.events.emit(OrderServiceEvent::OrderPlaced(order.clone()));")
+        );
+        assert!(compact.contains("returnself.events.emit(OrderServiceEvent::OrderCancelled(id.clone(),reason.clone(),));"));
+        assert!(compact.contains(
+            "orders.events.on(\"orderPlaced\",None,Box::new(move|event:&OrderServiceEvent|{ifletOrderServiceEvent::OrderPlaced(order)=event.clone(){"
+        ));
+        assert!(compact.contains("orders.events.once(\"closed\",None,"));
+        assert!(compact.contains(
+            "orders.events.on(\"orderCancelled\",Some(\"audit\"),Box::new(move|event:&OrderServiceEvent|{ifletOrderServiceEvent::OrderCancelled(arg0,arg1)=event.clone(){audit(arg0,arg1);}}),);"
+        ));
+        assert!(compact.contains("orders.events.off(\"orderCancelled\",\"audit\");"));
+        assert!(!rust_code.contains("use events"));
+    }
+
+    #[test]
+    fn test_event_emitter_across_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let input_dir = temp_dir.path().join("input");
+        let output_dir = temp_dir.path().join("output");
+        std::fs::create_dir_all(&input_dir).unwrap();
+        std::fs::write(
+            input_dir.join("orders.ts"),
+            r#"
+            import { EventEmitter } from "events";
+            export class OrderService extends EventEmitter {
+                close(): void {
+                    this.emit("closed");
+                }
+            }
+        "#,
+        )
+        .unwrap();
+        std::fs::write(
+            input_dir.join("audit.ts"),
+            r#"
+            import { OrderService } from "./orders";
+            export class AuditedOrders extends OrderService {
+                audit(id: string): void {
+                    this.emit("audited", id);
+                }
+            }
+            export function watch(orders: OrderService): void {
+                orders.on("closed", () => console.log("closed"));
+            }
+        "#,
+        )
+        .unwrap();
+
+        ox_orchestrator::build_project(input_dir, output_dir.clone()).expect("Build failed");
+
+        let module = std::fs::read_to_string(output_dir.join("src/audit.rs")).unwrap();
+        println!("Generated Rust code:\n{}", module);
+        let compact: String = module.split_whitespace().collect();
+        assert!(compact.contains("usesuper::orders::OrderService;"));
+        assert!(compact.contains("usesuper::orders::OrderServiceEvent;"));
+        assert!(compact.contains("pubenumAuditedOrdersEvent{Closed,Audited(String),}"));
+        assert!(compact.contains("pubevents:crate::events::EventBus<AuditedOrdersEvent>"));
+        assert!(compact.contains("orders.events.on(\"closed\",None,"));
+    }
+
+    #[test]
+    fn test_mapping_files() {
+        let dir = std::env::temp_dir().join("expr_mapping_files");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("lodash.typerust.toml"),
+            r#"
+            module = "lodash"
+
+            [dependencies]
+            itertools = "0.13"
+
+            [[mapping]]
+            function = "chunk"
+            rust = "$0.chunks($1 as usize).map(|c| c.to_vec()).collect::<Vec<_>>()"
+        "#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("acme.typerust.json"),
+            r#"{ "mapping": [{ "function": "Acme.slug", "rust": "$0.to_lowercase().replace(' ', \"-\")" }] }"#,
+        )
+        .unwrap();
+        let ts_file = dir.join("mapped.ts");
+        std::fs::write(
+            &ts_file,
+            r#"
+            import _ from "lodash";
+            import { chunk as split } from "lodash";
+            function pages(items: number[], size: number): number[][] {
+                const first = _.chunk(items, size);
+                return split(items, size + 1);
+            }
+            function slug(title: string): string {
+                return Acme.slug(title);
+            }
+        "#,
+        )
+        .unwrap();
+
+        let rust_code =
+            ox_orchestrator::build(FilePath::from(ts_file)).expect("Failed to generate Rust code");
+        println!("Generated Rust code:\n{}", rust_code);
+
+        let compact: String = rust_code.split_whitespace().collect();
+        assert!(!rust_code.contains("use lodash"));
+        assert!(compact.contains(
+            "letmutfirst=items.chunks(sizeasusize).map(|c|c.to_vec()).collect::<Vec<_>>();"
+        ));
+        assert!(compact.contains(
+            "returnitems.chunks((size+1f64)asusize).map(|c|c.to_vec()).collect::<Vec<_>>();"
+        ));
+        assert!(compact.contains("returntitle.to_lowercase().replace('',\"-\");"));
+    }
+
+    #[test]
+    fn test_custom_stdlib_mapping() {
+        struct Acme;
+        impl ox_codegen::stdlib::registry::StdlibMapping for Acme {
+            fn handles_module(&self, module: &str) -> bool {
+                module == "@acme/utils"
+            }
+            fn map_call(
+                &self,
+                call: &ox_codegen::stdlib::registry::MappedCall<'_>,
+            ) -> Option<proc_macro2::TokenStream> {
+                let value = call.arg(0)?;
+                match (call.module, call.function) {
+                    (Some("@acme/utils"), "isBlank") => {
+                        Some(quote::quote! { #value.trim().is_empty() })
+                    }
+                    _ => None,
+                }
+            }
+        }
+
+        let ts_file = std::env::temp_dir().join("expr_custom_mapping.ts");
+        std::fs::write(
+            &ts_file,
+            r#"
+            import { isBlank } from "@acme/utils";
+            function check(name: string): boolean {
+                return isBlank(name);
+            }
+        "#,
+        )
+        .unwrap();
+        let mut options = ox_orchestrator::Options::default();
+        options.mappings.register(Acme);
+        let rust_code = ox_orchestrator::build_with_options(FilePath::from(ts_file), &options)
+            .expect("Failed to generate Rust code");
+        println!("Generated Rust code:\n{}", rust_code);
+
+        assert!(!rust_code.contains("use @acme"));
+        assert!(rust_code.contains("return name.trim().is_empty();"));
+    }
+
+    #[test]
+    fn test_console_to_tracing() {
+        let ts_file = std::env::temp_dir().join("expr_console_tracing.ts");
+        std::fs::write(
+            &ts_file,
+            r#"
+            interface Item { name: string; qty: number; }
+            function audit(items: Item[], first: Item): void {
+                console.log("first:", first);
+                console.warn("%o", items);
+                console.error("failed", 3);
+                console.table(items);
+            }
+        "#,
+        )
+        .unwrap();
+
+        let options = ox_orchestrator::Options {
+            console_tracing: true,
+            ..Default::default()
+        };
+        let rust_code = ox_orchestrator::build_with_options(FilePath::from(ts_file), &options)
+            .expect("Failed to generate Rust code");
+        println!("Generated Rust code:\n{}", rust_code);
+
+        let compact: String = rust_code.split_whitespace().collect();
+        assert!(compact.contains(
+            "tracing::info!(\"first:{}\",serde_json::to_string(&typerust_runtime::json_value(&first)).unwrap_or_default())"
+        ));
+        assert!(compact.contains(
+            "tracing::warn!(\"{}\",serde_json::to_string(&typerust_runtime::json_value(&items)).unwrap_or_default())"
+        ));
+        assert!(compact.contains("tracing::error!(\"failed{}\",3f64)"));
+        assert!(compact.contains("letmuttable=String::from(\"(index)\");"));
+        assert!(!compact.contains("println!"));
+    }
+
+    #[test]
+    fn test_method_dispatch_by_receiver_type() {
+        let rust_code = transpile(
+            "receiver_dispatch",
+            r#"
+            class Money {
+                constructor(private cents: number) {}
+                toString(): string {
+                    return "$" + (this.cents / 100).toFixed(2);
+                }
+            }
+            class Basket {
+                tags: string[] = [];
+                hasTag(tag: string): boolean {
+                    return this.tags.includes(tag);
+                }
+            }
+            function names(): string[] {
+                return ["a", "b"];
+            }
+            function check(word: string, price: Money, count: number): boolean {
+                const shown = price.toString();
+                const n = count.toString();
+                return word.includes("x") && names().indexOf(word) >= 0;
+            }
+        "#,
+        );
+        let compact: String = rust_code.split_whitespace().collect();
+
+        // Array field and array-returning function go to the array handler
+        assert!(compact.contains("self.tags.clone().iter().any(|x|*x==tag)"));
+        assert!(compact.contains("names().iter().position(|x|*x==word)"));
+        // A string parameter goes to the string handler
+        assert!(compact.contains("word.contains(\"x\")"));
+        // The class's own method, and a number's formatting
+        assert!(compact.contains("letmutshown=price.to_string();"));
+        assert!(compact.contains("letmutn=typerust_runtime::js_to_string(count);"));
+        assert!(compact.contains("letx:f64=(self.cents.clone()/100f64);"));
+    }
+
+    #[test]
+    fn test_method_dispatch_by_inferred_type() {
+        let rust_code = transpile(
+            "receiver_inferred",
+            r#"
+            const PREFIX = "id-";
+            function last(): number {
+                const arr = [3, 1, 2];
+                const tail = arr.slice(-2);
+                return arr.at(-1) ?? tail[0];
+            }
+            function word(): string {
+                const s = "hello";
+                const n = 42;
+                return s.slice(-3) + n.toFixed(1) + PREFIX.at(0);
+            }
+            function rest(...xs: number[]): number[] {
+                return xs.slice(1);
+            }
+        "#,
+        );
+        let compact: String = rust_code.split_whitespace().collect();
+
+        // Literal arrays, rest parameters and strings are routed by their type
+        assert!(compact.contains("letmuttail={letitems=&arr;"));
+        assert!(compact.contains("return{letitems=&arr;"));
+        assert!(compact.contains("items.get(indexasusize).cloned()"));
+        assert!(compact.contains("return{letitems=&xs;"));
+        assert!(compact.contains("letunits:Vec<u16>=s.encode_utf16().collect();"));
+        assert!(compact.contains("letunits:Vec<u16>=PREFIX.encode_utf16().collect();"));
+        assert!(compact.contains("letx:f64=n;"));
+        assert!(!compact.contains("arr.encode_utf16()"));
+        assert!(!compact.contains("xs.encode_utf16()"));
+
+        // In index.ts, top-level bindings keep their types inside functions
+        let ts_file = std::env::temp_dir().join("index_receiver_inferred.ts");
+        std::fs::write(
+            &ts_file,
+            r#"
+            const ITEMS: number[] = [3, 1, 2];
+            const TAGS = ["x", "y"];
+            function tail(): number[] {
+                return ITEMS.slice(1);
+            }
+            function hasTag(t: string): boolean {
+                return TAGS.includes(t);
+            }
+            console.log(tail(), hasTag("x"));
+        "#,
+        )
+        .unwrap();
+        let generated = ox_codegen::generate(&ox_parser::parse(&ts_file).unwrap(), true);
+        let compact: String = generated.code.split_whitespace().collect();
+        assert!(compact.contains("letitems=&ITEMS;"));
+        assert!(compact.contains("TAGS.iter().any(|x|*x==t)"));
+        assert!(!generated
+            .diagnostics
+            .iter()
+            .any(|d| d.contains("has no known type")));
+    }
+
+    #[test]
+    fn test_lodash_mapping() {
+        let ts_file = std::env::temp_dir().join("expr_lodash.ts");
+        std::fs::write(
+            &ts_file,
+            r#"
+            import _ from "lodash";
+            import { groupBy, uniq, chunk, pick, zip } from "lodash";
+            import debounce from "lodash/debounce";
+            interface User { name: string; dept: string; }
+            function report(users: User[], ids: number[]): void {
+                const byDept = groupBy(users, u => u.dept);
+                const unique = uniq(ids);
+                const pages = chunk(ids, 2);
+                const sorted = _.sortBy(users, "name");
+                const slim = pick(users[0], ["name"]);
+                const pairs = zip(ids, ids);
+                const save = debounce(() => console.log("saved"), 100);
+            }
+        "#,
+        )
+        .unwrap();
+        let generated = ox_codegen::generate(&ox_parser::parse(&ts_file).unwrap(), false);
+        let compact: String = generated.code.split_whitespace().collect();
+        println!("Generated Rust code:\n{}", generated.code);
+
+        assert!(!compact.contains("uselodash"));
+        assert!(compact.contains("itertools::Itertools::into_group_map_by(users.iter().cloned(),|item|{letu=item.clone();u.dept}.to_string())"));
+        assert!(compact.contains("if!unique.contains(item){unique.push(item.clone());}"));
+        assert!(compact.contains("ids.chunks((2f64asusize).max(1))"));
+        assert!(compact.contains("left.name.clone().partial_cmp(&right.name.clone())"));
+        assert!(compact.contains("letkeys=[\"name\"];"));
+        assert!(compact.contains("crate::lodash::debounce(move|()|"));
+        assert_eq!(generated.dependencies["itertools"], "\"0.13\"");
+        assert!(generated.support.contains("lodash"));
+        assert!(generated
+            .diagnostics
+            .iter()
+            .any(|d| d.contains("lodash functions without a Rust translation: `zip`")));
+    }
+
+    #[test]
+    fn test_nullish_coalescing_only_on_options() {
+        let ts_file = std::env::temp_dir().join("expr_nullish.ts");
+        std::fs::write(
+            &ts_file,
+            r#"
+            interface Config { host: string; port?: number; }
+            function describe(config: Config, name?: string, tags: string[] = []): string {
+                const port = config.port ?? 80;
+                const who = name ?? "anonymous";
+                const host = config.host ?? "localhost";
+                const first = tags.find((t) => t.length > 0) ?? "none";
+                return `${who}@${host}:${port} ${first}`;
+            }
+        "#,
+        )
+        .unwrap();
+        let generated = ox_codegen::generate(&ox_parser::parse(&ts_file).unwrap(), false);
+        let compact: String = generated.code.split_whitespace().collect();
+        println!("Generated Rust code:\n{}", generated.code);
+
+        assert!(compact.contains("letmutport=config.port.unwrap_or_else(||80f64);"));
+        assert!(compact.contains("letmutwho=name.unwrap_or_else(||String::from(\"anonymous\"));"));
+        assert!(compact.contains("letmuthost=config.host;"));
+        assert!(compact.contains(".unwrap_or_else(||String::from(\"none\"));"));
+        assert!(generated
+            .diagnostics
+            .iter()
+            .any(|d| d.contains("the left side of `??` is never null or undefined")));
+    }
+
+    #[test]
+    fn test_runtime_crate_for_js_semantics() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let input_dir = temp_dir.path().join("input");
+        let output_dir = temp_dir.path().join("output");
+        std::fs::create_dir_all(&input_dir).unwrap();
+        std::fs::write(
+            input_dir.join("semantics.ts"),
+            r#"
+            interface User { name: string; }
+            export function run(count: number, label: string, ids: number[], user: User | undefined): string {
+                if (label && !ids.length) { return "empty"; }
+                if (user) { return user.name; }
+                if (label == count) { return "same"; }
+                ids.sort();
+                return "n=" + count + JSON.stringify({ count });
+            }
+        "#,
+        )
+        .unwrap();
+
+        ox_orchestrator::build_project(input_dir, output_dir.clone()).expect("Build failed");
+
+        let module = std::fs::read_to_string(output_dir.join("src/semantics.rs")).unwrap();
+        let compact: String = module.split_whitespace().collect();
+        assert!(compact.contains(
+            "iftyperust_runtime::truthy(&label)&&!typerust_runtime::truthy(&(ids.len()asf64))"
+        ));
+        assert!(compact.contains("ifuser.is_some()"));
+        assert!(compact.contains("iftyperust_runtime::loose_eq(&label,&count)"));
+        assert!(compact.contains("ids.sort_by(typerust_runtime::compare_default)"));
+        assert!(compact.contains("String::from(\"n=\")+&typerust_runtime::js_to_string(count)"));
+        assert!(compact.contains("serde_json::to_string(&typerust_runtime::json_value("));
+
+        let cargo_toml = std::fs::read_to_string(output_dir.join("Cargo.toml")).unwrap();
+        assert!(cargo_toml.contains("typerust_runtime = { path = \"typerust_runtime\" }"));
+        assert!(cargo_toml
+            .contains("serde_json = { version = \"1.0\", features = [\"preserve_order\"] }"));
+        assert!(output_dir.join("typerust_runtime/Cargo.toml").exists());
+        assert!(output_dir.join("typerust_runtime/src/lib.rs").exists());
+    }
+}
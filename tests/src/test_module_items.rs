@@ -0,0 +1,191 @@
+#[cfg(test)]
+mod module_item_tests {
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Builds a one-file project and returns the generated module
+    fn build_module(temp_dir: &TempDir, file_name: &str, ts_code: &str) -> String {
+        let input_dir = temp_dir.path().join("input");
+        let output_dir = temp_dir.path().join("output");
+        std::fs::create_dir_all(&input_dir).unwrap();
+        std::fs::write(input_dir.join(format!("{}.ts", file_name)), ts_code).unwrap();
+
+        ox_orchestrator::build_project(input_dir, output_dir.clone()).expect("Build failed");
+
+        let module_path = Path::new(&output_dir)
+            .join("src")
+            .join(format!("{}.rs", file_name));
+        let rust_code = std::fs::read_to_string(module_path).unwrap();
+        println!("Generated Rust code:\n{}", rust_code);
+        rust_code
+    }
+
+    #[test]
+    fn test_module_constants() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_code = build_module(
+            &temp_dir,
+            "config",
+            r#"
+            export const API_URL = 'https://api.example.com';
+            const DEFAULT_LIMIT = 50;
+            export const RETRY_DELAYS: number[] = [100, 200];
+            export let requestCount = 0;
+
+            export function nextRequest(): number {
+                requestCount++;
+                return requestCount + DEFAULT_LIMIT;
+            }
+        "#,
+        );
+
+        assert!(rust_code.contains("pub const API_URL: &str = \"https://api.example.com\";"));
+        assert!(rust_code.contains("const DEFAULT_LIMIT: f64 = 50f64;"));
+        assert!(rust_code.contains("pub static RETRY_DELAYS: std::sync::LazyLock<Vec<f64>>"));
+        assert!(rust_code
+            .contains("pub static request_count: std::sync::LazyLock<std::sync::Mutex<f64>>"));
+        assert!(rust_code.contains("*request_count.lock().unwrap() += 1.0;"));
+    }
+
+    #[test]
+    fn test_imported_module_statics() {
+        let temp_dir = TempDir::new().unwrap();
+        let input_dir = temp_dir.path().join("input");
+        let output_dir = temp_dir.path().join("output");
+        std::fs::create_dir_all(&input_dir).unwrap();
+        std::fs::write(
+            input_dir.join("state.ts"),
+            r#"
+            export let requestCount = 0;
+            export let queue: string[] = [];
+            export const LIMIT = 10;
+            export function track(): void {
+                requestCount++;
+            }
+        "#,
+        )
+        .unwrap();
+        std::fs::write(
+            input_dir.join("report.ts"),
+            r#"
+            import { requestCount, queue as pending, LIMIT } from "./state";
+            export function enqueue(job: string): number {
+                pending.push(job);
+                return requestCount + LIMIT;
+            }
+        "#,
+        )
+        .unwrap();
+
+        ox_orchestrator::build_project(input_dir, output_dir.clone()).expect("Build failed");
+
+        let rust_code =
+            std::fs::read_to_string(Path::new(&output_dir).join("src/report.rs")).unwrap();
+        println!("Generated Rust code:\n{}", rust_code);
+        // An imported `let` is accessed through its lock, as in its own file
+        assert!(rust_code.contains("(*pending.lock().unwrap()).push(job);"));
+        assert!(rust_code.contains("let value = (*request_count.lock().unwrap()).clone();"));
+        assert!(!rust_code.contains("LIMIT.lock()"));
+    }
+
+    #[test]
+    fn test_namespaces_and_ambient_declarations() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_code = build_module(
+            &temp_dir,
+            "billing",
+            r#"
+            export namespace Billing {
+                export interface Invoice { total: number; }
+                export const TAX = 0.2;
+                export function charge(amount: number): number {
+                    return amount + amount * TAX;
+                }
+                function round(amount: number): number {
+                    return amount;
+                }
+            }
+            export namespace Billing {
+                export function refund(amount: number): number {
+                    return -amount;
+                }
+            }
+            export namespace Shop.Cart {
+                export const LIMIT = 10;
+            }
+            export namespace Shop.Cart {
+                export function isFull(count: number): boolean {
+                    return count >= LIMIT;
+                }
+            }
+            declare function nativeHash(input: string): string;
+            declare module 'legacy-lib' {
+                export function legacy(): void;
+            }
+            export function total(invoice: Billing.Invoice): number {
+                return Billing.charge(invoice.total);
+            }
+        "#,
+        );
+
+        assert!(rust_code.contains("pub mod billing {"));
+        assert!(rust_code.contains("pub fn charge(amount: f64) -> f64"));
+        assert!(rust_code.contains("    fn round(amount: f64) -> f64"));
+        // Declarations of the same namespace merge into one module
+        assert_eq!(rust_code.matches("mod billing {").count(), 1);
+        assert_eq!(rust_code.matches("mod cart {").count(), 1);
+        let billing = &rust_code[rust_code.find("mod billing {").unwrap()..];
+        assert!(billing.find("pub fn refund").unwrap() < billing.find("pub mod shop").unwrap());
+        assert!(rust_code.contains("pub fn is_full(count: f64) -> bool"));
+        assert!(rust_code.contains("pub fn total(invoice: billing::Invoice) -> f64"));
+        assert!(rust_code.contains("billing::charge(invoice.total)"));
+        assert!(rust_code.contains("todo!(\"`nativeHash` is an ambient declaration"));
+        assert!(!rust_code.contains("fn legacy"));
+    }
+
+    #[test]
+    fn test_dates_use_chrono() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_code = build_module(
+            &temp_dir,
+            "orders",
+            r#"
+            export interface Order { id: string; createdAt: Date; }
+            export function ageInDays(order: Order, now: Date): number {
+                const elapsed = now.getTime() - order.createdAt.getTime();
+                return elapsed / 86400000;
+            }
+            export function isExpired(order: Order): boolean {
+                const deadline = new Date(order.createdAt.getTime() + 3600000);
+                return deadline < new Date();
+            }
+            export function stamp(iso: string): string {
+                const d = new Date(iso);
+                d.setHours(0);
+                const span = new Date() - d;
+                return `${d.toISOString()} ${d.getFullYear()} ${Date.now()} ${span}`;
+            }
+            export function nextDay(d: Date): number {
+                return d.setDate(d.getDate() + 1);
+            }
+        "#,
+        );
+
+        assert!(rust_code.contains("pub createdAt: chrono::DateTime<chrono::Utc>"));
+        assert!(rust_code.contains("now: chrono::DateTime<chrono::Utc>"));
+        assert!(rust_code.contains("(now.timestamp_millis() as f64)"));
+        assert!(rust_code.contains("chrono::DateTime::from_timestamp_millis("));
+        assert!(rust_code.contains("chrono::DateTime::parse_from_rfc3339(text)"));
+        // Setters rebuild the date so out-of-range values roll over as in JS
+        assert!(rust_code.contains("let date = d;"));
+        assert!(rust_code.contains("+ chrono::Duration::milliseconds(offset as i64)"));
+        assert!(!rust_code.contains("with_hour"));
+        assert!(rust_code.contains("pub fn next_day(mut d: chrono::DateTime<chrono::Utc>) -> f64"));
+        assert!(rust_code.contains("d.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)"));
+        assert!(rust_code.contains("chrono::Datelike::year(&d)"));
+
+        let cargo_toml =
+            std::fs::read_to_string(temp_dir.path().join("output").join("Cargo.toml")).unwrap();
+        assert!(cargo_toml.contains("chrono = { version = \"0.4\", features = [\"serde\"] }"));
+    }
+}
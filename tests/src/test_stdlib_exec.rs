@@ -49,6 +49,12 @@ fn main() {{
                 const upper = input.toUpperCase();
                 return upper;
             }
+            function trimmed(input: string): string {
+                const both = input.trim();
+                const start = input.trimStart();
+                const end = input.trimEnd();
+                return both + "|" + start + "|" + end;
+            }
         "#;
 
         let temp_dir = std::env::temp_dir();
@@ -68,6 +74,7 @@ fn main() {{
     let result = test_string("hello".to_string());
     println!("testString('hello') = {{}}", result);
     assert_eq!(result, "HELLO", "Expected uppercase");
+    assert_eq!(trimmed(" a ".to_string()), "a|a | a");
     println!("✅ String stdlib test passed!");
 }}
 "#,
@@ -77,6 +84,152 @@ fn main() {{
         execute_rust_program(&program, "String stdlib");
     }
 
+    #[test]
+    fn test_stdlib_string_utf16_execution() {
+        let ts_code = r#"
+            function testStringIndices(input: string): string {
+                const first = input.replace("l", "L");
+                const all = input.replaceAll("l", "L");
+                const tail = input.slice(-3);
+                const middle = input.substring(4, 1);
+                const code = input.charCodeAt(1);
+                const pos = input.indexOf("o");
+                const last = input.lastIndexOf("l");
+                const padded = "7".padStart(3, "0");
+                const parts = "a,b,c".split(",", 2);
+                const starts = input.startsWith("hé") && input.endsWith("😀");
+                return `${first}|${all}|${tail}|${middle}|${code}|${pos}|${last}|${padded}|${parts.join("+")}|${starts}|${input.length}|${input.charAt(2)}`;
+            }
+        "#;
+
+        let temp_dir = std::env::temp_dir();
+        let ts_file = temp_dir.join("stdlib_string_utf16_test.ts");
+        std::fs::write(&ts_file, ts_code).unwrap();
+
+        let rust_code =
+            ox_orchestrator::build(FilePath::from(ts_file)).expect("Failed to generate Rust code");
+
+        println!("Generated Rust code:\n{}", rust_code);
+
+        let program = format!(
+            r#"
+{}
+
+fn main() {{
+    let result = test_string_indices("héllo😀".to_string());
+    println!("testStringIndices = {{}}", result);
+    assert_eq!(result, "héLlo😀|héLLo😀|o😀|éll|233|4|3|007|a+b|true|7|l");
+    println!("✅ String index test passed!");
+}}
+"#,
+            rust_code
+        );
+
+        execute_rust_program(&program, "String indices");
+    }
+
+    #[test]
+    fn test_stdlib_number_execution() {
+        let ts_code = r#"
+            function testNumbers(flag: boolean): string {
+                const a = parseInt("  42px", 10);
+                const b = parseInt("0x1F");
+                const c = parseFloat("3.25e2abc");
+                const d = Number("  12.5 ");
+                const e = Number("12px");
+                const f = Number("");
+                const g = isNaN(parseInt("abc"));
+                const h = Number.isInteger(5.0) && !Number.isInteger(5.5);
+                const big = Number.MAX_SAFE_INTEGER;
+                const rounded = (2.5).toFixed(0) + "/" + (1.005).toFixed(2) + "/" + (-1.5).toFixed(0);
+                const hex = (255).toString(16);
+                const on = Number(flag);
+                const off = Number(!flag);
+                return `${a}|${b}|${c}|${d}|${isNaN(e)}|${f}|${g}|${h}|${big}|${rounded}|${hex}|${on}${off}`;
+            }
+        "#;
+
+        let temp_dir = std::env::temp_dir();
+        let ts_file = temp_dir.join("stdlib_number_test.ts");
+        std::fs::write(&ts_file, ts_code).unwrap();
+
+        let rust_code =
+            ox_orchestrator::build(FilePath::from(ts_file)).expect("Failed to generate Rust code");
+
+        println!("Generated Rust code:\n{}", rust_code);
+
+        let program = format!(
+            r#"
+{}
+
+fn main() {{
+    let result = test_numbers(true);
+    println!("testNumbers() = {{}}", result);
+    assert_eq!(result, "42|31|325|12.5|true|0|true|true|9007199254740991|3/1.00/-2|ff|10");
+    println!("✅ Number stdlib test passed!");
+}}
+"#,
+            rust_code
+        );
+
+        execute_rust_program(&program, "Number stdlib");
+    }
+
+    #[test]
+    fn test_stdlib_array_execution() {
+        let ts_code = r#"
+            function testArray(): number {
+                const nums: number[] = [3, 1, 2];
+                const total = nums.reduce((acc, n) => acc + n, 0);
+                const firstBig = nums.find((n) => n > 1);
+                const bigIndex = nums.findIndex((n) => n > 2);
+                const hasTwo = nums.includes(2);
+                const allPositive = nums.every((n) => n > 0);
+                const lastTwo = nums.slice(-2);
+                const sorted = nums.sort((a, b) => a - b);
+                const removed = nums.splice(0, 1);
+                nums.unshift(10);
+                const last = nums.at(-1);
+                const nested: number[][] = [[1], [2, 3]];
+                const flat = nested.flat();
+                const squares = Array.from({ length: 3 }, (_, i) => i * i);
+                let sum = 0;
+                squares.forEach((x) => {
+                    sum += x;
+                });
+                if (!hasTwo || !allPositive || firstBig === undefined) {
+                    return -1;
+                }
+                return total + bigIndex + lastTwo[0] + sorted[0] + removed[0] + nums[0] + flat[2] + sum;
+            }
+        "#;
+
+        let temp_dir = std::env::temp_dir();
+        let ts_file = temp_dir.join("stdlib_array_test.ts");
+        std::fs::write(&ts_file, ts_code).unwrap();
+
+        let rust_code =
+            ox_orchestrator::build(FilePath::from(ts_file)).expect("Failed to generate Rust code");
+
+        println!("Generated Rust code:\n{}", rust_code);
+
+        let program = format!(
+            r#"
+{}
+
+fn main() {{
+    let result = test_array();
+    println!("testArray() = {{}}", result);
+    assert_eq!(result, 27.0, "Expected 6 + 0 + 1 + 1 + 1 + 10 + 3 + 5 = 27");
+    println!("✅ Array stdlib test passed!");
+}}
+"#,
+            rust_code
+        );
+
+        execute_rust_program(&program, "Array stdlib");
+    }
+
     #[test]
     fn test_stdlib_console() {
         let ts_code = r#"
@@ -102,9 +255,222 @@ fn main() {{
         println!("✅ Console stdlib mapping verified!");
     }
 
+    #[test]
+    fn test_stdlib_console_formatting() {
+        let ts_code = r#"
+            function report(name: string, count: number): void {
+                console.time("report");
+                console.log("%s has %d items {braces}", name, count, "extra");
+                console.info("100%% done");
+                console.log("%d of %f", "42", "1.5");
+                console.warn("low stock:", count);
+                console.debug("%c styled", "color: red");
+                console.assert(count > 100, "expected more than %d", 100);
+                console.timeEnd("report");
+            }
+        "#;
+
+        let temp_dir = std::env::temp_dir();
+        let ts_file = temp_dir.join("stdlib_console_formatting_test.ts");
+        std::fs::write(&ts_file, ts_code).unwrap();
+
+        let rust_code =
+            ox_orchestrator::build(FilePath::from(ts_file)).expect("Failed to generate Rust code");
+
+        println!("Generated Rust code:\n{}", rust_code);
+
+        let compact: String = rust_code.split_whitespace().collect();
+        assert!(compact
+            .contains("println!(\"{}has{}items{{braces}}extra\",name,((count)asf64).trunc())"));
+        assert!(compact.contains("println!(\"100%done\")"));
+        assert!(
+            compact.contains("eprintln!(\"lowstock:{}\",typerust_runtime::js_to_string(count))")
+        );
+        assert!(compact.contains("eprintln!(\"Assertionfailed:expectedmorethan{}\""));
+        assert!(compact.contains("letconsole_timer_report=std::time::Instant::now();"));
+
+        let program = format!(
+            r#"
+{}
+
+fn main() {{
+    report("widgets".to_string(), 3.7);
+    println!("✅ Console formatting test passed!");
+}}
+"#,
+            rust_code
+        );
+
+        execute_rust_program(&program, "Console formatting");
+    }
+
+    #[test]
+    fn test_stdlib_array_sort_temporaries() {
+        let ts_code = r#"
+            function sortedKeys(m: Record<string, number>): string {
+                return Object.keys(m).sort().join(",");
+            }
+            function sortedCopy(xs: number[]): string {
+                const copy = xs.slice().sort();
+                return copy.join(",");
+            }
+            function mappedReversed(xs: number[]): string {
+                return xs.map((x) => x * 2).reverse().join("-");
+            }
+            function flags(on: boolean[]): string {
+                return on.join();
+            }
+        "#;
+
+        let temp_dir = std::env::temp_dir();
+        let ts_file = temp_dir.join("stdlib_array_sort_test.ts");
+        std::fs::write(&ts_file, ts_code).unwrap();
+
+        let rust_code =
+            ox_orchestrator::build(FilePath::from(ts_file)).expect("Failed to generate Rust code");
+
+        println!("Generated Rust code:\n{}", rust_code);
+
+        let program = format!(
+            r#"
+{}
+
+fn main() {{
+    let m = std::collections::HashMap::from([("b".to_string(), 1.0), ("a".to_string(), 2.0)]);
+    assert_eq!(sorted_keys(m), "a,b");
+    assert_eq!(sorted_copy(vec![10.0, 9.0, 1.5]), "1.5,10,9");
+    assert_eq!(mapped_reversed(vec![1.0, 2.0, 3.0]), "6-4-2");
+    assert_eq!(flags(vec![true, false]), "true,false");
+    println!("✅ Array sort test passed!");
+}}
+"#,
+            rust_code
+        );
+
+        execute_rust_program(&program, "Array sort");
+    }
+
+    #[test]
+    fn test_stdlib_path_execution() {
+        let ts_code = r#"
+            import * as path from "path";
+            function joined(dir: string, file: string): string {
+                return path.join(dir, "/nested/", "../" + file);
+            }
+            function resolved(dir: string): string {
+                return path.resolve("/ignored", dir, "./a/../b");
+            }
+            function normalized(p: string): string {
+                return path.normalize(p);
+            }
+        "#;
+
+        let temp_dir = std::env::temp_dir();
+        let ts_file = temp_dir.join("stdlib_path_test.ts");
+        std::fs::write(&ts_file, ts_code).unwrap();
+
+        let rust_code =
+            ox_orchestrator::build(FilePath::from(ts_file)).expect("Failed to generate Rust code");
+
+        println!("Generated Rust code:\n{}", rust_code);
+
+        let program = format!(
+            r#"
+{}
+
+fn main() {{
+    // An absolute later segment is appended, not a new root
+    assert_eq!(joined("/x/".to_string(), "y".to_string()), "/x/y");
+    assert_eq!(joined("x".to_string(), "/y".to_string()), "x/y");
+    assert_eq!(resolved("/srv/app".to_string()), "/srv/app/b");
+    assert_eq!(normalized("/a/b/../../../c/./d/".to_string()), "/c/d/");
+    assert_eq!(normalized("a/../../b".to_string()), "../b");
+    println!("✅ Path test passed!");
+}}
+"#,
+            rust_code
+        );
+
+        execute_rust_program(&program, "Path");
+    }
+
+    #[test]
+    fn test_stdlib_js_semantics() {
+        let ts_code = r#"
+            function describe(count: number, label: string, ids: number[]): string {
+                let out: string = "";
+                if (label) { out = out + "label;"; }
+                if (!ids.length) { out = out + "empty;"; }
+                if (label == count) { out = out + "loose;"; }
+                return out + count;
+            }
+            function sorted(ids: number[]): number[] {
+                const copy: number[] = ids;
+                copy.sort();
+                return copy;
+            }
+            function summary(label: string, ids: number[], ok: boolean, note?: string): string {
+                return label + ids.includes(3) + " " + String(ok) + " " + String(ids) + " " + note;
+            }
+            const API_URL = "https://api";
+            function pair(a: number, b: boolean): string {
+                return String(a) + String(b);
+            }
+            function endpoint(label: string, enabled: boolean): string {
+                return label + API_URL + String(enabled);
+            }
+            function frozen(ids: number[]): number {
+                const copy = Object.freeze(ids);
+                Object.freeze(ids);
+                return copy.length + ids.length;
+            }
+        "#;
+
+        let temp_dir = std::env::temp_dir();
+        let ts_file = temp_dir.join("stdlib_js_semantics_test.ts");
+        std::fs::write(&ts_file, ts_code).unwrap();
+
+        let rust_code =
+            ox_orchestrator::build(FilePath::from(ts_file)).expect("Failed to generate Rust code");
+
+        println!("Generated Rust code:\n{}", rust_code);
+
+        let program = format!(
+            r#"
+{}
+
+fn main() {{
+    assert_eq!(describe(5.0, "5".to_string(), vec![1.0]), "label;loose;5");
+    assert_eq!(describe(0.5, "".to_string(), vec![]), "empty;0.5");
+    assert_eq!(sorted(vec![10.0, 9.0, 1.0]), vec![1.0, 10.0, 9.0]);
+    assert_eq!(summary("L".to_string(), vec![1.0, 3.0], false, None), "Ltrue false 1,3 undefined");
+    assert_eq!(pair(1.5, true), "1.5true");
+    assert_eq!(endpoint("get ".to_string(), false), "get https://apifalse");
+    assert_eq!(frozen(vec![1.0, 2.0]), 4.0);
+    println!("✅ JS semantics test passed!");
+}}
+"#,
+            rust_code
+        );
+
+        execute_rust_program(&program, "JS semantics");
+    }
+
     fn execute_rust_program(program: &str, test_name: &str) {
         let temp_dir = TempDir::new().unwrap();
         let src_file = temp_dir.path().join("main.rs");
+        // Plain rustc has no dependencies, so the runtime crate is a module
+        let program = if program.contains("typerust_runtime::") {
+            let runtime = ox_codegen::runtime::FILES
+                .iter()
+                .find(|(file, _)| *file == "src/lib.rs")
+                .unwrap()
+                .1;
+            fs::write(temp_dir.path().join("typerust_runtime.rs"), runtime).unwrap();
+            format!("mod typerust_runtime;\n{}", program)
+        } else {
+            program.to_string()
+        };
         fs::write(&src_file, program).unwrap();
 
         // Compile